@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+/// Errors reported to the user, by the TUI and by headless runs.
+#[derive(Debug, Error)]
+pub enum RenameError {
+    #[error("File error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Could not read episode titles: {0}")]
+    Scrape(String),
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("{0} file(s) skipped because their new names collide")]
+    Collision(usize),
+}
+
+/// Engine errors are `anyhow` errors; I/O and HTTP failures keep their kind and
+/// anything else the engine rejects is reported as a configuration problem.
+impl From<anyhow::Error> for RenameError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<std::io::Error>() {
+            Ok(io_error) => return RenameError::Io(io_error),
+            Err(error) => error,
+        };
+        if let Some(http_error) = error.downcast_ref::<reqwest::Error>() {
+            return RenameError::Network(http_error.to_string());
+        }
+        RenameError::InvalidConfig(error.to_string())
+    }
+}
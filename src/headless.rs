@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+use crate::error::RenameError;
+use crate::paths::expand_path;
+use crate::rename_engine::{extract_season_from_directory, rename_plan_json, ConfigBuilder, ConflictStrategy, FileType, RenameEngine, RenameOutcome};
+use crate::report::{default_report_path, write_report, ProcessingStats, ProcessingStatus, ReportEntry};
+
+/// Settings for a non-interactive run, parsed from the command line.
+#[derive(Debug, Clone)]
+pub struct HeadlessOptions {
+    pub directory: PathBuf,
+    pub file_type: FileType,
+    /// Season such as `S02`. Detected from the directory name when omitted.
+    pub season: Option<String>,
+    pub imdb_id: Option<String>,
+    pub year: Option<String>,
+    pub dry_run: bool,
+    pub recursive: bool,
+    /// Move episodes into `Season NN` folders.
+    pub organize: bool,
+    /// Write `.nfo` metadata next to each renamed file.
+    pub nfo: bool,
+    /// Regex tried before the built-in episode patterns.
+    pub custom_pattern: Option<String>,
+    /// Transliterate titles to ASCII.
+    pub ascii: bool,
+    /// What to do when a target already exists on disk.
+    pub on_conflict: ConflictStrategy,
+    pub exclude_patterns: Vec<Regex>,
+    /// Write the rename plan here as JSON and stop without renaming.
+    pub plan_json: Option<PathBuf>,
+    /// Write a summary report here after the run; `--report` alone uses the target directory.
+    pub report: Option<PathBuf>,
+}
+
+impl HeadlessOptions {
+    /// Parses the flags following `--headless`:
+    /// `--dir <path> [--type tv|movie] [--season S02] [--imdb ttXXXX] [--year 2023] [--dry-run] [--recursive] [--organize] [--nfo] [--pattern <regex>] [--ascii] [--on-conflict skip|overwrite|suffix] [--plan-json <file>] [--report] [--report-path <file>]`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut directory = None;
+        let mut file_type = FileType::TvShow;
+        let mut season = None;
+        let mut imdb_id = None;
+        let mut year = None;
+        let mut dry_run = false;
+        let mut recursive = false;
+        let mut organize = false;
+        let mut nfo = false;
+        let mut custom_pattern = None;
+        let mut ascii = false;
+        let mut on_conflict = ConflictStrategy::default();
+        let mut plan_json = None;
+        let mut report = None;
+        let mut report_in_directory = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().cloned().ok_or_else(|| anyhow!("{} requires a value", arg));
+            match arg.as_str() {
+                "--headless" => {}
+                "--dir" => directory = Some(PathBuf::from(expand_path(&value()?))),
+                "--type" => {
+                    file_type = match value()?.to_lowercase().as_str() {
+                        "tv" => FileType::TvShow,
+                        "movie" => FileType::Movie,
+                        "auto" => FileType::Auto,
+                        other => return Err(anyhow!("Unknown --type '{}', expected tv, movie or auto", other)),
+                    }
+                }
+                "--season" => season = Some(value()?),
+                "--imdb" => imdb_id = Some(value()?),
+                "--year" => year = Some(value()?),
+                "--dry-run" => dry_run = true,
+                "--recursive" => recursive = true,
+                "--organize" => organize = true,
+                "--nfo" => nfo = true,
+                "--pattern" => custom_pattern = Some(value()?),
+                "--ascii" => ascii = true,
+                "--on-conflict" => {
+                    on_conflict = match value()?.to_lowercase().as_str() {
+                        "skip" => ConflictStrategy::Skip,
+                        "overwrite" => ConflictStrategy::Overwrite,
+                        "suffix" => ConflictStrategy::RenameWithSuffix,
+                        other => return Err(anyhow!("Unknown --on-conflict '{}', expected skip, overwrite or suffix", other)),
+                    }
+                }
+                "--plan-json" => plan_json = Some(PathBuf::from(value()?)),
+                "--report" => report_in_directory = true,
+                "--report-path" => report = Some(PathBuf::from(value()?)),
+                other => return Err(anyhow!("Unknown headless argument '{}'", other)),
+            }
+        }
+
+        let directory = directory.ok_or_else(|| anyhow!("--dir is required in headless mode"))?;
+        if !directory.is_dir() {
+            return Err(anyhow!("{} is not a directory", directory.display()));
+        }
+        if report.is_none() && report_in_directory {
+            report = Some(default_report_path(&directory));
+        }
+
+        Ok(Self {
+            directory,
+            file_type,
+            season,
+            imdb_id,
+            year,
+            dry_run,
+            recursive,
+            organize,
+            nfo,
+            custom_pattern,
+            ascii,
+            on_conflict,
+            exclude_patterns: Vec::new(),
+            plan_json,
+            report,
+        })
+    }
+}
+
+/// Outcome of a headless run.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HeadlessSummary {
+    pub renamed: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+}
+
+/// Scans the directory and renames every match, printing each rename and a final
+/// summary. Uses the same engine code paths as the TUI without touching the terminal.
+pub async fn run(options: HeadlessOptions) -> Result<HeadlessSummary> {
+    let config = ConfigBuilder::new()
+        .directory(&options.directory)
+        .file_type(options.file_type.clone())
+        .year(options.year.clone())
+        .imdb(options.imdb_id.clone())
+        .dry_run(options.dry_run)
+        .recursive(options.recursive)
+        .organize_into_folders(options.organize)
+        .write_nfo(options.nfo)
+        .transliterate(options.ascii)
+        .on_conflict(options.on_conflict)
+        .exclude_patterns(options.exclude_patterns.clone());
+
+    let config = if options.file_type.has_episodes() {
+        config
+            .season(options.season.clone().unwrap_or_else(|| detect_season(&options.directory)))
+            .custom_pattern(options.custom_pattern.clone().unwrap_or_default())
+    } else {
+        config
+    };
+
+    let mut engine = RenameEngine::new(config.build()?)?;
+    if let Some(message) = engine.fetch_imdb_titles().await? {
+        eprintln!("Warning: {}", RenameError::Scrape(message));
+    }
+
+    let plan = engine.scan_directory()?;
+    let mut summary = HeadlessSummary::default();
+
+    if let Some(path) = &options.plan_json {
+        std::fs::write(path, rename_plan_json(&plan)?)?;
+        summary.unchanged = plan.iter().filter(|file_rename| !file_rename.needs_rename).count();
+        println!("Wrote a plan for {} files to {}", plan.len(), path.display());
+        return Ok(summary);
+    }
+
+    let mut entries = Vec::with_capacity(plan.len());
+    for file_rename in plan {
+        let mut entry = ReportEntry {
+            original_name: file_rename.original_name.clone(),
+            new_name: file_rename.new_name.clone(),
+            status: ProcessingStatus::Skipped,
+            error_message: None,
+        };
+        if !file_rename.needs_rename {
+            summary.unchanged += 1;
+            entries.push(entry);
+            continue;
+        }
+
+        let result = engine.rename_file(&file_rename).await;
+        entry.error_message = result.error_message();
+        if let RenameOutcome::Skipped(_) = &result.outcome {
+            summary.unchanged += 1;
+            eprintln!("Skipped: {}: {}", file_rename.original_path.display(), result.error_message().unwrap_or_default());
+        } else if result.success() {
+            summary.renamed += 1;
+            entry.status = ProcessingStatus::Success;
+            let target = if options.organize {
+                engine.target_path(&file_rename).display().to_string()
+            } else {
+                file_rename.new_name.clone()
+            };
+            if result.cross_device {
+                println!("{} -> {} (copied across filesystems)", file_rename.original_path.display(), target);
+            } else {
+                println!("{} -> {}", file_rename.original_path.display(), target);
+            }
+        } else {
+            summary.failed += 1;
+            entry.status = ProcessingStatus::Error;
+            eprintln!(
+                "Failed: {}: {}",
+                file_rename.original_path.display(),
+                result.error_message().unwrap_or_default()
+            );
+        }
+        entries.push(entry);
+    }
+
+    let verb = if options.dry_run { "would be renamed" } else { "renamed" };
+    println!("{} {}, {} unchanged, {} failed", summary.renamed, verb, summary.unchanged, summary.failed);
+
+    if let Some(path) = &options.report {
+        let stats = ProcessingStats {
+            total: entries.len(),
+            processed: entries.len(),
+            successful: summary.renamed,
+            failed: summary.failed,
+            skipped: summary.unchanged,
+        };
+        write_report(path, &entries, &stats)?;
+        println!("Wrote a report to {}", path.display());
+    }
+    Ok(summary)
+}
+
+fn detect_season(directory: &Path) -> String {
+    let season = directory
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(extract_season_from_directory)
+        .unwrap_or(1);
+    format!("S{:02}", season)
+}
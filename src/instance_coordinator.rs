@@ -1,14 +1,81 @@
-use std::fs::{self, OpenOptions};
-use std::io::{Write, Read};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use fs2::FileExt;
+use tracing::{debug, info, warn};
+
+use crate::rename_engine::VIDEO_EXTENSIONS;
+
+// How often the coordinator checks the shared file list for new entries.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long the coordinator waits for other instances to submit their files.
+///
+/// Each window can be overridden in milliseconds through an environment variable,
+/// which helps when a slow disk or a large drag-and-drop starts instances late:
+///
+/// | Variable                         | Default | Range          |
+/// |----------------------------------|---------|----------------|
+/// | `JELLYFIN_RENAME_MAX_WAIT_MS`    | 30000   | 1000 - 300000  |
+/// | `JELLYFIN_RENAME_STABILITY_MS`   | 500     | 100 - 30000    |
+/// | `JELLYFIN_RENAME_INACTIVITY_MS`  | 3000    | 500 - 60000    |
+///
+/// Values outside the range are clamped; unparsable ones fall back to the default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinatorTiming {
+    /// Collection stops after this long no matter what.
+    pub max_wait: Duration,
+    /// Collection stops once the file count has not changed for this long.
+    pub stability: Duration,
+    /// Collection gives up after this long when no file has arrived at all.
+    pub inactivity: Duration,
+}
+
+impl Default for CoordinatorTiming {
+    fn default() -> Self {
+        Self {
+            max_wait: Duration::from_millis(30000),
+            stability: Duration::from_millis(500),
+            inactivity: Duration::from_millis(3000),
+        }
+    }
+}
+
+impl CoordinatorTiming {
+    /// Reads overrides from the process environment.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads overrides through `lookup`, which maps a variable name to its value.
+    pub fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let read = |name: &str, default: Duration, min: u64, max: u64| {
+            lookup(name)
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(|ms| Duration::from_millis(ms.clamp(min, max)))
+                .unwrap_or(default)
+        };
+
+        Self {
+            max_wait: read("JELLYFIN_RENAME_MAX_WAIT_MS", defaults.max_wait, 1000, 300_000),
+            stability: read("JELLYFIN_RENAME_STABILITY_MS", defaults.stability, 100, 30_000),
+            inactivity: read("JELLYFIN_RENAME_INACTIVITY_MS", defaults.inactivity, 500, 60_000),
+        }
+    }
+}
 
 /// Coordinates multiple instances of the application to process files together
 pub struct InstanceCoordinator {
-    temp_dir: std::path::PathBuf,
+    temp_dir: PathBuf,
     app_id: String,
     session_id: String,
+    extensions: Vec<String>,
+    timing: CoordinatorTiming,
 }
 
 impl InstanceCoordinator {
@@ -23,66 +90,136 @@ impl InstanceCoordinator {
             temp_dir: std::env::temp_dir(),
             app_id: "jellyfin_rename".to_string(),
             session_id,
+            extensions: VIDEO_EXTENSIONS.iter().map(|ext| ext.to_string()).collect(),
+            timing: CoordinatorTiming::from_env(),
+        }
+    }
+
+    /// Keeps the lock and the shared file list under `temp_dir` instead of the system
+    /// temp directory, so unrelated groups of instances don't meet.
+    pub fn with_temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// Replaces the collection windows read from the environment.
+    pub fn with_timing(mut self, timing: CoordinatorTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    pub fn timing(&self) -> CoordinatorTiming {
+        self.timing
+    }
+
+    /// Restricts collected files to the given extensions. An empty list accepts every file.
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions
+            .into_iter()
+            .map(|ext| ext.trim_start_matches('.').to_lowercase())
+            .collect();
+        self
+    }
+
+    /// Returns true if the path should enter the pipeline. Directories are always accepted.
+    pub fn accepts_path(&self, path: &str) -> bool {
+        let path = Path::new(path);
+        if self.extensions.is_empty() || path.is_dir() {
+            return true;
         }
+
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| self.extensions.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext)))
+            .unwrap_or(false)
+    }
+
+    pub fn filter_video_files(&self, paths: Vec<String>) -> Vec<String> {
+        paths.into_iter().filter(|path| self.accepts_path(path)).collect()
     }
 
+    /// Submits `initial_file` and, if no other instance is collecting, gathers the
+    /// files every instance submits. Returns `None` in instances that only submitted.
     pub fn collect_files_from_instances(&self, initial_file: &str) -> Option<Vec<String>> {
+        self.collect_paths_from_instances(&[initial_file.to_string()])
+    }
+
+    /// Like [`collect_files_from_instances`](Self::collect_files_from_instances), for an
+    /// instance started with several paths at once, as "Send to" does.
+    pub fn collect_paths_from_instances(&self, initial_paths: &[String]) -> Option<Vec<String>> {
         let base_path = self.temp_dir.join(&self.app_id);
-        
         let _ = fs::create_dir_all(&base_path);
-        
+
         let lock_file_path = base_path.join("coordinator.lock");
-        let files_dir = base_path.join("files");
-        let _ = fs::create_dir_all(&files_dir);
-        
-        self.add_file_to_collection(&files_dir, initial_file);
-        
-        match self.try_become_coordinator(&lock_file_path) {
-            Some(_) => {
-                self.handle_coordinator_instance(&files_dir, &lock_file_path)
+        let entries_path = base_path.join("files.txt");
+
+        self.add_files_to_collection(&entries_path, initial_paths);
+
+        // Losing the lock doesn't mean a coordinator will see our paths: the holder may
+        // be handing over, its list already moved aside before we appended to a fresh one.
+        // So keep trying until either a coordinator has taken them or the lock comes free.
+        let start_time = Instant::now();
+        loop {
+            if !self.is_waiting(&entries_path, initial_paths) {
+                debug!(session = %self.session_id, "a coordinator took our paths");
+                return None;
             }
-            None => {
-                None
+            if let Some(lock) = self.try_become_coordinator(&lock_file_path) {
+                // They may have been taken just before the lock came free
+                if !self.is_waiting(&entries_path, initial_paths) {
+                    return None;
+                }
+                return Some(self.handle_coordinator_instance(&entries_path, lock));
             }
+            // A coordinator takes the list within one collection window
+            if start_time.elapsed() > self.timing.max_wait {
+                warn!(paths = ?initial_paths, "no coordinator took our paths");
+                return None;
+            }
+            thread::sleep(POLL_INTERVAL);
         }
     }
 
-    fn try_become_coordinator(&self, lock_file_path: &std::path::Path) -> Option<()> {
-        match OpenOptions::new()
+    /// Whether any of `paths` is still in the shared list, i.e. no coordinator has taken it yet.
+    fn is_waiting(&self, entries_path: &Path, paths: &[String]) -> bool {
+        let Ok(contents) = fs::read_to_string(entries_path) else {
+            return false;
+        };
+        contents.lines().any(|line| paths.iter().any(|path| path == line.trim()))
+    }
+
+    /// Takes an advisory lock that the OS releases when the holder exits, so a
+    /// crashed coordinator never blocks the next one.
+    fn try_become_coordinator(&self, lock_file_path: &Path) -> Option<File> {
+        let mut lock_file = OpenOptions::new()
+            .read(true)
             .write(true)
-            .create_new(true)
-            .open(lock_file_path) 
-        {
-            Ok(mut lock_file) => {
+            .create(true)
+            .truncate(false)
+            .open(lock_file_path)
+            .ok()?;
+
+        match lock_file.try_lock_exclusive() {
+            Ok(()) => {
+                let _ = lock_file.set_len(0);
                 let _ = writeln!(lock_file, "{}:{}", self.session_id, std::process::id());
-                Some(())
+                debug!(session = %self.session_id, lock = %lock_file_path.display(), "acquired coordinator lock");
+                Some(lock_file)
             }
             Err(_) => {
-                if let Ok(mut file) = std::fs::File::open(lock_file_path) {
-                    let mut contents = String::new();
-                    if file.read_to_string(&mut contents).is_ok() {
-                        if let Some(first_line) = contents.lines().next() {
-                            if let Some((_session, pid_str)) = first_line.split_once(':') {
-                                if let Ok(pid) = pid_str.parse::<u32>() {
-                                    if !self.is_process_running(pid) {
-                                        let _ = fs::remove_file(lock_file_path);
-                                        return self.try_become_coordinator(lock_file_path);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+                debug!(session = %self.session_id, "another instance holds the coordinator lock");
                 None
             }
         }
     }
 
-    fn is_process_running(&self, pid: u32) -> bool {
+    /// Returns true if a process with this id exists.
+    #[cfg(windows)]
+    pub fn is_process_running(&self, pid: u32) -> bool {
         use std::process::Command;
         
         match Command::new("tasklist")
-            .args(&["/FI", &format!("PID eq {}", pid), "/FO", "CSV"])
+            .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV"])
             .output() 
         {
             Ok(output) => {
@@ -93,19 +230,70 @@ impl InstanceCoordinator {
         }
     }
 
-    fn add_file_to_collection(&self, files_dir: &std::path::Path, file_path: &str) {
-        let file_id = format!("{}.txt", self.session_id);
-        let file_entry_path = files_dir.join(&file_id);
-        
+    /// Returns true if a process with this id exists.
+    #[cfg(unix)]
+    pub fn is_process_running(&self, pid: u32) -> bool {
+        use nix::errno::Errno;
+        use nix::sys::signal::kill;
+        use nix::unistd::Pid;
+
+        let Ok(pid) = i32::try_from(pid) else {
+            return false;
+        };
+
+        // Signal 0 only checks for existence; EPERM means it exists but belongs to another user
+        match kill(Pid::from_raw(pid), None) {
+            Ok(()) => true,
+            Err(Errno::EPERM) => true,
+            Err(_) => false,
+        }
+    }
+
+    /// Replaces each directory in `paths` with the video files directly inside it,
+    /// sorted by name, and drops other files and duplicates. A mixed drop of
+    /// folders and files becomes one selection this way.
+    pub fn expand_directories(&self, paths: Vec<String>) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut files = Vec::new();
+
+        for path in paths {
+            let candidates = if Path::new(&path).is_dir() {
+                let mut entries: Vec<String> = fs::read_dir(&path)
+                    .map(|entries| entries
+                        .flatten()
+                        .map(|entry| entry.path().to_string_lossy().to_string())
+                        .collect())
+                    .unwrap_or_default();
+                entries.sort();
+                entries
+            } else {
+                vec![path]
+            };
+
+            for candidate in candidates {
+                if Path::new(&candidate).is_file() && self.accepts_path(&candidate) && seen.insert(candidate.clone()) {
+                    files.push(candidate);
+                }
+            }
+        }
+        files
+    }
+
+    /// Appends `file_paths` to the shared list as one write, which the OS keeps whole
+    /// even when several instances append at once.
+    fn add_files_to_collection(&self, entries_path: &Path, file_paths: &[String]) {
+        let entries: String = file_paths.iter().map(|path| format!("{}\n", path)).collect();
+
         for attempt in 0..10 {
             match OpenOptions::new()
                 .create(true)
                 .append(true)
-                .open(&file_entry_path) 
+                .open(entries_path)
             {
                 Ok(mut file) => {
-                    if writeln!(file, "{}", file_path).is_ok() {
-                        break;
+                    if file.write_all(entries.as_bytes()).is_ok() {
+                        debug!(files = ?file_paths, session = %self.session_id, "added files to collection");
+                        return;
                     }
                 }
                 Err(_) => {
@@ -113,76 +301,106 @@ impl InstanceCoordinator {
                 }
             }
         }
+        warn!(files = ?file_paths, "could not add files to collection");
     }
 
-    fn handle_coordinator_instance(
-        &self,
-        files_dir: &std::path::Path,
-        lock_file_path: &std::path::Path,
-    ) -> Option<Vec<String>> {
-        let mut collected_files = HashSet::new();
-        
+    /// Watches the shared list for one collection window, reading only what was
+    /// appended since the last check, until the file count holds still.
+    fn handle_coordinator_instance(&self, entries_path: &Path, lock: File) -> Vec<String> {
+        let mut collection = Collection::default();
+
         let start_time = Instant::now();
-        let absolute_max_wait_time = Duration::from_millis(30000);
         let mut last_file_count = 0;
         let mut stable_count = 0;
         let mut last_activity_time = Instant::now();
-        
-        let stability_threshold = 5; // 500ms of stability (5 * 100ms)
-        let max_inactivity_time = Duration::from_millis(3000);
-        
+
+        // Polls in a row without a change before the collection counts as complete
+        let stability_threshold = (self.timing.stability.as_millis() / POLL_INTERVAL.as_millis()).max(1);
+
         loop {
-            thread::sleep(Duration::from_millis(100));
-            
-            if start_time.elapsed() > absolute_max_wait_time {
+            thread::sleep(POLL_INTERVAL);
+
+            if start_time.elapsed() > self.timing.max_wait {
                 break;
             }
-            
-            if let Ok(entries) = fs::read_dir(files_dir) {
-                collected_files.clear();
-                
-                for entry in entries.flatten() {
-                    if let Ok(content) = fs::read_to_string(entry.path()) {
-                        for line in content.lines() {
-                            let line = line.trim();
-                            if !line.is_empty() && std::path::Path::new(line).exists() {
-                                collected_files.insert(line.to_string());
-                            }
-                        }
-                    }
-                }
-                
-                if collected_files.len() != last_file_count {
-                    if collected_files.len() > last_file_count {
-                        last_activity_time = Instant::now();
-                    }
-                    stable_count = 0;
-                    last_file_count = collected_files.len();
-                } else {
-                    stable_count += 1;
-                }
-                
-                let should_stop = if collected_files.is_empty() {
-                    last_activity_time.elapsed() > max_inactivity_time
-                } else {
-                    stable_count >= stability_threshold
-                };
-                
-                if should_stop {
-                    break;
+
+            self.read_new_entries(entries_path, &mut collection);
+
+            if collection.files.len() != last_file_count {
+                if collection.files.len() > last_file_count {
+                    last_activity_time = Instant::now();
                 }
+                stable_count = 0;
+                last_file_count = collection.files.len();
+            } else {
+                stable_count += 1;
+            }
+
+            let should_stop = if collection.files.is_empty() {
+                last_activity_time.elapsed() > self.timing.inactivity
+            } else {
+                stable_count >= stability_threshold
+            };
+
+            if should_stop {
+                break;
+            }
+        }
+
+        // Move the list aside before unlocking, so instances arriving from here on start
+        // a fresh list; they wait for this lock to come free and one of them coordinates it
+        let claimed_path = entries_path.with_file_name(format!("files_{}.txt", self.session_id));
+        let renamed = fs::rename(entries_path, &claimed_path).is_ok();
+        drop(lock);
+
+        if renamed {
+            // Entries appended between the last poll and the rename
+            self.read_new_entries(&claimed_path, &mut collection);
+            let _ = fs::remove_file(&claimed_path);
+        }
+
+        info!(files = collection.files.len(), elapsed_ms = start_time.elapsed().as_millis() as u64, "collected files from instances");
+
+        collection.files
+    }
+
+    fn read_new_entries(&self, path: &Path, collection: &mut Collection) {
+        let Ok(mut file) = File::open(path) else {
+            return;
+        };
+        if file.seek(SeekFrom::Start(collection.offset)).is_err() {
+            return;
+        }
+
+        let mut appended = Vec::new();
+        if file.read_to_end(&mut appended).is_err() {
+            return;
+        }
+
+        // A line still being written is picked up by the next read
+        let Some(complete) = appended.iter().rposition(|&byte| byte == b'\n').map(|end| end + 1) else {
+            return;
+        };
+        collection.offset += complete as u64;
+
+        for line in String::from_utf8_lossy(&appended[..complete]).lines() {
+            let line = line.trim();
+            if !line.is_empty() && Path::new(line).exists() && self.accepts_path(line) && collection.seen.insert(line.to_string()) {
+                collection.files.push(line.to_string());
             }
         }
-        
-        let _ = fs::remove_file(lock_file_path);
-        let _ = fs::remove_dir_all(files_dir);
-        
-        let final_files: Vec<String> = collected_files.into_iter().collect();
-        
-        Some(final_files)
     }
 }
 
+/// Files gathered so far, in the order they were submitted.
+#[derive(Default)]
+struct Collection {
+    files: Vec<String>,
+    seen: HashSet<String>,
+    /// Bytes of the shared list already read.
+    offset: u64,
+}
+
 impl Default for InstanceCoordinator {
     fn default() -> Self {
         Self::new()
@@ -1,3 +1,20 @@
+//! Renames TV episodes and movies into the layout Jellyfin expects.
+//!
+//! Library users should start from [`rename_engine::plan_directory`], which
+//! builds a list of [`rename_engine::FileRename`]s from a
+//! [`rename_engine::RenameConfig`] (see [`rename_engine::ConfigBuilder`]), and
+//! [`rename_engine::apply`], which carries them out. These two functions are
+//! the stable entry points; the `tui` and `headless` modules are front ends
+//! built on top of them and may change between releases.
+
+pub mod error;
+pub mod headless;
 pub mod rename_engine;
 pub mod instance_coordinator;
-
+pub mod logging;
+pub mod nfo;
+pub mod paths;
+pub mod report;
+pub mod settings;
+pub mod title_cache;
+pub mod tui;
\ No newline at end of file
@@ -0,0 +1,41 @@
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use tracing_subscriber::EnvFilter;
+
+/// Where logs go while the TUI owns the terminal.
+pub fn default_log_path() -> PathBuf {
+    std::env::temp_dir().join("jellyfin_rename").join("jellyfin_rename.log")
+}
+
+/// `RUST_LOG` wins when set; otherwise `debug` with `--verbose` and `warn` without.
+pub fn env_filter(verbose: bool) -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(if verbose { "debug" } else { "warn" }))
+}
+
+/// Logs to stderr, for runs that never enter the TUI.
+pub fn init_stderr(verbose: bool) -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(env_filter(verbose))
+        .with_writer(std::io::stderr)
+        .try_init()
+        .map_err(|e| anyhow!("Failed to initialise logging: {}", e))
+}
+
+/// Appends logs to `path` so the TUI's alternate screen is never written over.
+pub fn init_file(verbose: bool, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    tracing_subscriber::fmt()
+        .with_env_filter(env_filter(verbose))
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .try_init()
+        .map_err(|e| anyhow!("Failed to initialise logging: {}", e))
+}
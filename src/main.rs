@@ -1,52 +1,99 @@
 use std::env;
 use std::path::Path;
+use std::time::Duration;
 
-mod rename_engine;
-mod instance_coordinator;
-mod tui;
-
-use instance_coordinator::InstanceCoordinator;
+use jellyfin_rename::headless::{self, HeadlessOptions};
+use jellyfin_rename::instance_coordinator::InstanceCoordinator;
+use jellyfin_rename::logging;
+use jellyfin_rename::paths::expand_path;
+use jellyfin_rename::rename_engine::{compile_exclude_pattern, RenameOperation, RenameOutcome};
+use jellyfin_rename::tui;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    
-    let selected_files = if args.len() >= 2 {
-        let coordinator = InstanceCoordinator::new();
-        let collected_files = coordinator.collect_files_from_instances(&args[1]);
-        
-        if collected_files.is_none() {
-            return Ok(());
-        }
-        
-        let collected_files = collected_files.unwrap();
-        
-        if collected_files.len() > 1 {
-            collected_files
+    let mut args: Vec<String> = Vec::new();
+    let mut exclude_patterns = Vec::new();
+    let mut verbose = false;
+    let mut processing_delay = None;
+    let mut rename_to = None;
+
+    // Pull out repeatable `--exclude <pattern>` flags, validating each up front
+    let mut raw_args = env::args();
+    while let Some(arg) = raw_args.next() {
+        if arg == "--exclude" {
+            let pattern = raw_args.next().ok_or("--exclude requires a pattern")?;
+            exclude_patterns.push(compile_exclude_pattern(&pattern)?);
+        } else if arg == "--delay" {
+            let millis = raw_args.next().ok_or("--delay requires a value in milliseconds")?;
+            let millis: u64 = millis.parse().map_err(|_| format!("Invalid --delay value: {}", millis))?;
+            processing_delay = Some(Duration::from_millis(millis));
+        } else if arg == "--rename-to" {
+            rename_to = Some(raw_args.next().ok_or("--rename-to requires a new name")?);
+        } else if arg == "--verbose" || arg == "-v" {
+            verbose = true;
         } else {
-            let path = Path::new(&args[1]);
-            if path.is_file() {
-                vec![args[1].clone()]
-            } else {
-                vec![]
+            args.push(arg);
+        }
+    }
+
+    // Scripted runs never enter the TUI; writing a plan implies one
+    if args.iter().any(|arg| arg == "--headless" || arg == "--plan-json") {
+        logging::init_stderr(verbose)?;
+        let mut options = HeadlessOptions::parse(&args[1..])?;
+        options.exclude_patterns = exclude_patterns;
+        let summary = headless::run(options).await?;
+        if summary.failed > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // A single file with a name typed out is renamed straight away, without the TUI
+    if let Some(new_name) = rename_to {
+        logging::init_stderr(verbose)?;
+        let [_, file] = args.as_slice() else {
+            return Err("--rename-to takes exactly one file".into());
+        };
+        let mut operation = RenameOperation::new(expand_path(file));
+        operation.update_new_name(&new_name)?;
+        match operation.execute() {
+            RenameOutcome::Success(_) => println!("{} -> {}", operation.get_original_name(), operation.get_new_name()),
+            outcome => {
+                eprintln!("{}: {}", operation.get_original_name(), outcome.error_message().unwrap_or_default());
+                std::process::exit(1);
             }
         }
-    } else {
-        vec![]
-    };
+        return Ok(());
+    }
 
-    let directory_arg = if args.len() >= 2 {
-        let path = Path::new(&args[1]);
-        if path.is_dir() {
-            Some(args[1].clone())
-        } else {
-            None
+    // The TUI owns the terminal, so logs go to a file instead
+    if let Err(e) = logging::init_file(verbose, &logging::default_log_path()) {
+        eprintln!("Warning: {}", e);
+    }
+    
+    // A quoted `~/Media` or `%USERPROFILE%\Media` isn't expanded by the shell
+    for path in args.iter_mut().skip(1) {
+        *path = expand_path(path);
+    }
+
+    let (selected_files, directory_arg) = if args.len() >= 2 {
+        let coordinator = InstanceCoordinator::new();
+        let Some(collected) = coordinator.collect_paths_from_instances(&args[1..]) else {
+            // Another instance is collecting and takes our paths with it
+            return Ok(());
+        };
+
+        match collected.as_slice() {
+            // A lone folder is scanned by the TUI itself
+            [only] if Path::new(only).is_dir() => (vec![], Some(only.clone())),
+            // Folders dropped with other folders or files contribute their video files
+            _ => (coordinator.expand_directories(collected), None),
         }
     } else {
-        None
+        (vec![], None)
     };
 
-    tui::run_tui(directory_arg, selected_files).await?;
+    tui::run_tui(directory_arg, selected_files, exclude_patterns, processing_delay).await?;
 
     Ok(())
 }
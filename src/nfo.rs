@@ -0,0 +1,54 @@
+//! Minimal Kodi-style `.nfo` documents, which Jellyfin reads as local metadata.
+
+/// Escapes the five characters XML reserves in text content.
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn document(root: &str, fields: &[(&str, Option<String>)]) -> String {
+    let mut xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<{}>\n", root);
+    for (tag, value) in fields {
+        if let Some(value) = value {
+            xml.push_str(&format!("  <{0}>{1}</{0}>\n", tag, xml_escape(value)));
+        }
+    }
+    xml.push_str(&format!("</{}>\n", root));
+    xml
+}
+
+/// An `<episodedetails>` document for one episode.
+pub fn episode_nfo(title: &str, season: u32, episode: u32, year: Option<&str>) -> String {
+    document("episodedetails", &[
+        ("title", Some(title.to_string())),
+        ("season", Some(season.to_string())),
+        ("episode", Some(episode.to_string())),
+        ("year", year.map(str::to_string)),
+    ])
+}
+
+/// A `<movie>` document.
+pub fn movie_nfo(title: &str, year: Option<&str>) -> String {
+    document("movie", &[
+        ("title", Some(title.to_string())),
+        ("year", year.map(str::to_string)),
+    ])
+}
+
+/// The series-level `tvshow.nfo` document.
+pub fn tvshow_nfo(title: &str, year: Option<&str>) -> String {
+    document("tvshow", &[
+        ("title", Some(title.to_string())),
+        ("year", year.map(str::to_string)),
+    ])
+}
@@ -0,0 +1,54 @@
+//! Expansion of `~` and environment variables in paths typed by the user or
+//! passed on the command line, shared by the TUI and headless mode.
+
+/// Expands a leading `~` to the home directory and `$NAME`, `${NAME}` or
+/// `%NAME%` environment variables in a typed path. Variables that aren't set
+/// are left as typed.
+pub fn expand_path(input: &str) -> String {
+    let home = dirs::home_dir().map(|home| home.to_string_lossy().to_string());
+    expand_path_with(input, home.as_deref(), |name| std::env::var(name).ok())
+}
+
+/// [`expand_path`] with the home directory given and variables looked up through `lookup`.
+pub fn expand_path_with(input: &str, home: Option<&str>, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let input = input.trim();
+    let input = match (input.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => format!("{}{}", home, rest),
+        _ => input.to_string(),
+    };
+
+    let mut expanded = String::with_capacity(input.len());
+    let mut rest = input.as_str();
+    while let Some(start) = rest.find(['$', '%']) {
+        expanded.push_str(&rest[..start]);
+        let sigil = &rest[start..start + 1];
+        let after = &rest[start + 1..];
+        let (name, consumed) = match (sigil, after.strip_prefix('{')) {
+            ("$", Some(braced)) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            ("$", None) => {
+                let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+                (&after[..end], end)
+            }
+            // Windows style, `%NAME%`
+            _ => match after.find('%') {
+                Some(end) => (&after[..end], end + 1),
+                None => ("", 0),
+            },
+        };
+        match Some(name).filter(|name| !name.is_empty()).and_then(&lookup) {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = &after[consumed..];
+            }
+            None => {
+                expanded.push_str(sigil);
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
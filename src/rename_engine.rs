@@ -1,13 +1,167 @@
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::collections::HashSet;
 use anyhow::{Result, Context};
+use filetime::FileTime;
 use regex::Regex;
 use reqwest;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use unicode_normalization::UnicodeNormalization;
+use crate::error::RenameError;
+use crate::nfo;
+
+use crate::title_cache::{TitleCache, DEFAULT_CACHE_TTL};
+
+/// Sidecar files renamed along with their video, e.g. `Show.S01E01.en.srt`.
+pub const COMPANION_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "sub", "idx", "vtt", "nfo"];
+
+/// Suffixes download clients give files still being written, e.g. `Movie.mkv.part`.
+pub const INCOMPLETE_SUFFIXES: &[&str] = &["part", "partial", "crdownload", "download", "!qB", "!ut"];
+
+/// Files named like samples but at least this large (200 MiB) are treated as real videos.
+pub const SAMPLE_SIZE_LIMIT: u64 = 200 * 1024 * 1024;
+
+/// How many times episode-title requests are attempted before giving up.
+pub const DEFAULT_FETCH_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; each further retry doubles it.
+const RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Extensions recognized as video files.
+pub const VIDEO_EXTENSIONS: &[&str] = &["mkv", "mp4", "avi", "ts", "m4v", "mov", "wmv", "flv", "webm", "m2ts"];
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FileType {
     TvShow,
     Movie,
+    /// Decided per file: episodes where a TV pattern matches, movies otherwise.
+    Auto,
+}
+
+impl FileType {
+    /// Whether files of this type may be named as episodes, and so need a season.
+    pub fn has_episodes(&self) -> bool {
+        matches!(self, FileType::TvShow | FileType::Auto)
+    }
+}
+
+/// Where episode titles are fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TitleSource {
+    /// IMDb series ids, read from IMDb's episodes page, or through OMDb when that fails.
+    #[default]
+    Imdb,
+    /// TMDB series ids, looked up through the TMDB API. Needs an API key.
+    Tmdb,
+    /// IMDb series ids, looked up through OMDb with your own API key instead of
+    /// the shared demo key.
+    Omdb,
+}
+
+/// How colliding target names are told apart.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DisambiguationStyle {
+    /// `Title_2.mkv`, `Title_3.mkv`, ...
+    #[default]
+    Numeric,
+    /// The source resolution, e.g. `Title_1080p.mkv`. Falls back to numeric.
+    Resolution,
+    /// The source modification time in Unix seconds. Falls back to numeric.
+    Timestamp,
+}
+
+/// What [`RenameEngine::rename_file`] does when its target already exists on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConflictStrategy {
+    /// Leave the file alone and report it as [`RenameOutcome::Skipped`].
+    #[default]
+    Skip,
+    /// Replace the existing file, which is moved to the trash rather than deleted.
+    Overwrite,
+    /// Rename to the first free `Title (2).mkv`, `Title (3).mkv`, ...
+    RenameWithSuffix,
+}
+
+/// How a date with the year last (`05.11.2023`) is read. Year-first dates are
+/// always year, month, day.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DateOrder {
+    /// `DD.MM.YYYY`
+    #[default]
+    DayFirst,
+    /// `MM.DD.YYYY`
+    MonthFirst,
+}
+
+/// What to do with a punctuation character that is legal but not always wanted in titles.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum SymbolHandling {
+    #[default]
+    Keep,
+    Strip,
+    Replace(String),
+}
+
+impl SymbolHandling {
+    fn apply(&self, text: &str, symbols: &[char]) -> String {
+        match self {
+            SymbolHandling::Keep => text.to_string(),
+            SymbolHandling::Strip => text.replace(symbols, "").split_whitespace().collect::<Vec<_>>().join(" "),
+            SymbolHandling::Replace(replacement) => text.replace(symbols, replacement),
+        }
+    }
+}
+
+/// How titles parsed from filenames are capitalized. Fetched episode titles are
+/// always used as published.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Casing {
+    /// Upper-cases the first letter of every word: `the long night` becomes `The Long Night`.
+    #[default]
+    TitleCase,
+    /// Keeps the filename's own casing, for stylized titles like `iCarly`.
+    Preserve,
+    Lowercase,
+}
+
+impl Casing {
+    fn apply(&self, title: &str) -> String {
+        match self {
+            Casing::TitleCase => title
+                .split_whitespace()
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" "),
+            Casing::Preserve => title.to_string(),
+            Casing::Lowercase => title.to_lowercase(),
+        }
+    }
+}
+
+/// NFC-normalizes a title so composed and decomposed accents produce the same
+/// name, and with `transliterate` also reduces it to ASCII (`Café — Finalé`
+/// becomes `Cafe -- Finale`).
+pub fn normalize_unicode(title: &str, transliterate: bool) -> String {
+    let composed: String = title.nfc().collect();
+    if transliterate {
+        deunicode::deunicode(&composed)
+    } else {
+        composed
+    }
+}
+
+/// Applies apostrophe and ampersand handling to a title.
+pub fn normalize_title_symbols(title: &str, apostrophes: &SymbolHandling, ampersands: &SymbolHandling) -> String {
+    let title = apostrophes.apply(title, &['\'', '\u{2019}']);
+    ampersands.apply(&title, &['&'])
 }
 
 #[derive(Debug, Clone)]
@@ -19,9 +173,168 @@ pub struct RenameConfig {
     pub use_imdb: bool,
     pub imdb_id: Option<String>,
     pub file_type: FileType,
+    pub pad_part_numbers: bool,
+    pub disambiguation: DisambiguationStyle,
+    pub on_conflict: ConflictStrategy,
+    pub exclude_patterns: Vec<Regex>,
+    pub apostrophes: SymbolHandling,
+    pub ampersands: SymbolHandling,
+    pub casing: Casing,
+    /// Reduce titles to ASCII after NFC normalization, e.g. `Café` to `Cafe`.
+    pub transliterate: bool,
+    /// Lowercase the extension in new names, so `.MKV` becomes `.mkv`.
+    pub normalize_extension_case: bool,
+    pub dry_run: bool,
+    /// Output naming template. `None` keeps the built-in formats.
+    pub template: Option<String>,
+    /// Extensions (without the dot) the filename patterns accept.
+    pub extensions: Vec<String>,
+    /// Also scan subdirectories of `directory`.
+    pub recursive: bool,
+    /// Sidecar extensions (without the dot) that follow their video's rename.
+    pub companion_extensions: Vec<String>,
+    /// Suffixes (without the dot) of unfinished downloads, which scanning leaves alone.
+    pub incomplete_suffixes: Vec<String>,
+    /// Leave out zero-byte files, typically placeholders for downloads not yet started.
+    pub skip_empty_files: bool,
+    /// Leave out sample clips; see [`RenameEngine::is_sample`].
+    pub skip_samples: bool,
+    /// Files at least this many bytes are never treated as samples.
+    pub sample_size_limit: u64,
+    /// Leave out dotfiles, and dot-directories when scanning recursively.
+    pub skip_hidden_files: bool,
+    pub title_source: TitleSource,
+    pub tmdb_api_key: Option<String>,
+    pub omdb_api_key: Option<String>,
+    /// Skips the on-disk title cache for both reads and writes.
+    pub no_cache: bool,
+    pub cache_ttl: std::time::Duration,
+    pub cache_path: PathBuf,
+    /// Attempts per episode-title request, including the first.
+    pub fetch_attempts: u32,
+    /// Match bare episode numbers such as `Show - 135.mkv`, as used for anime.
+    pub absolute_numbering: bool,
+    /// Episode counts per season used to map absolute numbers onto seasons.
+    /// Empty treats every absolute number as an episode of season 1.
+    pub episodes_per_season: Vec<u32>,
+    /// Match air dates such as `Show.2023.11.05.mkv`, as used for daily shows.
+    pub date_based: bool,
+    pub date_order: DateOrder,
+    /// Re-apply the source's access and modification times after a rename,
+    /// for moves that reset them (cross-device copies, some network shares).
+    pub preserve_timestamps: bool,
+    /// Longest filename, in bytes, a rename may produce. Titles are shortened to fit.
+    pub max_filename_bytes: usize,
+    /// Series name put in front of episode names. `None` leaves it out.
+    pub show_name: Option<String>,
+    /// Move episodes into `Season NN` subfolders of their directory, creating them as needed.
+    pub organize_into_folders: bool,
+    /// Write `.nfo` metadata next to each renamed file, plus `tvshow.nfo` for series.
+    pub write_nfo: bool,
+    /// User regex tried before the built-in episode patterns; see [`compile_custom_pattern`].
+    pub custom_pattern: Option<String>,
+    /// Joins words and name parts in the built-in formats, e.g. `_` in `The_Matrix_(1999).mkv`.
+    pub separator: char,
 }
 
-#[derive(Debug, Clone)]
+/// Default for [`RenameConfig::max_filename_bytes`], under the 255-byte limit of ext4 and NTFS.
+pub const DEFAULT_MAX_FILENAME_BYTES: usize = 250;
+
+/// Values substituted into a naming template.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues<'a> {
+    pub show: &'a str,
+    pub title: &'a str,
+    pub season: u32,
+    pub episode: u32,
+    /// Last episode of a multi-episode file.
+    pub episode_end: Option<u32>,
+    pub year: Option<&'a str>,
+    pub part: &'a str,
+    pub ext: &'a str,
+}
+
+/// Placeholders accepted in naming templates.
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &["title", "show", "season", "episode", "year", "part", "ext"];
+
+/// Checks that a template only uses known placeholders and includes `{ext}`.
+pub fn validate_template(template: &str) -> Result<()> {
+    let re = Regex::new(r"\{([^{}]*)\}").unwrap();
+    for captures in re.captures_iter(template) {
+        let name = &captures[1];
+        if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+            return Err(anyhow::anyhow!(
+                "Unknown placeholder {{{}}} in template (expected one of: {})",
+                name,
+                TEMPLATE_PLACEHOLDERS.join(", ")
+            ));
+        }
+    }
+    if !template.contains("{ext}") {
+        return Err(anyhow::anyhow!("Template must include {{ext}} so renamed files keep their extension"));
+    }
+    Ok(())
+}
+
+/// Named groups a custom episode pattern must define.
+pub const CUSTOM_PATTERN_GROUPS: &[&str] = &["title", "season", "episode", "extension"];
+
+/// Compiles a user-supplied episode pattern, checking that it defines every
+/// group in [`CUSTOM_PATTERN_GROUPS`].
+pub fn compile_custom_pattern(pattern: &str) -> Result<Regex> {
+    let regex = Regex::new(pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid custom pattern: {}", e))?;
+    let missing: Vec<&str> = CUSTOM_PATTERN_GROUPS
+        .iter()
+        .copied()
+        .filter(|group| !regex.capture_names().flatten().any(|name| name == *group))
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Custom pattern is missing the named group(s): {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(regex)
+}
+
+/// Expands a validated template. `{season}` and `{episode}` are zero-padded to two digits.
+pub fn expand_template(template: &str, values: &TemplateValues) -> String {
+    let expanded = drop_empty_placeholders(template, values)
+        .replace("{show}", values.show)
+        .replace("{title}", values.title)
+        .replace("{season}", &format!("{:02}", values.season))
+        .replace("{episode}", &match values.episode_end {
+            Some(end) => format!("{:02}-E{:02}", values.episode, end),
+            None => format!("{:02}", values.episode),
+        })
+        .replace("{year}", values.year.unwrap_or(""))
+        .replace("{part}", values.part)
+        .replace("{ext}", values.ext.trim_start_matches('.'));
+
+    match expanded.rsplit_once('.') {
+        Some((stem, extension)) => assemble_filename(stem, extension),
+        None => expanded,
+    }
+}
+
+/// Removes `{show}`, `{year}` and `{part}` when they have no value, along with the
+/// brackets around them and the separator before them, so a movie without a year
+/// comes out as `Film.mkv` rather than `Film ().mkv`.
+fn drop_empty_placeholders(template: &str, values: &TemplateValues) -> String {
+    let mut template = template.to_string();
+    let optional = [("show", values.show), ("year", values.year.unwrap_or("")), ("part", values.part)];
+    for (name, value) in optional {
+        if value.is_empty() {
+            let token = Regex::new(&format!(r"[\s._-]*(?:\(\{{{name}\}}\)|\[\{{{name}\}}\]|\{{{name}\}})")).unwrap();
+            template = token.replace_all(&template, "").to_string();
+        }
+    }
+    // A leading `{show} - ` leaves its separator behind
+    template.trim_start_matches([' ', '.', '_', '-']).to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileRename {
     pub original_path: PathBuf,
     pub original_name: String,
@@ -30,39 +343,281 @@ pub struct FileRename {
     pub season_number: u32,
     pub episode_title: String,
     pub needs_rename: bool,
+    pub match_kind: MatchKind,
+}
+
+/// Which filename pattern a proposed rename came from, so a wrong name can be traced
+/// back to the regex behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// The user's own regex; see [`compile_custom_pattern`].
+    Custom,
+    /// `S01E01`, or `Season 1 Episode 1` spelled out.
+    Standard,
+    /// `1x01`.
+    Flexible,
+    /// An air date such as `2023.11.05`.
+    Date,
+    /// A bare episode number such as `Show - 135`.
+    Absolute,
+    Movie,
+    /// Nothing matched, so the file keeps its name.
+    Unmatched,
+}
+
+impl MatchKind {
+    pub fn description(self) -> &'static str {
+        match self {
+            MatchKind::Custom => "custom pattern",
+            MatchKind::Standard => "standard pattern (S01E01)",
+            MatchKind::Flexible => "flexible pattern (1x01)",
+            MatchKind::Date => "air date pattern",
+            MatchKind::Absolute => "absolute numbering pattern",
+            MatchKind::Movie => "movie pattern",
+            MatchKind::Unmatched => "no pattern",
+        }
+    }
+}
+
+/// What a file operation did to the source file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperationKind {
+    /// Renamed within its own directory.
+    Rename,
+    /// Moved to a different directory.
+    Move,
+}
+
+/// How a rename ended: where the file went, or why it stayed put.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameOutcome {
+    /// The file is now at this path, or would be after a dry run.
+    Success(PathBuf),
+    /// The file was left alone because this target already exists; see [`ConflictStrategy::Skip`].
+    Skipped(PathBuf),
+    /// Something already occupies this target path.
+    AlreadyExists(PathBuf),
+    NoPermission(String),
+    /// The source file disappeared before it could be renamed.
+    SourceNotFound(PathBuf),
+    OtherError(String),
+}
+
+impl RenameOutcome {
+    /// Classifies a failed rename of `source` to `target`.
+    pub fn from_io_error(error: &std::io::Error, source: &Path, target: &Path) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound if !source.exists() => RenameOutcome::SourceNotFound(source.to_path_buf()),
+            std::io::ErrorKind::AlreadyExists => RenameOutcome::AlreadyExists(target.to_path_buf()),
+            std::io::ErrorKind::PermissionDenied => RenameOutcome::NoPermission(error.to_string()),
+            _ => RenameOutcome::OtherError(error.to_string()),
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, RenameOutcome::Success(_))
+    }
+
+    /// Where the file ended up. `None` if the operation failed.
+    pub fn final_path(&self) -> Option<&Path> {
+        match self {
+            RenameOutcome::Success(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// A message describing the failure, or `None` on success.
+    pub fn error_message(&self) -> Option<String> {
+        match self {
+            RenameOutcome::Success(_) => None,
+            RenameOutcome::Skipped(path) => Some(format!("{} already exists, skipped", path.display())),
+            RenameOutcome::AlreadyExists(path) => Some(format!("{} already exists", path.display())),
+            RenameOutcome::NoPermission(message) => Some(format!("Permission denied: {}", message)),
+            RenameOutcome::SourceNotFound(path) => Some(format!("{} no longer exists", path.display())),
+            RenameOutcome::OtherError(message) => Some(message.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RenameResult {
-    pub success: bool,
-    pub error_message: Option<String>,
+    pub outcome: RenameOutcome,
+    pub operation: OperationKind,
+    /// Sidecar files that were renamed with the video, as `(original, final)` paths.
+    pub companions: Vec<(PathBuf, PathBuf)>,
+    /// The target was on another filesystem, so the file was copied and the source removed.
+    pub cross_device: bool,
+    /// Directories created to hold the file, outermost first, so undo can remove them.
+    pub created_dirs: Vec<PathBuf>,
+    /// Metadata files written alongside the rename, which undo deletes.
+    pub created_files: Vec<PathBuf>,
+    /// Existing files at the video's or a sidecar's target that were moved to the
+    /// trash to make room, by their original paths, so undo can restore them.
+    pub trashed: Vec<PathBuf>,
+}
+
+impl RenameResult {
+    /// Whether the rename went through; see [`RenameOutcome`] for why it didn't.
+    pub fn success(&self) -> bool {
+        self.outcome.is_success()
+    }
+
+    pub fn final_path(&self) -> Option<&Path> {
+        self.outcome.final_path()
+    }
+
+    pub fn error_message(&self) -> Option<String> {
+        self.outcome.error_message()
+    }
+}
+
+/// A one-off rename of a single file within its own directory, for when a name is
+/// typed by hand rather than worked out from a pattern. The extension is kept.
+#[derive(Debug, Clone)]
+pub struct RenameOperation {
+    original_path: PathBuf,
+    new_name: String,
+}
+
+impl RenameOperation {
+    /// Starts from the file's current name, so executing it unchanged is a no-op.
+    pub fn new(original_path: impl Into<PathBuf>) -> Self {
+        let original_path = original_path.into();
+        let new_name = original_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        Self { original_path, new_name }
+    }
+
+    pub fn get_original_path(&self) -> &Path {
+        &self.original_path
+    }
+
+    pub fn get_original_name(&self) -> String {
+        self.original_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    /// The original extension, without the dot; empty if the file has none.
+    pub fn get_extension(&self) -> String {
+        self.original_path.extension().map(|ext| ext.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    pub fn get_new_name(&self) -> &str {
+        &self.new_name
+    }
+
+    /// Sets the new name from a stem; the original extension is appended and
+    /// characters Windows rejects are replaced. A stem that is empty once trimmed
+    /// is refused, since it would leave a hidden `.mkv`, and the name is unchanged.
+    pub fn update_new_name(&mut self, stem: &str) -> Result<(), RenameError> {
+        let stem = sanitize_filename(stem.trim());
+        let stem = stem.trim_end_matches(['.', ' ']);
+        if stem.is_empty() {
+            return Err(RenameError::InvalidConfig("The new name is empty".to_string()));
+        }
+        let extension = self.get_extension();
+        self.new_name = if extension.is_empty() {
+            stem.to_string()
+        } else {
+            assemble_filename(stem, &extension)
+        };
+        Ok(())
+    }
+
+    /// Renames the file, refusing to replace anything already at the new name.
+    pub fn execute(&self) -> RenameOutcome {
+        let target = self.original_path.with_file_name(&self.new_name);
+        if self.new_name.is_empty() {
+            return RenameOutcome::OtherError("The new name is empty".to_string());
+        }
+        if !self.original_path.exists() {
+            return RenameOutcome::SourceNotFound(self.original_path.clone());
+        }
+        if target == self.original_path {
+            return RenameOutcome::Success(target);
+        }
+        if target.exists() && !is_same_file(&self.original_path, &target) {
+            return RenameOutcome::AlreadyExists(target);
+        }
+
+        match move_file(&self.original_path, &target) {
+            Ok(_) => {
+                info!(from = %self.original_path.display(), to = %target.display(), "renamed");
+                RenameOutcome::Success(target)
+            }
+            Err(e) => RenameOutcome::from_io_error(&e, &self.original_path, &target),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct RenameEngine {
     pub config: RenameConfig,
     imdb_titles: Vec<String>,
+    /// Set while `imdb_titles` may not belong to the configured series and season.
+    titles_stale: bool,
+    custom_pattern: Option<Regex>,
     standard_pattern: Regex,
+    /// `Season 2 Episode 3` spelled out, with the same groups as `standard_pattern`.
+    worded_pattern: Regex,
     flexible_pattern: Regex,
     movie_pattern: Regex,
+    part_pattern: Regex,
+    absolute_pattern: Regex,
+    date_pattern: Regex,
 }
 
 impl RenameEngine {
     pub fn new(config: RenameConfig) -> Result<Self> {
-        let standard_pattern = Regex::new(
-            r"(?i)(?P<title>.*?)S(?P<season>\d{1,2})E(?P<episode>\d{2})(?P<suffix>.*)\.(?P<extension>mkv|mp4|avi|ts)$"
-        )?;
-          let flexible_pattern = Regex::new(
-            r"(?i)(?P<title>.*?)\b(?P<season>\d{1,2})x(?P<episode>\d{2})\b(?P<suffix>.*)\.(?P<extension>mkv|mp4|avi|ts)$"
-        )?;        let movie_pattern = Regex::new(
-            r"(?i)^(?:Watch\s+)?(?P<title>.*?)(?:\.(?P<year>\d{4}))?(?:\.(?P<quality>.*?))?\.(?P<extension>mkv|mp4|avi|ts)$"
+        let extensions = config.extensions
+            .iter()
+            .map(|ext| regex::escape(ext))
+            .collect::<Vec<_>>()
+            .join("|");
+
+        let standard_pattern = Regex::new(&format!(
+            r"(?i)(?P<title>.*?)S(?P<season>\d{{1,2}})E(?P<episode>\d{{1,3}})(?:-?E(?P<episode_end>\d{{1,3}}))?(?P<suffix>.*)\.(?P<extension>{})$",
+            extensions
+        ))?;
+        let worded_pattern = Regex::new(&format!(
+            r"(?i)(?P<title>.*?)(?:^|[\s._-])Season[\s._-]*(?P<season>\d{{1,2}})[\s._-]*Episode[\s._-]*(?P<episode>\d{{1,3}})(?P<suffix>(?:\D.*)?)\.(?P<extension>{})$",
+            extensions
+        ))?;
+        let flexible_pattern = Regex::new(&format!(
+            r"(?i)(?P<title>.*?)\b(?P<season>\d{{1,2}})x(?P<episode>\d{{1,3}})\b(?P<suffix>.*)\.(?P<extension>{})$",
+            extensions
+        ))?;
+        let movie_pattern = Regex::new(&format!(
+            r"(?i)^(?:Watch\s+)?(?P<title>.*?)(?:\.(?P<year>\d{{4}}))?(?:\.(?P<quality>.*?))?\.(?P<extension>{})$",
+            extensions
+        ))?;
+        let part_pattern = Regex::new(
+            r"(?i)(?:^|[\s._-])(?:cd|part|disc|disk)[\s._-]?(?P<part>\d{1,2})(?:[\s._-]|$)"
         )?;
+        let absolute_pattern = Regex::new(&format!(
+            r"(?i)^(?P<title>.+?)\s+-\s+(?P<episode>\d{{1,4}})(?:v\d+)?(?P<suffix>(?:\s.*)?)\.(?P<extension>{})$",
+            extensions
+        ))?;
+        let date_pattern = Regex::new(&format!(
+            r"(?i)^(?P<title>.*?)[\s._-]*\b(?:(?P<year_first>\d{{4}})[.-](?P<month>\d{{2}})[.-](?P<day>\d{{2}})|(?P<first>\d{{2}})[.-](?P<second>\d{{2}})[.-](?P<year_last>\d{{4}}))\b(?P<suffix>.*)\.(?P<extension>{})$",
+            extensions
+        ))?;
+
+        let custom_pattern = config.custom_pattern
+            .as_deref()
+            .map(compile_custom_pattern)
+            .transpose()?;
 
         Ok(Self {
             imdb_titles: Vec::new(),
+            titles_stale: true,
+            custom_pattern,
             standard_pattern,
+            worded_pattern,
             flexible_pattern,
             movie_pattern,
+            part_pattern,
+            absolute_pattern,
+            date_pattern,
             config,
         })
     }    pub async fn fetch_imdb_titles(&mut self) -> Result<Option<String>> {
@@ -72,30 +627,193 @@ impl RenameEngine {
 
         let imdb_id = match self.config.imdb_id.as_ref() {
             Some(id) => id.clone(),
-            None => return Ok(Some("IMDb ID is required when use_imdb is true".to_string())),
+            None => return Ok(Some("A series ID is required when use_imdb is true".to_string())),
+        };
+
+        match self.config.title_source {
+            TitleSource::Imdb => {
+                let provider = ImdbProvider::new(imdb_id).with_attempts(self.config.fetch_attempts);
+                self.fetch_titles_from(&provider).await
+            }
+            TitleSource::Tmdb => {
+                let Some(api_key) = self.config.tmdb_api_key.clone() else {
+                    return Ok(Some("A TMDB API key is required for TMDB titles".to_string()));
+                };
+                let provider = TmdbProvider::new(api_key, imdb_id).with_attempts(self.config.fetch_attempts);
+                self.fetch_titles_from(&provider).await
+            }
+            TitleSource::Omdb => {
+                let Some(api_key) = self.config.omdb_api_key.clone() else {
+                    return Ok(Some("An OMDb API key is required for OMDb titles".to_string()));
+                };
+                let provider = OmdbProvider::new(api_key, imdb_id).with_attempts(self.config.fetch_attempts);
+                self.fetch_titles_from(&provider).await
+            }
+        }
+    }
+
+    /// Fetches this season's titles from `provider`, reading and filling the title
+    /// cache under the configured source and series ID unless `no_cache` is set.
+    /// Like [`Self::fetch_imdb_titles`], a failed fetch comes back as a message.
+    pub async fn fetch_titles_from<P: EpisodeTitleProvider>(&mut self, provider: &P) -> Result<Option<String>> {
+        let imdb_id = self.config.imdb_id.clone().unwrap_or_default();
+        let season_num = self.config.season_num;
+        let source = match self.config.title_source {
+            TitleSource::Imdb => "imdb",
+            TitleSource::Tmdb => "tmdb",
+            TitleSource::Omdb => "omdb",
         };
+        let cache = TitleCache::new(&self.config.cache_path, self.config.cache_ttl);
+
+        if !self.config.no_cache {
+            if let Some(titles) = cache.load(source, &imdb_id, season_num) {
+                debug!(source, id = %imdb_id, season = season_num, titles = titles.len(), "episode titles served from cache");
+                self.imdb_titles = titles;
+                return Ok(None);
+            }
+        }
 
-        match scrape_imdb_episodes(&imdb_id, Some(self.config.season_num)).await {
+        match provider.titles(season_num).await {
             Ok(titles) if !titles.is_empty() => {
+                info!(source, id = %imdb_id, season = season_num, titles = titles.len(), "fetched episode titles");
+                if !self.config.no_cache {
+                    // A cache that can't be written just means the next run fetches again
+                    let _ = cache.store(source, &imdb_id, season_num, &titles);
+                }
                 self.imdb_titles = titles;
                 Ok(None)
             }
-            Ok(_) => Ok(Some("OMDb returned no episodes for this title/season".to_string())),
-            Err(e) => Ok(Some(format!("Failed to fetch episode titles: {}", e))),
+            Ok(_) => {
+                warn!(source, id = %imdb_id, season = season_num, "no episode titles returned");
+                Ok(Some("No episodes were returned for this title/season".to_string()))
+            }
+            Err(e) => {
+                warn!(source, id = %imdb_id, season = season_num, error = %e, "failed to fetch episode titles");
+                Ok(Some(e.to_string()))
+            }
         }
     }
     
     #[allow(dead_code)]
     pub fn get_imdb_titles(&self) -> &Vec<String> {
         &self.imdb_titles
-    }    pub fn scan_directory(&self) -> Result<Vec<FileRename>> {
+    }
+
+    /// Uses titles from another source, e.g. an [`EpisodeTitleProvider`].
+    pub fn set_imdb_titles(&mut self, titles: Vec<String>) {
+        self.imdb_titles = titles;
+        self.titles_stale = false;
+    }
+
+    /// Swaps in a new configuration. Fetched titles are kept when they still apply,
+    /// and otherwise dropped so [`Self::fetch_titles_if_needed`] fetches again.
+    pub fn reconfigure(&mut self, config: RenameConfig) -> Result<()> {
+        let mut rebuilt = RenameEngine::new(config)?;
+        if rebuilt.fetches_same_titles_as(self) {
+            rebuilt.imdb_titles = std::mem::take(&mut self.imdb_titles);
+            rebuilt.titles_stale = self.titles_stale;
+        }
+        *self = rebuilt;
+        Ok(())
+    }
+
+    /// Whether the next [`Self::fetch_titles_if_needed`] would go to the title source.
+    pub fn needs_title_fetch(&self) -> bool {
+        self.titles_stale
+    }
+
+    /// Fetches titles unless the ones held already match the configuration. A failed
+    /// fetch leaves them stale so the next call tries again.
+    pub async fn fetch_titles_if_needed(&mut self) -> Result<Option<String>> {
+        if !self.titles_stale {
+            return Ok(None);
+        }
+        let message = self.fetch_imdb_titles().await?;
+        self.titles_stale = self.config.use_imdb && self.imdb_titles.is_empty();
+        Ok(message)
+    }
+
+    /// Whether [`Self::fetch_imdb_titles`] would ask `other`'s source for the same series and season.
+    pub fn fetches_same_titles_as(&self, other: &RenameEngine) -> bool {
+        let (ours, theirs) = (&self.config, &other.config);
+        ours.use_imdb == theirs.use_imdb
+            && ours.imdb_id == theirs.imdb_id
+            && ours.season_num == theirs.season_num
+            && ours.title_source == theirs.title_source
+            && ours.tmdb_api_key == theirs.tmdb_api_key
+            && ours.omdb_api_key == theirs.omdb_api_key
+    }
+
+    pub fn scan_directory(&self) -> Result<Vec<FileRename>> {
         if !self.config.directory.exists() {
             return Err(anyhow::anyhow!("Directory does not exist: {:?}", self.config.directory));
-        }        let files: Vec<_> = fs::read_dir(&self.config.directory)?
+        }
+
+        let mut proposed_renames = self.scan_single_directory()?;
+
+        if self.config.recursive {
+            for directory in collect_subdirectories(&self.config.directory, self.config.skip_hidden_files)? {
+                proposed_renames.extend(self.for_subdirectory(&directory).scan_single_directory()?);
+            }
+        }
+
+        self.disambiguate_targets(&mut proposed_renames);
+
+        Ok(proposed_renames)
+    }
+
+    /// A copy of this engine scoped to a nested directory. For TV shows the season
+    /// comes from that directory's own name (e.g. `Season 02`) when it has one.
+    fn for_subdirectory(&self, directory: &Path) -> RenameEngine {
+        let mut config = self.config.clone();
+        config.directory = directory.to_path_buf();
+
+        if config.file_type.has_episodes() {
+            let detected_season = directory.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(extract_season_from_directory);
+            if let Some(season_num) = detected_season {
+                config.season_num = season_num;
+                config.season = format!("S{:02}", season_num);
+            }
+        }
+
+        self.with_config(config)
+    }
+
+    fn with_config(&self, config: RenameConfig) -> RenameEngine {
+        RenameEngine {
+            config,
+            imdb_titles: self.imdb_titles.clone(),
+            titles_stale: self.titles_stale,
+            custom_pattern: self.custom_pattern.clone(),
+            standard_pattern: self.standard_pattern.clone(),
+            worded_pattern: self.worded_pattern.clone(),
+            flexible_pattern: self.flexible_pattern.clone(),
+            movie_pattern: self.movie_pattern.clone(),
+            part_pattern: self.part_pattern.clone(),
+            absolute_pattern: self.absolute_pattern.clone(),
+            date_pattern: self.date_pattern.clone(),
+        }
+    }
+
+    fn scan_single_directory(&self) -> Result<Vec<FileRename>> {
+        let files: Vec<_> = fs::read_dir(&self.config.directory)?
             .filter_map(|entry| entry.ok())
             .filter(|entry| entry.file_type().map(|ft| ft.is_file()).unwrap_or(false))
-            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter_map(|entry| {
+                let filename = entry.file_name().to_string_lossy().to_string();
+                let size = entry.metadata().map(|metadata| metadata.len()).ok();
+                match self.skip_reason(&filename, size) {
+                    Some(reason) => {
+                        debug!(file = %filename, "skipped: {}", reason);
+                        None
+                    }
+                    None => Some(filename),
+                }
+            })
             .collect();
+        debug!(directory = %self.config.directory.display(), files = files.len(), "scanning directory");
 
         let mut proposed_renames = Vec::new();
         
@@ -104,21 +822,38 @@ impl RenameEngine {
                 let mut files_for_flexible = Vec::new();
                 
                 for filename in &files {
-                    if let Some(rename) = self.process_file_standard(filename)? {
+                    let rename = match self.process_file_custom(filename)? {
+                        Some(rename) => Some(rename),
+                        None => self.process_file_standard(filename)?,
+                    };
+                    if let Some(rename) = rename {
                         proposed_renames.push(rename);
                     } else {
                         files_for_flexible.push(filename.clone());
                     }
                 }
 
+                let mut unmatched = Vec::new();
                 if proposed_renames.is_empty() && !files_for_flexible.is_empty() {
-                    println!("No files matched standard pattern, trying flexible pattern...");
+                    debug!("no files matched the standard pattern, trying the flexible pattern");
                     
                     for filename in &files_for_flexible {
                         if let Some(rename) = self.process_file_flexible(filename)? {
                             proposed_renames.push(rename);
+                        } else {
+                            unmatched.push(filename.clone());
                         }
                     }
+                } else {
+                    unmatched = files_for_flexible;
+                }
+
+                for filename in &unmatched {
+                    if let Some(rename) = self.process_file_fallback(filename)? {
+                        proposed_renames.push(rename);
+                    } else {
+                        debug!(file = %filename, "skipped: no TV pattern matched");
+                    }
                 }
             },
             FileType::Movie => {
@@ -128,11 +863,78 @@ impl RenameEngine {
                     }
                 }
             }
+            FileType::Auto => {
+                for filename in &files {
+                    if let Some(rename) = self.process_file(filename)? {
+                        proposed_renames.push(rename);
+                    }
+                }
+            }
         }
 
         Ok(proposed_renames)
-    }pub fn process_file_standard(&self, filename: &str) -> Result<Option<FileRename>> {
-        if let Some(captures) = self.standard_pattern.captures(filename) {
+    }
+
+    /// Matches `filename` against the user's custom pattern, if one is configured.
+    /// The `title` group is cleaned into the episode title unless titles were
+    /// fetched; an optional `show` group fills `{show}` in templates.
+    pub fn process_file_custom(&self, filename: &str) -> Result<Option<FileRename>> {
+        let Some(captures) = self.custom_pattern.as_ref().and_then(|pattern| pattern.captures(filename)) else {
+            return Ok(None);
+        };
+        let number = |group: &str| captures.name(group).and_then(|value| value.as_str().parse::<u32>().ok());
+        let (Some(season_number), Some(episode_number)) = (number("season"), number("episode")) else {
+            return Ok(None);
+        };
+        let title = captures.name("title").map(|title| title.as_str()).unwrap_or("");
+        let extension = &self.output_extension(captures.name("extension").map(|ext| ext.as_str()).unwrap_or("mkv"));
+
+        let episode_title = match self.imdb_title(episode_number) {
+            Some(title) => title.to_string(),
+            None => self.extract_episode_title_from_suffix(title),
+        };
+
+        let new_name = match &self.config.template {
+            Some(template) => {
+                let show = captures.name("show").map(|show| clean_show_name(show.as_str())).unwrap_or_default();
+                let show = self.template_show(&show);
+                self.fit_filename(&sanitize_filename(&self.normalize_title(&episode_title)), |title| {
+                    expand_template(template, &TemplateValues {
+                        show: &show,
+                        title,
+                        season: season_number,
+                        episode: episode_number,
+                        episode_end: None,
+                        year: self.config.year.as_deref(),
+                        part: "",
+                        ext: extension,
+                    })
+                })
+            }
+            None => {
+                let sanitized_title = self.separated_title(&episode_title);
+                let season_episode = format_season_episode(season_number, episode_number, None);
+                self.fit_filename(&sanitized_title, |title| {
+                    assemble_filename(&self.with_year(self.episode_stem(title, &season_episode)), extension)
+                })
+            }
+        };
+
+        debug!(file = filename, pattern = "custom", season = season_number, episode = episode_number, new_name = %new_name, "pattern matched");
+        Ok(Some(FileRename {
+            original_path: self.config.directory.join(filename),
+            original_name: filename.to_string(),
+            needs_rename: filename != new_name,
+            match_kind: MatchKind::Custom,
+            new_name,
+            episode_number,
+            season_number,
+            episode_title,
+        }))
+    }
+
+    pub fn process_file_standard(&self, filename: &str) -> Result<Option<FileRename>> {
+        if let Some(captures) = self.standard_captures(filename) {
             let episode_number: u32 = captures.name("episode")
                 .unwrap()
                 .as_str()
@@ -143,22 +945,48 @@ impl RenameEngine {
                 .as_str()
                 .parse()?;
             
+            let episode_end: Option<u32> = captures.name("episode_end")
+                .map(|end| end.as_str().parse())
+                .transpose()?;
+
+            let show = captures.name("title").unwrap().as_str();
             let suffix = captures.name("suffix").unwrap().as_str();
-            let extension = captures.name("extension").unwrap().as_str();
+            let extension = &self.output_extension(captures.name("extension").unwrap().as_str());
 
-            let episode_title = if !self.imdb_titles.is_empty() && episode_number <= self.imdb_titles.len() as u32 {
-                self.imdb_titles[(episode_number - 1) as usize].clone()
-            } else {
-                self.extract_episode_title_from_suffix(suffix)
+            let episode_title = match (self.imdb_title(episode_number), episode_end.and_then(|end| self.imdb_title(end))) {
+                (Some(first), Some(last)) => format!("{} & {}", first, last),
+                (Some(first), None) => first.to_string(),
+                _ => self.extract_episode_title_from_suffix(suffix),
             };
 
-            let sanitized_title = sanitize_filename(&episode_title.replace(' ', "_"));
-            let season_episode = format!("S{:02}E{:02}", season_number, episode_number);
-
-            let new_name = format!("{}_({}).{}", sanitized_title, season_episode, extension);
+            let new_name = match &self.config.template {
+                Some(template) => {
+                    let show = self.template_show(&clean_show_name(show));
+                    self.fit_filename(&sanitize_filename(&self.normalize_title(&episode_title)), |title| {
+                        expand_template(template, &TemplateValues {
+                            show: &show,
+                            title,
+                            season: season_number,
+                            episode: episode_number,
+                            episode_end,
+                            year: self.config.year.as_deref(),
+                            part: "",
+                            ext: extension,
+                        })
+                    })
+                }
+                None => {
+                    let sanitized_title = self.separated_title(&episode_title);
+                    let season_episode = format_season_episode(season_number, episode_number, episode_end);
+                    self.fit_filename(&sanitized_title, |title| {
+                        assemble_filename(&self.with_year(self.episode_stem(title, &season_episode)), extension)
+                    })
+                }
+            };
 
+            debug!(file = filename, pattern = "standard", season = season_number, episode = episode_number, new_name = %new_name, "pattern matched");
             let original_path = self.config.directory.join(filename);
-            let needs_rename = filename != &new_name;
+            let needs_rename = filename != new_name;
             
             return Ok(Some(FileRename {
                 original_path,
@@ -168,6 +996,7 @@ impl RenameEngine {
                 season_number,
                 episode_title,
                 needs_rename,
+                match_kind: MatchKind::Standard,
             }));
         }
 
@@ -184,28 +1013,44 @@ impl RenameEngine {
                 .as_str()
                 .parse()?;
             
-            let title = captures.name("title").unwrap().as_str();
-            let extension = captures.name("extension").unwrap().as_str();
+            let title = strip_scene_tags(captures.name("title").unwrap().as_str());
+            let extension = &self.output_extension(captures.name("extension").unwrap().as_str());
 
-            let episode_title = if !self.imdb_titles.is_empty() && episode_number <= self.imdb_titles.len() as u32 {
-                self.imdb_titles[(episode_number - 1) as usize].clone()
-            } else {
-                title.replace('.', "_")
+            let episode_title = match self.imdb_title(episode_number) {
+                Some(imdb_title) => imdb_title.to_string(),
+                None => title.replace('.', " "),
             };
 
-            let sanitized_title = sanitize_filename(&episode_title.replace(' ', "_"));            let year_part = self.config.year.as_ref()
-                .map(|y| format!("({})", y))
-                .unwrap_or_default();
-
-            let new_name = format!("{}_{}{}.{}", 
-                sanitized_title,
-                self.config.season, 
-                year_part, 
-                extension
-            );
+            let new_name = match &self.config.template {
+                Some(template) => {
+                    let show = self.template_show(&clean_show_name(&title));
+                    self.fit_filename(&sanitize_filename(&self.normalize_title(&episode_title)), |title| {
+                        expand_template(template, &TemplateValues {
+                            show: &show,
+                            title,
+                            season: season_number,
+                            episode: episode_number,
+                            episode_end: None,
+                            year: self.config.year.as_deref(),
+                            part: "",
+                            ext: extension,
+                        })
+                    })
+                }
+                None => {
+                    let sanitized_title = self.separated_title(&episode_title);
+                    self.fit_filename(&sanitized_title, |title| {
+                        assemble_filename(
+                            &self.with_year(format!("{}{}{}", title, self.config.separator, self.config.season)),
+                            extension
+                        )
+                    })
+                }
+            };
 
+            debug!(file = filename, pattern = "flexible", season = season_number, episode = episode_number, new_name = %new_name, "pattern matched");
             let original_path = self.config.directory.join(filename);
-            let needs_rename = filename != &new_name;
+            let needs_rename = filename != new_name;
             
             return Ok(Some(FileRename {
                 original_path,
@@ -215,70 +1060,249 @@ impl RenameEngine {
                 season_number,
                 episode_title,
                 needs_rename,
+                match_kind: MatchKind::Flexible,
             }));        }
 
         Ok(None)
-    }pub fn process_file_with_manual_season(&self, filename: &str, manual_season: u32) -> Result<Option<FileRename>> {
-        let mut file_rename_result = self.process_file_standard(filename)?;
-        if file_rename_result.is_none() {
-            file_rename_result = self.process_file_flexible(filename)?;
-        }
-        
-        if file_rename_result.is_none() {
-            file_rename_result = self.process_file_movie(filename)?;
-        }
-        
-        if let Some(mut file_rename) = file_rename_result {
-            if file_rename.episode_number > 0 {
-                let extension = std::path::Path::new(filename)
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .unwrap_or("mkv");
-                    
-                let sanitized_title = sanitize_filename(&file_rename.episode_title.replace(' ', "_"));
-                
-                let season_episode = format!("S{:02}E{:02}", manual_season, file_rename.episode_number);
-                
-                let new_name = if let Some(year) = &self.config.year {
-                    format!("{}_({}({}).{}", sanitized_title, season_episode, year, extension)
-                } else {
-                    format!("{}_({}).{}", sanitized_title, season_episode, extension)
-                };
-                
-                file_rename.new_name = new_name;
-                file_rename.needs_rename = filename != &file_rename.new_name;
-                file_rename.season_number = manual_season;
+    }
+
+    /// The opt-in TV patterns tried once standard and flexible fail: air dates, then absolute numbers.
+    fn process_file_fallback(&self, filename: &str) -> Result<Option<FileRename>> {
+        if self.config.date_based {
+            if let Some(file_rename) = self.process_file_date(filename)? {
+                return Ok(Some(file_rename));
             }
-            
-            return Ok(Some(file_rename));
         }
-        
+        if self.config.absolute_numbering {
+            return self.process_file_absolute(filename);
+        }
         Ok(None)
-    }    pub fn process_file_movie(&self, filename: &str) -> Result<Option<FileRename>> {
-        if let Some(captures) = self.movie_pattern.captures(filename) {
-            let raw_title = captures.name("title").unwrap().as_str();
-            let extension = captures.name("extension").unwrap().as_str();
-            let extracted_year = captures.name("year").map(|y| y.as_str());
-            let quality_part = captures.name("quality").map(|q| q.as_str()).unwrap_or("");
-            
-            let cleaned_title = self.clean_movie_title(raw_title, quality_part);
-            
-            if cleaned_title.is_empty() {
-                return Ok(None);
+    }
+
+    /// Handles daily shows named by air date, producing `Show_(2023-11-05).mkv`.
+    /// Accepts `.` or `-` separators with the year first or last; see [`DateOrder`].
+    pub fn process_file_date(&self, filename: &str) -> Result<Option<FileRename>> {
+        let Some(captures) = self.date_pattern.captures(filename) else {
+            return Ok(None);
+        };
+
+        let number = |name: &str| -> Result<u32> {
+            Ok(captures.name(name).unwrap().as_str().parse()?)
+        };
+        let (year, month, day) = if captures.name("year_first").is_some() {
+            (number("year_first")?, number("month")?, number("day")?)
+        } else {
+            let (first, second) = (number("first")?, number("second")?);
+            match self.config.date_order {
+                DateOrder::DayFirst => (number("year_last")?, second, first),
+                DateOrder::MonthFirst => (number("year_last")?, first, second),
+            }
+        };
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Ok(None);
+        }
+
+        let show = clean_show_name(captures.name("title").unwrap().as_str());
+        let extension = &self.output_extension(captures.name("extension").unwrap().as_str());
+        let air_date = format!("{:04}-{:02}-{:02}", year, month, day);
+
+        let new_name = if show.is_empty() {
+            assemble_filename(&format!("({})", air_date), extension)
+        } else {
+            self.fit_filename(&show.replace(' ', &self.config.separator.to_string()), |show| {
+                assemble_filename(&format!("{}{}({})", show, self.config.separator, air_date), extension)
+            })
+        };
+
+        debug!(file = filename, pattern = "date", air_date = %air_date, new_name = %new_name, "pattern matched");
+        let original_path = self.config.directory.join(filename);
+        let needs_rename = filename != new_name;
+
+        Ok(Some(FileRename {
+            original_path,
+            original_name: filename.to_string(),
+            new_name,
+            episode_number: 0,
+            season_number: self.config.season_num,
+            episode_title: air_date,
+            needs_rename,
+            match_kind: MatchKind::Date,
+        }))
+    }
+
+    /// Handles absolutely numbered files like `Show - 135.mkv`, mapping the
+    /// number onto a season with [`RenameConfig::episodes_per_season`].
+    pub fn process_file_absolute(&self, filename: &str) -> Result<Option<FileRename>> {
+        let Some(captures) = self.absolute_pattern.captures(filename) else {
+            return Ok(None);
+        };
+
+        let absolute: u32 = captures.name("episode").unwrap().as_str().parse()?;
+        if absolute == 0 {
+            return Ok(None);
+        }
+        let (season_number, episode_number) = map_absolute_episode(absolute, &self.config.episodes_per_season);
+
+        let show = captures.name("title").unwrap().as_str();
+        let suffix = captures.name("suffix").unwrap().as_str();
+        let extension = &self.output_extension(captures.name("extension").unwrap().as_str());
+
+        // Fetched titles only cover the configured season
+        let episode_title = match self.imdb_title(episode_number) {
+            Some(title) if season_number == self.config.season_num => title.to_string(),
+            _ => self.extract_episode_title_from_suffix(suffix),
+        };
+
+        let new_name = match &self.config.template {
+            Some(template) => {
+                let show = self.template_show(&clean_show_name(show));
+                self.fit_filename(&sanitize_filename(&self.normalize_title(&episode_title)), |title| {
+                    expand_template(template, &TemplateValues {
+                        show: &show,
+                        title,
+                        season: season_number,
+                        episode: episode_number,
+                        episode_end: None,
+                        year: self.config.year.as_deref(),
+                        part: "",
+                        ext: extension,
+                    })
+                })
+            }
+            None => {
+                let sanitized_title = self.separated_title(&episode_title);
+                let season_episode = format_season_episode(season_number, episode_number, None);
+                self.fit_filename(&sanitized_title, |title| {
+                    assemble_filename(&self.with_year(self.episode_stem(title, &season_episode)), extension)
+                })
+            }
+        };
+
+        debug!(file = filename, pattern = "absolute", season = season_number, episode = episode_number, new_name = %new_name, "pattern matched");
+        let original_path = self.config.directory.join(filename);
+        let needs_rename = filename != new_name;
+
+        Ok(Some(FileRename {
+            original_path,
+            original_name: filename.to_string(),
+            new_name,
+            episode_number,
+            season_number,
+            episode_title,
+            needs_rename,
+            match_kind: MatchKind::Absolute,
+        }))
+    }
+
+    pub fn process_file_with_manual_season(&self, filename: &str, manual_season: u32) -> Result<Option<FileRename>> {
+        self.process_file_with_season_and_title(filename, manual_season, None)
+    }
+
+    /// Like [`Self::process_file_with_manual_season`], naming an episode after `title`
+    /// instead of whatever the filename or the fetched titles give.
+    pub fn process_file_with_season_and_title(&self, filename: &str, manual_season: u32, title: Option<&str>) -> Result<Option<FileRename>> {
+        let mut file_rename_result = self.process_file_standard(filename)?;
+        if file_rename_result.is_none() {
+            file_rename_result = self.process_file_flexible(filename)?;
+        }
+        
+        if file_rename_result.is_none() {
+            file_rename_result = self.process_file_movie(filename)?;
+        }
+        
+        if let Some(mut file_rename) = file_rename_result {
+            if file_rename.episode_number > 0 {
+                if let Some(title) = title {
+                    file_rename.episode_title = title.to_string();
+                }
+                let extension = &self.output_extension(std::path::Path::new(filename)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("mkv"));
+                    
+                let episode_end = self.standard_captures(filename)
+                    .and_then(|captures| captures.name("episode_end"))
+                    .and_then(|end| end.as_str().parse().ok());
+
+                let new_name = if let Some(template) = &self.config.template {
+                    let show = self.template_show(&self.show_name(filename));
+                    self.fit_filename(&sanitize_filename(&self.normalize_title(&file_rename.episode_title)), |title| {
+                        expand_template(template, &TemplateValues {
+                            show: &show,
+                            title,
+                            season: manual_season,
+                            episode: file_rename.episode_number,
+                            episode_end,
+                            year: self.config.year.as_deref(),
+                            part: "",
+                            ext: extension,
+                        })
+                    })
+                } else {
+                    let sanitized_title = self.separated_title(&file_rename.episode_title);
+                    let season_episode = format_season_episode(manual_season, file_rename.episode_number, episode_end);
+
+                    self.fit_filename(&sanitized_title, |title| {
+                        assemble_filename(&self.with_year(self.episode_stem(title, &season_episode)), extension)
+                    })
+                };
+                
+                file_rename.new_name = new_name;
+                file_rename.needs_rename = filename != file_rename.new_name;
+                file_rename.season_number = manual_season;
             }
             
-            let sanitized_title = sanitize_filename(&cleaned_title.replace(' ', "_"));
+            return Ok(Some(file_rename));
+        }
+        
+        Ok(None)
+    }    pub fn process_file_movie(&self, filename: &str) -> Result<Option<FileRename>> {
+        if let Some(captures) = self.movie_pattern.captures(filename) {
+            let raw_extension = captures.name("extension").unwrap().as_str();
+            let stem = filename.strip_suffix(raw_extension).unwrap_or(filename).trim_end_matches('.');
+            let extension = &self.output_extension(raw_extension);
+            let (raw_title, extracted_year, quality_part) = split_movie_stem(stem);
+            let part_number = self.extract_part_number(stem);
+            let raw_title = self.part_pattern.replace_all(raw_title, " ");
+            
+            let cleaned_title = self.clean_movie_title(&raw_title, quality_part);
             
-            let year_part = if let Some(config_year) = &self.config.year {
-                format!("_({})", config_year)
-            } else if let Some(extracted_year) = extracted_year {
-                format!("_({})", extracted_year)
-            } else {
-                String::new()
-            };
+            if cleaned_title.is_empty() {
+                return Ok(None);
+            }
+            
+            let year = self.config.year.as_deref().or(extracted_year);
                 
-            let new_name = format!("{}{}.{}", sanitized_title, year_part, extension);
+            // Jellyfin stacks multi-part movies named "<name>-part1", "<name>-part2", ...
+            let part_suffix = part_number
+                .map(|part| self.format_part_suffix(part))
+                .unwrap_or_default();
+
+            let new_name = match &self.config.template {
+                Some(template) => {
+                    self.fit_filename(&sanitize_filename(&self.normalize_title(&cleaned_title)), |title| {
+                        expand_template(template, &TemplateValues {
+                            show: title,
+                            title,
+                            season: 0,
+                            episode: 0,
+                            episode_end: None,
+                            year,
+                            part: &part_suffix,
+                            ext: extension,
+                        })
+                    })
+                }
+                None => {
+                    let sanitized_title = self.separated_title(&cleaned_title);
+                    let year_part = year.map(|year| format!("{}({})", self.config.separator, year)).unwrap_or_default();
+                    self.fit_filename(&sanitized_title, |title| {
+                        assemble_filename(&format!("{}{}{}", title, year_part, part_suffix), extension)
+                    })
+                }
+            };
             
+            debug!(file = filename, pattern = "movie", new_name = %new_name, "pattern matched");
             let file_rename = FileRename {
                 original_path: self.config.directory.join(filename),
                 original_name: filename.to_string(),
@@ -287,13 +1311,217 @@ impl RenameEngine {
                 episode_number: 0,
                 season_number: 1,
                 needs_rename: filename != new_name,
+                match_kind: MatchKind::Movie,
             };
             
             return Ok(Some(file_rename));
         }
         
         Ok(None)
-    }    fn clean_movie_title(&self, title: &str, quality_part: &str) -> String {
+    }
+
+    /// Whether `filename` carries an `S01E01`, `Season 1 Episode 1` or `1x01` episode marker.
+    pub fn is_episode_filename(&self, filename: &str) -> bool {
+        self.standard_captures(filename).is_some()
+            || self.flexible_pattern.is_match(filename)
+            || self.custom_pattern.as_ref().is_some_and(|pattern| pattern.is_match(filename))
+    }
+
+    /// Whether `filename` is a video file with an accepted extension.
+    pub fn is_video_filename(&self, filename: &str) -> bool {
+        self.movie_pattern.is_match(filename)
+    }
+
+    /// `Title_(S01E01)`, or `Show_S01E01_Title` when a show name is configured.
+    fn episode_stem(&self, title: &str, season_episode: &str) -> String {
+        let separator = self.config.separator;
+        match &self.config.show_name {
+            Some(show) => {
+                let show = sanitize_filename(&show.replace(' ', &separator.to_string()));
+                if title.is_empty() {
+                    format!("{}{}{}", show, separator, season_episode)
+                } else {
+                    format!("{}{}{}{}{}", show, separator, season_episode, separator, title)
+                }
+            }
+            None => format!("{}{}({})", title, separator, season_episode),
+        }
+    }
+
+    /// Appends the configured year, as in `Pilot_(S01E01)_(2008)`, so every episode
+    /// pattern tags names the same way.
+    fn with_year(&self, stem: String) -> String {
+        match &self.config.year {
+            Some(year) => format!("{}{}({})", stem, self.config.separator, year),
+            None => stem,
+        }
+    }
+
+    /// A title as the built-in names write it: normalized, with spaces turned into
+    /// the configured separator, then sanitized.
+    fn separated_title(&self, title: &str) -> String {
+        sanitize_filename(&self.normalize_title(title).replace(' ', &self.config.separator.to_string()))
+    }
+
+    /// The `{show}` template value: the configured show name, else the one parsed from the filename.
+    fn template_show(&self, parsed: &str) -> String {
+        self.config.show_name.clone().unwrap_or_else(|| parsed.to_string())
+    }
+
+    /// The fetched title for `episode_number`, if there is one. Episode 0, numbers past
+    /// the list and gaps left by [`titles_by_episode`] all fall back to the filename.
+    fn imdb_title(&self, episode_number: u32) -> Option<&str> {
+        let index = episode_number.checked_sub(1)? as usize;
+        self.imdb_titles.get(index).map(String::as_str).filter(|title| !title.is_empty())
+    }
+
+    /// Captures for an `S01E01` marker, or failing that a spelled-out `Season 1 Episode 1`.
+    fn standard_captures<'a>(&self, filename: &'a str) -> Option<regex::Captures<'a>> {
+        self.standard_pattern.captures(filename)
+            .or_else(|| self.worded_pattern.captures(filename))
+    }
+
+    /// The fetched title for `episode_number`, if titles were fetched and include it.
+    pub fn provider_title(&self, episode_number: u32) -> Option<&str> {
+        self.imdb_title(episode_number)
+    }
+
+    /// The series name at the front of an episode filename, if it has one.
+    fn show_name(&self, filename: &str) -> String {
+        self.standard_captures(filename)
+            .or_else(|| self.flexible_pattern.captures(filename))
+            .and_then(|captures| captures.name("title"))
+            .map(|title| clean_show_name(title.as_str()))
+            .unwrap_or_default()
+    }
+
+    /// Builds a filename from `title`, shortening the title until the whole name
+    /// fits in `max_filename_bytes` so suffixes like `(S01E01).mkv` survive intact.
+    fn fit_filename(&self, title: &str, build: impl Fn(&str) -> String) -> String {
+        let mut title = title;
+        let mut name = build(title);
+        while name.len() > self.config.max_filename_bytes && !title.is_empty() {
+            let excess = name.len() - self.config.max_filename_bytes;
+            title = truncate_to_char_boundary(title, title.len().saturating_sub(excess))
+                .trim_end_matches(['_', ' ', '.', '-']);
+            name = build(title);
+        }
+        name
+    }
+
+    /// The extension as it appears in new names; see [`RenameConfig::normalize_extension_case`].
+    fn output_extension(&self, extension: &str) -> String {
+        if self.config.normalize_extension_case {
+            extension.to_lowercase()
+        } else {
+            extension.to_string()
+        }
+    }
+
+    fn normalize_title(&self, title: &str) -> String {
+        let title = normalize_unicode(title, self.config.transliterate);
+        normalize_title_symbols(&title, &self.config.apostrophes, &self.config.ampersands)
+    }
+
+    /// Why a scan leaves out a file of `size` bytes (when known), or `None` to scan it.
+    fn skip_reason(&self, filename: &str, size: Option<u64>) -> Option<&'static str> {
+        if self.config.skip_hidden_files && filename.starts_with('.') {
+            Some("hidden file")
+        } else if self.config.skip_empty_files && size == Some(0) {
+            Some("empty file")
+        } else if self.is_incomplete_download(filename) {
+            Some("download still in progress")
+        } else if self.is_sample(filename, size) {
+            Some("sample clip")
+        } else if self.is_excluded(filename) {
+            Some("matches an exclude pattern")
+        } else {
+            None
+        }
+    }
+
+    /// Whether `filename` names a sample clip: "sample" anywhere in the name, ignoring case,
+    /// on a file smaller than the configured limit. A file of unknown size counts as small.
+    pub fn is_sample(&self, filename: &str, size: Option<u64>) -> bool {
+        self.config.skip_samples
+            && filename.to_lowercase().contains("sample")
+            && size.is_none_or(|size| size < self.config.sample_size_limit)
+    }
+
+    /// Whether `filename` ends in one of the unfinished-download suffixes, ignoring case.
+    pub fn is_incomplete_download(&self, filename: &str) -> bool {
+        let filename = filename.to_lowercase();
+        self.config.incomplete_suffixes.iter()
+            .any(|suffix| filename.ends_with(&format!(".{}", suffix.to_lowercase())))
+    }
+
+    pub fn is_excluded(&self, filename: &str) -> bool {
+        self.config.exclude_patterns.iter().any(|pattern| pattern.is_match(filename))
+    }
+
+    fn extract_part_number(&self, stem: &str) -> Option<u32> {
+        self.part_pattern
+            .captures(stem)
+            .and_then(|captures| captures.name("part"))
+            .and_then(|part| part.as_str().parse().ok())
+    }
+
+    fn format_part_suffix(&self, part: u32) -> String {
+        if self.config.pad_part_numbers {
+            format!("-part{:02}", part)
+        } else {
+            format!("-part{}", part)
+        }
+    }
+
+    /// Gives every rename a unique target within its directory. The first file keeps
+    /// its name and later collisions get a suffix in the configured [`DisambiguationStyle`].
+    pub fn disambiguate_targets(&self, renames: &mut [FileRename]) {
+        let mut taken_by_directory: std::collections::HashMap<PathBuf, HashSet<String>> = std::collections::HashMap::new();
+
+        for rename in renames.iter_mut() {
+            let directory = rename.original_path.parent().map(Path::to_path_buf).unwrap_or_default();
+            let taken = taken_by_directory.entry(directory).or_default();
+            if taken.insert(rename.new_name.to_lowercase()) {
+                continue;
+            }
+
+            let (stem, extension) = rename.new_name
+                .rsplit_once('.')
+                .map(|(stem, extension)| (stem.to_string(), extension.to_string()))
+                .unwrap_or_else(|| (rename.new_name.clone(), String::new()));
+
+            let tag = match self.config.disambiguation {
+                DisambiguationStyle::Numeric => None,
+                DisambiguationStyle::Resolution => extract_resolution_tag(&rename.original_name),
+                DisambiguationStyle::Timestamp => fs::metadata(&rename.original_path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|age| age.as_secs().to_string()),
+            };
+
+            let mut candidate = tag
+                .map(|tag| assemble_filename(&format!("{}{}{}", stem, self.config.separator, tag), &extension))
+                .filter(|name| !taken.contains(&name.to_lowercase()));
+
+            let mut counter = 2;
+            while candidate.is_none() {
+                let name = assemble_filename(&format!("{}{}{}", stem, self.config.separator, counter), &extension);
+                if !taken.contains(&name.to_lowercase()) {
+                    candidate = Some(name);
+                }
+                counter += 1;
+            }
+
+            let new_name = candidate.unwrap();
+            taken.insert(new_name.to_lowercase());
+            rename.needs_rename = rename.original_name != new_name;
+            rename.new_name = new_name;
+        }
+    }
+
+    fn clean_movie_title(&self, title: &str, quality_part: &str) -> String {
         let _ = quality_part;
         let mut cleaned = title.trim().to_string();
         
@@ -312,9 +1540,7 @@ impl RenameEngine {
             }
         }
         
-        cleaned = cleaned.replace('.', " ")
-                        .replace('_', " ")
-                        .replace('-', " ");
+        cleaned = cleaned.replace(['.', '_', '-'], " ");
         
         let quality_indicators = [
             "1080p", "720p", "480p", "4k", "2160p", "hd", "fhd", "uhd",
@@ -341,30 +1567,18 @@ impl RenameEngine {
         }
         
         cleaned = clean_words.join(" ");
+
+        // Brackets left empty once the year was split off, e.g. `Film ()`
+        cleaned = cleaned.replace("()", " ").replace("[]", " ");
         
-        if self.config.year.is_none() {
-            if let Ok(year_regex) = Regex::new(r"\b(19\d{2}|20\d{2})\b") {
-                cleaned = year_regex.replace_all(&cleaned, "").to_string();
-            }
-        }
-        
-        cleaned = cleaned.trim()
+        cleaned = cleaned
             .split_whitespace()
             .collect::<Vec<&str>>()
             .join(" ");
         
-        cleaned.split_whitespace()
-            .map(|word| {
-                let mut chars: Vec<char> = word.chars().collect();
-                if !chars.is_empty() {
-                    chars[0] = chars[0].to_uppercase().next().unwrap_or(chars[0]);
-                }
-                chars.into_iter().collect()
-            })
-            .collect::<Vec<String>>()
-            .join(" ")
+        self.config.casing.apply(&cleaned)
     }fn extract_episode_title_from_suffix(&self, suffix: &str) -> String {
-        let cleaned = suffix.trim().to_string();
+        let cleaned = strip_release_tags(suffix);
           let quality_indicators = [
             "1080p", "720p", "480p", "4k", "2160p", "hd", "fhd", "uhd",
             "x264", "x265", "h264", "h265", "xvid", "divx", "mpeg",
@@ -404,138 +1618,1108 @@ impl RenameEngine {
         }
           let title = title_words.join(" ");
         
-        title.split_whitespace()
-            .map(|word| {
-                let mut chars: Vec<char> = word.chars().collect();
-                if !chars.is_empty() {
-                    chars[0] = chars[0].to_uppercase().next().unwrap_or(chars[0]);
-                }
-                chars.into_iter().collect()
-            })
-            .collect::<Vec<String>>()
-            .join(" ")
+        self.config.casing.apply(&title)
     }
 
-    pub async fn rename_file(&self, file_rename: &FileRename) -> RenameResult {
-        let new_path = self.config.directory.join(&file_rename.new_name);
-        
-        match fs::rename(&file_rename.original_path, &new_path) {
-            Ok(_) => RenameResult {
-                success: true,
-                error_message: None,
-            },
-            Err(e) => RenameResult {
-                success: false,
-                error_message: Some(e.to_string()),            }
+    /// Where `file_rename` ends up: its own directory, or the matching season folder
+    /// inside it when `organize_into_folders` is set and the file is an episode.
+    pub fn target_path(&self, file_rename: &FileRename) -> PathBuf {
+        // Rename in place so batches spanning several directories stay put
+        let parent = file_rename.original_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.config.directory.clone());
+
+        // Movies found in auto mode have no episode number and stay where they are
+        if self.config.organize_into_folders && self.config.file_type.has_episodes() && file_rename.episode_number > 0 {
+            season_folder_path(&parent, file_rename.season_number).join(&file_rename.new_name)
+        } else {
+            parent.join(&file_rename.new_name)
         }
-    }    pub fn process_file_with_year(&self, filename: &str, year: Option<String>) -> Result<Option<FileRename>> {
-        let mut temp_config = self.config.clone();
-        temp_config.year = year;
-        
-        let temp_engine = RenameEngine {
-            config: temp_config,
-            imdb_titles: self.imdb_titles.clone(),
-            standard_pattern: self.standard_pattern.clone(),
-            flexible_pattern: self.flexible_pattern.clone(),
-            movie_pattern: self.movie_pattern.clone(),
+    }
+
+    pub async fn rename_file(&self, file_rename: &FileRename) -> RenameResult {
+        let mut new_path = self.target_path(file_rename);
+        let mut replaces_existing = false;
+        let target_dir = new_path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        let operation = if new_path.parent() == file_rename.original_path.parent() {
+            OperationKind::Rename
+        } else {
+            OperationKind::Move
         };
-          match self.config.file_type {
-            FileType::TvShow => {
-                if let Some(file_rename) = temp_engine.process_file_standard(filename)? {
-                    return Ok(Some(file_rename));
-                } else if let Some(file_rename) = temp_engine.process_file_flexible(filename)? {
-                    return Ok(Some(file_rename));
-                }
-            },
-            FileType::Movie => {
-                if let Some(file_rename) = temp_engine.process_file_movie(filename)? {
-                    return Ok(Some(file_rename));
+
+        // A case-only rename on a case-insensitive filesystem "finds" the source itself
+        if new_path.exists() && !is_same_file(&file_rename.original_path, &new_path) {
+            match self.config.on_conflict {
+                ConflictStrategy::Skip => {
+                    info!(from = %file_rename.original_path.display(), to = %new_path.display(), "target exists, skipped");
+                    return RenameResult {
+                        outcome: RenameOutcome::Skipped(new_path),
+                        operation,
+                        companions: Vec::new(),
+                        cross_device: false,
+                        created_dirs: Vec::new(),
+                        created_files: Vec::new(),
+                        trashed: Vec::new(),
+                    };
                 }
+                ConflictStrategy::Overwrite => replaces_existing = true,
+                ConflictStrategy::RenameWithSuffix => new_path = first_free_path(&new_path),
             }
         }
-        
-        Ok(None)
-    }
-}
+        let new_name = new_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
 
-pub fn sanitize_filename(filename: &str) -> String {
-    let re = Regex::new(r#"[<>:"/\\|?*,]"#).unwrap();
-    re.replace_all(filename, "_").to_string()
-}
+        // Sidecars follow the video into its season folder, and meet existing files the same way it does
+        let companions: Vec<(PathBuf, PathBuf)> = self.find_companions(&file_rename.original_path, &new_name)
+            .into_iter()
+            .filter_map(|(from, to)| {
+                let to = to.file_name().map(|name| target_dir.join(name)).unwrap_or(to);
+                let to = self.companion_target(&from, to)?;
+                Some((from, to))
+            })
+            .collect();
 
-pub fn extract_season_from_directory(dir_name: &str) -> Option<u32> {
-    let patterns = [
-        r"s(?:eason\s*)?(\d+)",           
-        r"(?:season\s+)(\d+)",            
-        r"(\d+)(?:st|nd|rd|th)\s*season", 
-        r"series\s*(\d+)",                
-    ];
-    
-    let dir_lower = dir_name.to_lowercase();
-    
-    for pattern in &patterns {
-        if let Ok(re) = regex::Regex::new(pattern) {
-            if let Some(captures) = re.captures(&dir_lower) {
-                if let Some(season_match) = captures.get(1) {
-                    if let Ok(season_num) = season_match.as_str().parse::<u32>() {
-                        return Some(season_num);
+        if self.config.dry_run {
+            return RenameResult {
+                outcome: RenameOutcome::Success(new_path),
+                operation,
+                companions,
+                cross_device: false,
+                created_dirs: Vec::new(),
+                created_files: Vec::new(),
+                trashed: Vec::new(),
+            };
+        }
+
+        let created_dirs = match create_missing_dirs(&target_dir) {
+            Ok(created_dirs) => created_dirs,
+            Err(e) => {
+                warn!(dir = %target_dir.display(), error = %e, "could not create folder");
+                return RenameResult {
+                    outcome: RenameOutcome::from_io_error(&e, &file_rename.original_path, &target_dir),
+                    operation,
+                    companions: Vec::new(),
+                    cross_device: false,
+                    created_dirs: Vec::new(),
+                    created_files: Vec::new(),
+                    trashed: Vec::new(),
+                };
+            }
+        };
+
+        // Never clobber: the replaced file goes to the trash, or the rename fails
+        let trashed = if replaces_existing {
+            if let Err(e) = trash::delete(&new_path) {
+                warn!(path = %new_path.display(), error = %e, "could not move to the trash");
+                remove_empty_dirs(&created_dirs);
+                return RenameResult {
+                    outcome: RenameOutcome::OtherError(format!("Could not move {} to the trash: {}", new_path.display(), e)),
+                    operation,
+                    companions: Vec::new(),
+                    cross_device: false,
+                    created_dirs: Vec::new(),
+                    created_files: Vec::new(),
+                    trashed: Vec::new(),
+                };
+            }
+            info!(path = %new_path.display(), "moved existing target to the trash");
+            vec![new_path.clone()]
+        } else {
+            Vec::new()
+        };
+
+        let source_metadata = if self.config.preserve_timestamps {
+            fs::metadata(&file_rename.original_path).ok()
+        } else {
+            None
+        };
+
+        match move_file(&file_rename.original_path, &new_path) {
+            Ok(cross_device) => {
+                info!(from = %file_rename.original_path.display(), to = %new_path.display(), cross_device, "renamed");
+                if let Some(metadata) = &source_metadata {
+                    if let Err(e) = restore_timestamps(&new_path, metadata) {
+                        warn!(path = %new_path.display(), error = %e, "could not restore timestamps");
                     }
                 }
+                let mut trashed = trashed;
+                let companions = self.move_companions(companions, &mut trashed);
+                let created_files = if self.config.write_nfo {
+                    self.write_nfo_files(file_rename, &new_path)
+                } else {
+                    Vec::new()
+                };
+                RenameResult {
+                    outcome: RenameOutcome::Success(new_path),
+                    operation,
+                    companions,
+                    cross_device,
+                    created_dirs,
+                    created_files,
+                    trashed,
+                }
             }
-        }
-    }
-    
-    None
-}
-
-pub fn extract_season_from_filename(filename: &str) -> Option<u32> {
-    let patterns = [
-        r"S(\d{1,2})E\d{2}",              
-        r"(?:season\s*)?(\d+)x\d{2}",     
-        r"s(\d+)e\d+",                    
-    ];
-    
-    let filename_lower = filename.to_lowercase();
-    
-    for pattern in &patterns {
-        if let Ok(re) = regex::Regex::new(pattern) {
-            if let Some(captures) = re.captures(&filename_lower) {
-                if let Some(season_match) = captures.get(1) {
-                    if let Ok(season_num) = season_match.as_str().parse::<u32>() {
-                        return Some(season_num);
+            Err(e) => {
+                warn!(from = %file_rename.original_path.display(), to = %new_path.display(), error = %e, "rename failed");
+                remove_empty_dirs(&created_dirs);
+                for trashed in &trashed {
+                    if let Err(e) = restore_from_trash(trashed) {
+                        warn!(path = %trashed.display(), error = %e, "could not restore replaced file from the trash");
                     }
                 }
+                RenameResult {
+                    outcome: RenameOutcome::from_io_error(&e, &file_rename.original_path, &new_path),
+                    operation,
+                    companions: Vec::new(),
+                    cross_device: false,
+                    created_dirs: Vec::new(),
+                    created_files: Vec::new(),
+                    trashed: Vec::new(),
+                }
             }
         }
     }
-    
-    None
-}
 
-pub async fn scrape_imdb_episodes(imdb_id: &str, season: Option<u32>) -> Result<Vec<String>> {
-    // OMDb API returns clean JSON and is not behind bot-protection.
-    // The "trilogy" key is a publicly usable demo key.
-    let season_num = season.unwrap_or(1);
-    let url = format!(
-        "https://www.omdbapi.com/?i={}&Season={}&type=series&apikey=trilogy",
-        imdb_id, season_num
-    );
+    /// Writes `.nfo` metadata for a renamed file: one next to the file and, for
+    /// episodes, a `tvshow.nfo` in the series folder. Existing files are never
+    /// overwritten, and a failed write is logged without failing the rename.
+    fn write_nfo_files(&self, file_rename: &FileRename, new_path: &Path) -> Vec<PathBuf> {
+        let is_episode = self.config.file_type.has_episodes() && file_rename.episode_number > 0;
+        let mut documents = Vec::new();
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Mozilla/5.0")
-        .send()
-        .await
-        .context("Failed to fetch OMDb episode data")?;
+        if is_episode {
+            documents.push((
+                new_path.with_extension("nfo"),
+                nfo::episode_nfo(
+                    &file_rename.episode_title,
+                    file_rename.season_number,
+                    file_rename.episode_number,
+                    self.config.year.as_deref(),
+                ),
+            ));
 
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!("OMDb HTTP error: {}", response.status()));
-    }
+            let show = self.config.show_name.clone()
+                .unwrap_or_else(|| self.show_name(&file_rename.original_name));
+            if let Some(episode_dir) = new_path.parent().filter(|_| !show.is_empty()) {
+                let in_season_folder = episode_dir
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(extract_season_from_directory)
+                    .is_some();
+                let series_dir = match episode_dir.parent() {
+                    Some(parent) if in_season_folder => parent,
+                    _ => episode_dir,
+                };
+                documents.push((series_dir.join("tvshow.nfo"), nfo::tvshow_nfo(&show, None)));
+            }
+        } else {
+            let year = self.config.year.clone().or_else(|| {
+                let stem = Path::new(&file_rename.original_name).file_stem()?.to_str()?;
+                split_movie_stem(stem).1.map(str::to_string)
+            });
+            documents.push((
+                new_path.with_extension("nfo"),
+                nfo::movie_nfo(&file_rename.episode_title, year.as_deref()),
+            ));
+        }
+
+        documents
+            .into_iter()
+            .filter(|(path, _)| !path.exists())
+            .filter_map(|(path, contents)| match fs::write(&path, contents) {
+                Ok(()) => Some(path),
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "could not write nfo");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Moves the sidecars after their video, returning the ones that moved. A sidecar
+    /// that fails to move is left behind rather than failing the video, and one that
+    /// replaces a file sends it to the trash first, adding it to `trashed`.
+    fn move_companions(&self, companions: Vec<(PathBuf, PathBuf)>, trashed: &mut Vec<PathBuf>) -> Vec<(PathBuf, PathBuf)> {
+        companions
+            .into_iter()
+            .filter(|(from, to)| {
+                let replaces = to.exists() && !is_same_file(from, to);
+                if replaces {
+                    // Appeared since the targets were resolved; only an overwrite may replace it
+                    if self.config.on_conflict != ConflictStrategy::Overwrite {
+                        return false;
+                    }
+                    if let Err(e) = trash::delete(to) {
+                        warn!(path = %to.display(), error = %e, "could not move to the trash");
+                        return false;
+                    }
+                    info!(path = %to.display(), "moved existing sidecar target to the trash");
+                }
+                match move_file(from, to) {
+                    Ok(_) => {
+                        if replaces {
+                            trashed.push(to.clone());
+                        }
+                        true
+                    }
+                    Err(e) => {
+                        warn!(from = %from.display(), to = %to.display(), error = %e, "sidecar rename failed");
+                        if replaces {
+                            if let Err(e) = restore_from_trash(to) {
+                                warn!(path = %to.display(), error = %e, "could not restore replaced file from the trash");
+                            }
+                        }
+                        false
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Where a sidecar bound for `target` goes under the conflict strategy: `None` when
+    /// an existing file there means it's skipped, otherwise `target` or the first free
+    /// numbered name next to it.
+    fn companion_target(&self, source: &Path, target: PathBuf) -> Option<PathBuf> {
+        if !target.exists() || is_same_file(source, &target) {
+            return Some(target);
+        }
+        match self.config.on_conflict {
+            ConflictStrategy::Skip => {
+                info!(from = %source.display(), to = %target.display(), "sidecar target exists, skipped");
+                None
+            }
+            ConflictStrategy::Overwrite => Some(target),
+            ConflictStrategy::RenameWithSuffix => Some(first_free_path(&target)),
+        }
+    }
+
+    /// Sidecars next to `original_path` that share its stem, paired with their new paths.
+    /// Anything between the stem and the extension (like `.en` or `.en.forced`) is kept.
+    pub fn find_companions(&self, original_path: &Path, new_name: &str) -> Vec<(PathBuf, PathBuf)> {
+        let (Some(parent), Some(original_stem)) = (
+            original_path.parent(),
+            original_path.file_stem().and_then(|stem| stem.to_str()),
+        ) else {
+            return Vec::new();
+        };
+        let new_stem = Path::new(new_name).file_stem().and_then(|stem| stem.to_str()).unwrap_or(new_name);
+        let prefix = format!("{}.", original_stem);
+
+        let Ok(entries) = fs::read_dir(parent) else {
+            return Vec::new();
+        };
+
+        let mut companions: Vec<(PathBuf, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let rest = name.strip_prefix(&prefix)?;
+                let extension = rest.rsplit('.').next()?;
+                if !self.config.companion_extensions.iter().any(|ext| ext.eq_ignore_ascii_case(extension)) {
+                    return None;
+                }
+                Some((entry.path(), parent.join(format!("{}.{}", new_stem, rest))))
+            })
+            .collect();
+        companions.sort();
+        companions
+    }    pub fn process_file_with_year(&self, filename: &str, year: Option<String>) -> Result<Option<FileRename>> {
+        let mut temp_config = self.config.clone();
+        temp_config.year = year;
+        
+        let temp_engine = self.with_config(temp_config);
+        temp_engine.process_file(filename)
+    }
+
+    /// Processes a single file with the pattern set matching the configured file type:
+    /// standard then flexible for TV shows, the movie pattern for movies. In auto mode
+    /// a file that doesn't look like an episode is treated as a movie.
+    pub fn process_file(&self, filename: &str) -> Result<Option<FileRename>> {
+        match self.config.file_type {
+            FileType::TvShow => self.process_file_episode(filename),
+            FileType::Movie => self.process_file_movie(filename),
+            FileType::Auto => match self.process_file_episode(filename)? {
+                Some(file_rename) => Ok(Some(file_rename)),
+                None => self.process_file_movie(filename),
+            },
+        }
+    }
+
+    fn process_file_episode(&self, filename: &str) -> Result<Option<FileRename>> {
+        if let Some(file_rename) = self.process_file_custom(filename)? {
+            return Ok(Some(file_rename));
+        }
+        if let Some(file_rename) = self.process_file_standard(filename)? {
+            return Ok(Some(file_rename));
+        }
+        if let Some(file_rename) = self.process_file_flexible(filename)? {
+            return Ok(Some(file_rename));
+        }
+        self.process_file_fallback(filename)
+    }
+}
+
+/// Plans every rename in `config.directory` without touching the filesystem.
+///
+/// When `config.use_imdb` is set the episode titles are fetched first; if the
+/// lookup comes back empty the warning is logged and titles parsed from the
+/// filenames are used instead.
+pub async fn plan_directory(config: RenameConfig) -> Result<Vec<FileRename>> {
+    let mut engine = RenameEngine::new(config)?;
+    if let Some(message) = engine.fetch_imdb_titles().await? {
+        warn!(%message, "episode titles unavailable");
+    }
+    engine.scan_directory()
+}
+
+/// Carries out a plan from [`plan_directory`], returning one result per entry in
+/// the same order. Entries that need no rename are reported as successful and left alone.
+pub async fn apply(renames: &[FileRename], config: &RenameConfig) -> Result<Vec<RenameResult>> {
+    let engine = RenameEngine::new(config.clone())?;
+    let mut results = Vec::with_capacity(renames.len());
+
+    for file_rename in renames {
+        if !file_rename.needs_rename {
+            results.push(RenameResult {
+                outcome: RenameOutcome::Success(file_rename.original_path.clone()),
+                operation: OperationKind::Rename,
+                companions: Vec::new(),
+                cross_device: false,
+                created_dirs: Vec::new(),
+                created_files: Vec::new(),
+                trashed: Vec::new(),
+            });
+            continue;
+        }
+        results.push(engine.rename_file(file_rename).await);
+    }
+    Ok(results)
+}
+
+/// The Jellyfin season folder for `season` inside `parent`, e.g. `Season 01`,
+/// or `Specials` for season 0. A `parent` that already is that season's folder
+/// is returned unchanged.
+pub fn season_folder_path(parent: &Path, season: u32) -> PathBuf {
+    let already_there = parent
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(extract_season_from_directory)
+        == Some(season);
+    if already_there {
+        parent.to_path_buf()
+    } else if season == 0 {
+        parent.join("Specials")
+    } else {
+        parent.join(format!("Season {:02}", season))
+    }
+}
+
+/// Creates `dir` and any missing parents, returning the ones that were created, outermost first.
+pub fn create_missing_dirs(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut missing: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+        .map(Path::to_path_buf)
+        .collect();
+    missing.reverse();
+    if !missing.is_empty() {
+        fs::create_dir_all(dir)?;
+    }
+    Ok(missing)
+}
+
+/// Removes directories created by [`create_missing_dirs`], innermost first.
+/// Any that are no longer empty are left alone.
+pub fn remove_empty_dirs(created_dirs: &[PathBuf]) {
+    for dir in created_dirs.iter().rev() {
+        if fs::remove_dir(dir).is_err() {
+            debug!(dir = %dir.display(), "left folder in place");
+        }
+    }
+}
+
+/// Moves `from` to `to`, falling back to copy-then-delete when they are on
+/// different filesystems. Returns `true` when the fallback was used.
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<bool> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(false),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            debug!(from = %from.display(), to = %to.display(), "cross-device rename, copying instead");
+            copy_then_delete(from, to)?;
+            Ok(true)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Puts back the most recently trashed file that used to live at `original`,
+/// as recorded in [`RenameResult::trashed`]. Fails if something is in its way.
+#[cfg(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+))]
+pub fn restore_from_trash(original: &Path) -> std::io::Result<()> {
+    let items = trash::os_limited::list().map_err(std::io::Error::other)?;
+    let item = items
+        .into_iter()
+        .filter(|item| item.original_path() == original)
+        .max_by_key(|item| item.time_deleted)
+        .ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} is not in the trash", original.display()),
+        ))?;
+    trash::os_limited::restore_all([item]).map_err(std::io::Error::other)
+}
+
+/// Puts back the most recently trashed file that used to live at `original`.
+/// This platform's trash can't be restored from programmatically.
+#[cfg(not(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+)))]
+pub fn restore_from_trash(original: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::other(format!(
+        "restoring {} from the trash is not supported on this platform",
+        original.display()
+    )))
+}
+
+/// Whether `a` and `b` name the same file, e.g. paths differing only in case on a
+/// case-insensitive filesystem.
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// `Title (2).mkv`, `Title (3).mkv`, ... next to `path`: the first that doesn't exist.
+fn first_free_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|ext| ext.to_string_lossy().to_string());
+
+    (2..)
+        .map(|counter| {
+            let name = match &extension {
+                Some(extension) => assemble_filename(&format!("{} ({})", stem, counter), extension),
+                None => format!("{} ({})", stem, counter),
+            };
+            path.with_file_name(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .expect("some numbered name is free")
+}
+
+/// Copies `from` to `to` and removes `from` only once the whole file has been
+/// copied. A partial copy is removed and the source left untouched.
+pub fn copy_then_delete(from: &Path, to: &Path) -> std::io::Result<()> {
+    let expected = fs::metadata(from)?.len();
+
+    let copied = match fs::copy(from, to) {
+        Ok(copied) => copied,
+        Err(e) => {
+            let _ = fs::remove_file(to);
+            return Err(e);
+        }
+    };
+    let written = fs::metadata(to).map(|metadata| metadata.len()).unwrap_or(0);
+    if copied != expected || written != expected {
+        let _ = fs::remove_file(to);
+        return Err(std::io::Error::other(format!(
+            "Incomplete copy to {}: {} of {} bytes",
+            to.display(),
+            written,
+            expected
+        )));
+    }
+
+    if let Err(e) = fs::remove_file(from) {
+        // Keep a single copy rather than leaving the file in both places
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Sets `path`'s access and modification times to those in `metadata`,
+/// leaving the file alone when they already match. [`copy_then_delete`] and
+/// some network shares reset them, so [`RenameEngine::rename_file`] calls this
+/// after the move when [`RenameConfig::preserve_timestamps`] is set.
+pub fn restore_timestamps(path: &Path, metadata: &fs::Metadata) -> std::io::Result<()> {
+    let atime = FileTime::from_last_access_time(metadata);
+    let mtime = FileTime::from_last_modification_time(metadata);
+
+    let current = fs::metadata(path)?;
+    if FileTime::from_last_access_time(&current) == atime && FileTime::from_last_modification_time(&current) == mtime {
+        return Ok(());
+    }
+    filetime::set_file_times(path, atime, mtime)
+}
+
+pub fn sanitize_filename(filename: &str) -> String {
+    let re = Regex::new(r#"[<>:"/\\|?*,]"#).unwrap();
+    re.replace_all(filename, "_").to_string()
+}
+
+/// Formats `S01E01`, or `S01E01-E02` for a multi-episode file.
+fn format_season_episode(season: u32, episode: u32, episode_end: Option<u32>) -> String {
+    match episode_end {
+        Some(end) => format!("S{:02}E{:02}-E{:02}", season, episode, end),
+        None => format!("S{:02}E{:02}", season, episode),
+    }
+}
+
+/// Returns a rename plan, as produced by [`RenameEngine::scan_directory`], as pretty JSON
+/// for review in other tools.
+pub fn rename_plan_json(renames: &[FileRename]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(renames)?)
+}
+
+/// Maps an absolute episode number onto `(season, episode)` given the episode
+/// count of each season. Numbers past the listed seasons stay in the last one.
+pub fn map_absolute_episode(absolute: u32, episodes_per_season: &[u32]) -> (u32, u32) {
+    let mut remaining = absolute;
+    for (index, &count) in episodes_per_season.iter().enumerate() {
+        let is_last = index + 1 == episodes_per_season.len();
+        if remaining <= count || is_last {
+            return (index as u32 + 1, remaining);
+        }
+        remaining -= count;
+    }
+    (1, absolute)
+}
+
+/// Turns a filename prefix like `Breaking.Bad.` into `Breaking Bad`.
+fn clean_show_name(raw: &str) -> String {
+    let cleaned = raw.replace(['.', '_'], " ");
+    let cleaned = cleaned.trim_end_matches([' ', '-']);
+    sanitize_filename(&cleaned.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Drops scene release tags such as `PROPER`, `REPACK` or `iNTERNAL` from a title,
+/// along with the separator in front of each. Only all-caps spellings count (see
+/// [`release_tag_kind`]), so a title word like "Proper" or "Limited" stays.
+fn strip_scene_tags(title: &str) -> String {
+    let is_scene_tag = |word: &str| release_tag_kind(word) == Some(TagKind::Scene);
+
+    let piece_re = Regex::new(r"[^._\s]+|[._\s]+").unwrap();
+    let mut cleaned = String::with_capacity(title.len());
+    let mut separator = "";
+    for piece in piece_re.find_iter(title).map(|m| m.as_str()) {
+        if piece.starts_with(['.', '_']) || piece.starts_with(char::is_whitespace) {
+            separator = if cleaned.is_empty() { "" } else { piece };
+        } else if is_scene_tag(piece) {
+            separator = "";
+        } else {
+            cleaned.push_str(separator);
+            cleaned.push_str(piece);
+            separator = "";
+        }
+    }
+    cleaned.push_str(separator);
+    cleaned
+}
+
+/// The longest prefix of `text` that is at most `max_bytes` long and ends on a char boundary.
+fn truncate_to_char_boundary(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Joins a filename stem and extension with a single dot.
+///
+/// Trailing dots and spaces are trimmed from the stem, since Windows rejects
+/// names like `Title.` and a dangling dot would otherwise produce `Title..mkv`.
+pub fn assemble_filename(stem: &str, extension: &str) -> String {
+    let stem = stem.trim_end_matches(['.', ' ']);
+    let extension = extension.trim_start_matches('.');
+    format!("{}.{}", stem, extension)
+}
+
+/// Compiles an `--exclude` pattern. Patterns are case-insensitive globs matched
+/// against the whole filename (`*sample*`); prefix with `re:` for a regex instead.
+pub fn compile_exclude_pattern(pattern: &str) -> Result<Regex> {
+    let source = match pattern.strip_prefix("re:") {
+        Some(regex) => format!("(?i){}", regex),
+        None => {
+            let glob = regex::escape(pattern)
+                .replace(r"\*", ".*")
+                .replace(r"\?", ".");
+            format!("(?i)^{}$", glob)
+        }
+    };
+
+    Regex::new(&source).with_context(|| format!("Invalid exclude pattern: {}", pattern))
+}
+
+/// Finds a resolution tag such as `1080p` or `4K` in a filename.
+pub fn extract_resolution_tag(filename: &str) -> Option<String> {
+    let re = Regex::new(r"(?i)(?:^|[\s._\[(-])(?P<res>\d{3,4}p|4k)(?:[\s._\])-]|$)").unwrap();
+    re.captures(filename)
+        .and_then(|captures| captures.name("res"))
+        .map(|res| res.as_str().to_lowercase())
+}
+
+/// Every directory below `root`, depth first and sorted by name. Symlinks are not followed.
+fn collect_subdirectories(root: &Path, skip_hidden: bool) -> Result<Vec<PathBuf>> {
+    let mut children: Vec<PathBuf> = fs::read_dir(root)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+        .filter(|entry| !(skip_hidden && entry.file_name().to_string_lossy().starts_with('.')))
+        .map(|entry| entry.path())
+        .collect();
+    children.sort();
+
+    let mut directories = Vec::new();
+    for child in children {
+        let nested = collect_subdirectories(&child, skip_hidden)?;
+        directories.push(child);
+        directories.extend(nested);
+    }
+    Ok(directories)
+}
+
+pub fn extract_season_from_directory(dir_name: &str) -> Option<u32> {
+    let patterns = [
+        r"s(?:eason\s*)?(\d+)",           
+        r"(?:season\s+)(\d+)",            
+        r"(\d+)(?:st|nd|rd|th)\s*season", 
+        r"series\s*(\d+)",                
+    ];
+    
+    let dir_lower = dir_name.to_lowercase();
+    
+    for pattern in &patterns {
+        if let Ok(re) = regex::Regex::new(pattern) {
+            if let Some(captures) = re.captures(&dir_lower) {
+                if let Some(season_match) = captures.get(1) {
+                    if let Ok(season_num) = season_match.as_str().parse::<u32>() {
+                        return Some(season_num);
+                    }
+                }
+            }
+        }
+    }
+
+    // Jellyfin files specials and extras under season 0
+    let specials = Regex::new(r"\b(?:specials?|extras)\b").unwrap();
+    if specials.is_match(&dir_lower) {
+        return Some(0);
+    }
+    
+    None
+}
+
+/// Removes release tags from the text after an episode marker: anything in square
+/// brackets (`[HorribleSubs]`, `[720p]`, CRCs), parentheses holding a single tag-like
+/// token (`(WEB)`, `(x264)`) and a trailing group glued on with a hyphen (`-RARBG`,
+/// `-NTb`). Parentheses with ordinary words, like `(Part 2)`, are kept, as are
+/// hyphenated words such as `Spider-Man`.
+fn strip_release_tags(suffix: &str) -> String {
+    let brackets = Regex::new(r"\[[^\]]*\]").unwrap();
+    let parens = Regex::new(r"\(([^()]*)\)").unwrap();
+    let group = Regex::new(r"([^\s._\-()\[\]]+)-([A-Za-z0-9]{2,})\s*$").unwrap();
+
+    let without_brackets = brackets.replace_all(suffix, " ");
+    let without_parens = parens.replace_all(&without_brackets, |captures: &regex::Captures| {
+        if is_release_tag(&captures[1]) {
+            " ".to_string()
+        } else {
+            captures[0].to_string()
+        }
+    });
+    let without_group = group.replace(without_parens.trim_end(), |captures: &regex::Captures| {
+        // A known group, or any name hung off a tag as in `x264-FLUX`; the `MEN` in
+        // `X-MEN` or the `Man` in `Spider-Man` is neither
+        if is_release_tag(&captures[2]) || is_release_tag(&captures[1]) {
+            captures[1].to_string()
+        } else {
+            captures[0].to_string()
+        }
+    });
+    without_group.trim().to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TagKind {
+    /// Resolution, source or codec markers such as `1080p` or `x264`.
+    Quality,
+    /// Scene flags such as `PROPER`. These are ordinary words too, so they only
+    /// count when spelled in caps.
+    Scene,
+    /// Release group names.
+    Group,
+}
+
+/// Release tags recognised in filenames, by their lowercase spelling. Shared by
+/// every title cleanup so they agree on what is a tag and what is a title word.
+const RELEASE_TAGS: &[(&str, TagKind)] = &[
+    ("1080p", TagKind::Quality), ("720p", TagKind::Quality), ("480p", TagKind::Quality),
+    ("2160p", TagKind::Quality), ("4k", TagKind::Quality), ("x264", TagKind::Quality),
+    ("x265", TagKind::Quality), ("h264", TagKind::Quality), ("h265", TagKind::Quality),
+    ("hevc", TagKind::Quality), ("xvid", TagKind::Quality), ("web", TagKind::Quality),
+    ("webrip", TagKind::Quality), ("web-dl", TagKind::Quality), ("hdtv", TagKind::Quality),
+    ("bluray", TagKind::Quality), ("dvdrip", TagKind::Quality), ("brrip", TagKind::Quality),
+    ("aac", TagKind::Quality), ("ac3", TagKind::Quality), ("dts", TagKind::Quality),
+    ("proper", TagKind::Scene), ("repack", TagKind::Scene), ("rerip", TagKind::Scene),
+    ("internal", TagKind::Scene), ("limited", TagKind::Scene), ("unrated", TagKind::Scene),
+    ("uncut", TagKind::Scene), ("readnfo", TagKind::Scene), ("dirfix", TagKind::Scene),
+    ("nfofix", TagKind::Scene), ("subbed", TagKind::Scene), ("dubbed", TagKind::Scene),
+    ("rarbg", TagKind::Group), ("ntb", TagKind::Group), ("yify", TagKind::Group),
+    ("ettv", TagKind::Group), ("eztv", TagKind::Group), ("pahe", TagKind::Group),
+    ("horriblesubs", TagKind::Group),
+];
+
+/// Looks `word` up in [`RELEASE_TAGS`]. Scene flags only match all-caps
+/// spellings, plus the scene's own `iNTERNAL`.
+fn release_tag_kind(word: &str) -> Option<TagKind> {
+    let lower = word.to_lowercase();
+    let (_, kind) = RELEASE_TAGS.iter().find(|(tag, _)| *tag == lower)?;
+    let in_caps = word.chars().all(|c| c.is_ascii_uppercase()) || word == "iNTERNAL";
+    (*kind != TagKind::Scene || in_caps).then_some(*kind)
+}
+
+/// A single token that is a known release tag. Unknown all-caps words such as
+/// `USA` or `II` are title text, not tags.
+fn is_release_tag(text: &str) -> bool {
+    let text = text.trim();
+    !text.contains(char::is_whitespace) && release_tag_kind(text).is_some()
+}
+
+/// Splits a movie filename stem into its title, release year and the trailing
+/// junk (quality, codec, group). The year is the last `19xx`/`20xx` token that
+/// isn't the start of the title, so `Blade.Runner.2049.2017` keeps `2049` in the
+/// title and `2012.2009` is the film `2012` from 2009. Without a year the whole
+/// stem is the title and the quality words are cleaned out later.
+fn split_movie_stem(stem: &str) -> (&str, Option<&str>, &str) {
+    let year_re = Regex::new(r"(?:19|20)\d{2}").unwrap();
+    let is_separator = |c: Option<char>| c.is_none_or(|c| !c.is_ascii_alphanumeric());
+
+    let year = year_re
+        .find_iter(stem)
+        .filter(|m| {
+            m.start() > 0
+                && is_separator(stem[..m.start()].chars().next_back())
+                && is_separator(stem[m.end()..].chars().next())
+        })
+        .last();
+
+    match year {
+        Some(year) => {
+            let title = stem[..year.start()].trim_end_matches([' ', '.', '_', '-', '(', '[']);
+            (title, Some(year.as_str()), &stem[year.end()..])
+        }
+        None => (stem, None, ""),
+    }
+}
+
+/// Guesses whether a batch holds TV episodes or movies: TV when at least half of
+/// the video files carry an episode marker, otherwise movies. `None` without videos.
+pub fn detect_file_type<S: AsRef<str>>(filenames: &[S]) -> Option<FileType> {
+    let config = ConfigBuilder::new()
+        .directory(".")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .build()
+        .ok()?;
+    let engine = RenameEngine::new(config).ok()?;
+
+    let videos: Vec<&str> = filenames.iter()
+        .map(AsRef::as_ref)
+        .filter(|filename| engine.is_video_filename(filename))
+        .collect();
+    if videos.is_empty() {
+        return None;
+    }
+
+    let episodes = videos.iter().filter(|filename| engine.is_episode_filename(filename)).count();
+    if episodes > 0 && episodes * 2 >= videos.len() {
+        Some(FileType::TvShow)
+    } else {
+        Some(FileType::Movie)
+    }
+}
+
+pub fn extract_season_from_filename(filename: &str) -> Option<u32> {
+    let patterns = [
+        r"S(\d{1,2})E\d{1,3}",              
+        r"(?:season\s*)?(\d+)x\d{2}",     
+        r"s(\d+)e\d+",                    
+        r"season[\s._-]*(\d+)[\s._-]*episode[\s._-]*\d+",
+    ];
+    
+    let filename_lower = filename.to_lowercase();
+    
+    for pattern in &patterns {
+        if let Ok(re) = regex::Regex::new(pattern) {
+            if let Some(captures) = re.captures(&filename_lower) {
+                if let Some(season_match) = captures.get(1) {
+                    if let Ok(season_num) = season_match.as_str().parse::<u32>() {
+                        return Some(season_num);
+                    }
+                }
+            }
+        }
+    }
+
+    // A special without an episode marker, e.g. `Show.Christmas.Special.mkv`
+    let special = Regex::new(r"\bspecials?\b").unwrap();
+    if special.is_match(&filename_lower) {
+        return Some(0);
+    }
+    
+    None
+}
+
+/// The release year in a movie filename such as `The.Matrix.1999.1080p.mkv`.
+pub fn extract_year_from_filename(filename: &str) -> Option<String> {
+    let stem = Path::new(filename).file_stem()?.to_str()?;
+    split_movie_stem(stem).1.map(str::to_string)
+}
+
+/// A source of episode titles for a single series, indexed by season.
+pub trait EpisodeTitleProvider {
+    fn titles(&self, season: u32) -> impl Future<Output = Result<Vec<String>>> + Send;
+}
+
+/// Where IMDb episode pages are fetched from.
+pub const IMDB_BASE_URL: &str = "https://www.imdb.com";
+
+/// Looks up episode titles for an IMDb series id. The season's IMDb episodes page
+/// is read first; when it can't be fetched or parsed (IMDb often serves a bot
+/// check instead), OMDb is asked with its public demo key.
+#[derive(Debug, Clone)]
+pub struct ImdbProvider {
+    pub imdb_id: String,
+    pub base_url: String,
+    pub attempts: u32,
+}
+
+impl ImdbProvider {
+    pub fn new(imdb_id: impl Into<String>) -> Self {
+        Self { imdb_id: imdb_id.into(), base_url: IMDB_BASE_URL.to_string(), attempts: DEFAULT_FETCH_ATTEMPTS }
+    }
+
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Fetches the episodes page from another host, e.g. a local stub in tests.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+impl EpisodeTitleProvider for ImdbProvider {
+    async fn titles(&self, season: u32) -> Result<Vec<String>> {
+        match fetch_imdb_page_titles(&self.base_url, &self.imdb_id, season, self.attempts).await {
+            Ok(titles) => Ok(titles),
+            Err(e) => {
+                debug!(id = %self.imdb_id, season, error = %e, "IMDb page unusable, asking OMDb");
+                // The "trilogy" key is a publicly usable demo key.
+                fetch_omdb_season(&self.imdb_id, season, "trilogy", self.attempts).await
+            }
+        }
+    }
+}
+
+/// Reads a season's episode titles off its IMDb episodes page with [`parse_imdb_episode_titles`].
+async fn fetch_imdb_page_titles(base_url: &str, imdb_id: &str, season: u32, attempts: u32) -> Result<Vec<String>> {
+    let url = format!("{}/title/{}/episodes/?season={}", base_url.trim_end_matches('/'), imdb_id, season);
+
+    let response = get_with_retry(&url, attempts)
+        .await
+        .context("Failed to fetch the IMDb episodes page")?;
+
+    let html = response.text().await
+        .context("Failed to read the IMDb episodes page")?;
+
+    let titles = parse_imdb_episode_titles(&html);
+    if titles.is_empty() {
+        return Err(anyhow::anyhow!("No episode titles found on the IMDb episodes page"));
+    }
+    Ok(titles)
+}
+
+/// Looks up episode titles through the TMDB API.
+#[derive(Debug, Clone)]
+pub struct TmdbProvider {
+    pub api_key: String,
+    pub series_id: String,
+    pub attempts: u32,
+}
+
+impl TmdbProvider {
+    pub fn new(api_key: impl Into<String>, series_id: impl Into<String>) -> Self {
+        Self { api_key: api_key.into(), series_id: series_id.into(), attempts: DEFAULT_FETCH_ATTEMPTS }
+    }
+
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+}
+
+impl EpisodeTitleProvider for TmdbProvider {
+    async fn titles(&self, season: u32) -> Result<Vec<String>> {
+        let url = format!(
+            "https://api.themoviedb.org/3/tv/{}/season/{}?api_key={}",
+            self.series_id, season, self.api_key
+        );
+
+        let response = get_with_retry(&url, self.attempts)
+            .await
+            .context("Failed to fetch TMDB season data")?;
+
+        let json: serde_json::Value = response.json().await
+            .context("Failed to parse TMDB JSON response")?;
+
+        parse_tmdb_season(&json)
+    }
+}
+
+/// Looks up episode titles for an IMDb series id through OMDb, using your own API key.
+#[derive(Debug, Clone)]
+pub struct OmdbProvider {
+    pub api_key: String,
+    pub imdb_id: String,
+    pub attempts: u32,
+}
+
+impl OmdbProvider {
+    pub fn new(api_key: impl Into<String>, imdb_id: impl Into<String>) -> Self {
+        Self { api_key: api_key.into(), imdb_id: imdb_id.into(), attempts: DEFAULT_FETCH_ATTEMPTS }
+    }
+
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+}
+
+impl EpisodeTitleProvider for OmdbProvider {
+    async fn titles(&self, season: u32) -> Result<Vec<String>> {
+        fetch_omdb_season(&self.imdb_id, season, &self.api_key, self.attempts).await
+    }
+}
+
+/// Extracts episode names from a TMDB season response, laid out by episode number
+/// as [`titles_by_episode`] does.
+pub fn parse_tmdb_season(json: &serde_json::Value) -> Result<Vec<String>> {
+    if let Some(message) = json.get("status_message").and_then(|v| v.as_str()) {
+        return Err(anyhow::anyhow!("TMDB error: {}", message));
+    }
+
+    let episodes = json
+        .get("episodes")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("No episodes field in TMDB response"))?;
+
+    let numbered: Vec<(u64, String)> = episodes
+        .iter()
+        .filter_map(|ep| {
+            let title = ep.get("name")?.as_str()?.to_string();
+            let num = ep.get("episode_number")?.as_u64()?;
+            Some((num, title))
+        })
+        .collect();
+
+    Ok(titles_by_episode(numbered))
+}
+
+/// Places each title at index `episode - 1`, so a season missing an episode leaves
+/// an empty entry instead of shifting every later title onto the wrong file.
+/// Episode 0 and implausibly large numbers are dropped.
+pub fn titles_by_episode(numbered: Vec<(u64, String)>) -> Vec<String> {
+    const MAX_EPISODE: u64 = 2000;
+
+    let mut titles: Vec<String> = Vec::new();
+    for (episode, title) in numbered {
+        if episode == 0 || episode > MAX_EPISODE {
+            continue;
+        }
+        let index = (episode - 1) as usize;
+        if titles.len() <= index {
+            titles.resize(index + 1, String::new());
+        }
+        if titles[index].is_empty() {
+            titles[index] = title;
+        }
+    }
+    titles
+}
+
+/// GETs `url`, retrying timeouts, connection failures and 5xx responses with
+/// exponential backoff. A 404 or other client error fails immediately, since
+/// retrying won't make a missing series or season appear.
+pub async fn get_with_retry(url: &str, attempts: u32) -> Result<reqwest::Response> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?;
+    let attempts = attempts.max(1);
+    let mut delay = RETRY_BASE_DELAY;
+
+    for attempt in 1..=attempts {
+        let last_attempt = attempt == attempts;
+
+        match client.get(url).header("User-Agent", "Mozilla/5.0").send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => {
+                return Err(anyhow::anyhow!("Not found (HTTP 404)"));
+            }
+            Ok(response) if !response.status().is_server_error() || last_attempt => {
+                return Err(anyhow::anyhow!("HTTP error: {}", response.status()));
+            }
+            Err(e) if last_attempt || !(e.is_timeout() || e.is_connect() || e.is_request()) => {
+                return Err(anyhow::anyhow!("Request failed after {} attempt(s): {}", attempt, e));
+            }
+            _ => {}
+        }
+
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+
+    unreachable!("the final attempt always returns")
+}
+
+pub async fn scrape_imdb_episodes(imdb_id: &str, season: Option<u32>) -> Result<Vec<String>> {
+    scrape_imdb_episodes_with_attempts(imdb_id, season, DEFAULT_FETCH_ATTEMPTS).await
+}
+
+pub async fn scrape_imdb_episodes_with_attempts(imdb_id: &str, season: Option<u32>, attempts: u32) -> Result<Vec<String>> {
+    ImdbProvider::new(imdb_id).with_attempts(attempts).titles(season.unwrap_or(1)).await
+}
+
+async fn fetch_omdb_season(imdb_id: &str, season: u32, api_key: &str, attempts: u32) -> Result<Vec<String>> {
+    let url = format!(
+        "https://www.omdbapi.com/?i={}&Season={}&type=series&apikey={}",
+        imdb_id, season, api_key
+    );
+
+    let response = get_with_retry(&url, attempts)
+        .await
+        .context("Failed to fetch OMDb episode data")?;
 
     let json: serde_json::Value = response.json().await
         .context("Failed to parse OMDb JSON response")?;
 
+    parse_omdb_season(&json)
+}
+
+/// Extracts episode titles from an OMDb season response, laid out by episode number
+/// as [`titles_by_episode`] does.
+pub fn parse_omdb_season(json: &serde_json::Value) -> Result<Vec<String>> {
     if json.get("Response").and_then(|v| v.as_str()) == Some("False") {
         let err = json.get("Error").and_then(|v| v.as_str()).unwrap_or("Unknown error");
         return Err(anyhow::anyhow!("OMDb error: {}", err));
@@ -546,8 +2730,7 @@ pub async fn scrape_imdb_episodes(imdb_id: &str, season: Option<u32>) -> Result<
         .and_then(|v| v.as_array())
         .ok_or_else(|| anyhow::anyhow!("No episodes field in OMDb response"))?;
 
-    // Episodes are returned in order; sort by episode number to be safe.
-    let mut numbered: Vec<(u64, String)> = episodes
+    let numbered: Vec<(u64, String)> = episodes
         .iter()
         .filter_map(|ep| {
             let title = ep.get("Title")?.as_str()?.to_string();
@@ -556,9 +2739,123 @@ pub async fn scrape_imdb_episodes(imdb_id: &str, season: Option<u32>) -> Result<
         })
         .collect();
 
-    numbered.sort_by_key(|(n, _)| *n);
+    Ok(titles_by_episode(numbered))
+}
+
+// IMDb's episode page layout drifts over time, so several selectors are tried.
+const IMDB_EPISODE_SELECTORS: [&str; 4] = [
+    "div.ipc-title.ipc-title--base.ipc-title--title .ipc-title__text",
+    ".titleColumn a",
+    ".ipc-title__text",
+    "h3.ipc-title__text",
+];
+
+// Scripts carrying the page's structured data, which outlive visual redesigns.
+const IMDB_STRUCTURED_DATA_SELECTORS: [&str; 2] = [
+    "script#__NEXT_DATA__",
+    r#"script[type="application/ld+json"]"#,
+];
+
+/// Extracts episode titles from an IMDb episodes page.
+///
+/// The embedded `__NEXT_DATA__` and JSON-LD blocks are read first. Only when
+/// they yield nothing is every CSS selector evaluated, keeping the largest
+/// candidate list, so a selector that only partially matches the page can't
+/// hide a better one.
+pub fn parse_imdb_episode_titles(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+
+    let structured = structured_episode_titles(&document);
+    if !structured.is_empty() {
+        return structured;
+    }
+
+    let candidates: Vec<Vec<String>> = IMDB_EPISODE_SELECTORS
+        .iter()
+        .filter_map(|selector_str| Selector::parse(selector_str).ok())
+        .map(|selector| {
+            let titles = document
+                .select(&selector)
+                .filter_map(|element| clean_imdb_title_text(&element.text().collect::<String>()))
+                .collect();
+            dedup_preserving_order(titles)
+        })
+        .filter(|titles: &Vec<String>| !titles.is_empty())
+        .collect();
 
-    Ok(numbered.into_iter().map(|(_, t)| t).collect())
+    select_best_candidate(candidates).unwrap_or_default()
+}
+
+fn structured_episode_titles(document: &Html) -> Vec<String> {
+    for selector in IMDB_STRUCTURED_DATA_SELECTORS.iter().filter_map(|selector| Selector::parse(selector).ok()) {
+        let mut titles = Vec::new();
+        for script in document.select(&selector) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&script.text().collect::<String>()) {
+                collect_episode_titles(&json, &mut titles);
+            }
+        }
+
+        let titles = dedup_preserving_order(titles);
+        if !titles.is_empty() {
+            return titles;
+        }
+    }
+    Vec::new()
+}
+
+/// Walks structured data for episodes: JSON-LD `TVEpisode` objects, or the
+/// `__NEXT_DATA__` items that pair an `episode` number with a `titleText`.
+fn collect_episode_titles(value: &serde_json::Value, titles: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(object) => {
+            let title = if object.get("@type").and_then(|v| v.as_str()) == Some("TVEpisode") {
+                object.get("name")
+            } else if object.contains_key("episode") {
+                object.get("titleText").map(|text| text.get("text").unwrap_or(text))
+            } else {
+                None
+            };
+
+            match title.and_then(|title| title.as_str()).map(str::trim) {
+                Some(title) if !title.is_empty() => titles.push(title.to_string()),
+                _ => object.values().for_each(|value| collect_episode_titles(value, titles)),
+            }
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|item| collect_episode_titles(item, titles)),
+        _ => {}
+    }
+}
+
+fn clean_imdb_title_text(text: &str) -> Option<String> {
+    // Titles are rendered as "S1.E1 ∙ Pilot"; keep only the part after the separator
+    let title = if text.contains('∙') {
+        text.split('∙').next_back()?.trim()
+    } else if !text.contains("S.") {
+        // Filter out episode numbers like "S1.E1"
+        text.trim()
+    } else {
+        return None;
+    };
+
+    if title.is_empty() {
+        None
+    } else {
+        Some(title.to_string())
+    }
+}
+
+/// The largest set wins; ties go to the earlier selector.
+fn select_best_candidate(candidates: Vec<Vec<String>>) -> Option<Vec<String>> {
+    candidates
+        .into_iter()
+        .enumerate()
+        .max_by_key(|(index, titles)| (titles.len(), std::cmp::Reverse(*index)))
+        .map(|(_, titles)| titles)
+}
+
+fn dedup_preserving_order(titles: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    titles.into_iter().filter(|title| seen.insert(title.clone())).collect()
 }
 
 pub struct ConfigBuilder {
@@ -569,6 +2866,49 @@ pub struct ConfigBuilder {
     use_imdb: bool,
     imdb_id: Option<String>,
     file_type: Option<FileType>,
+    pad_part_numbers: bool,
+    disambiguation: DisambiguationStyle,
+    on_conflict: ConflictStrategy,
+    exclude_patterns: Vec<Regex>,
+    apostrophes: SymbolHandling,
+    ampersands: SymbolHandling,
+    casing: Casing,
+    transliterate: bool,
+    normalize_extension_case: bool,
+    dry_run: bool,
+    template: Option<String>,
+    extensions: Option<Vec<String>>,
+    recursive: bool,
+    companion_extensions: Option<Vec<String>>,
+    incomplete_suffixes: Option<Vec<String>>,
+    skip_empty_files: bool,
+    skip_samples: bool,
+    sample_size_limit: u64,
+    skip_hidden_files: bool,
+    title_source: TitleSource,
+    tmdb_api_key: Option<String>,
+    omdb_api_key: Option<String>,
+    no_cache: bool,
+    cache_ttl: std::time::Duration,
+    cache_path: Option<PathBuf>,
+    fetch_attempts: u32,
+    absolute_numbering: bool,
+    episodes_per_season: Vec<u32>,
+    date_based: bool,
+    date_order: DateOrder,
+    preserve_timestamps: bool,
+    max_filename_bytes: usize,
+    show_name: Option<String>,
+    organize_into_folders: bool,
+    write_nfo: bool,
+    custom_pattern: Option<String>,
+    separator: char,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ConfigBuilder {    pub fn new() -> Self {
@@ -580,6 +2920,43 @@ impl ConfigBuilder {    pub fn new() -> Self {
             use_imdb: false,
             imdb_id: None,
             file_type: None,
+            pad_part_numbers: false,
+            disambiguation: DisambiguationStyle::default(),
+            on_conflict: ConflictStrategy::default(),
+            exclude_patterns: Vec::new(),
+            apostrophes: SymbolHandling::default(),
+            ampersands: SymbolHandling::default(),
+            casing: Casing::default(),
+            transliterate: false,
+            normalize_extension_case: true,
+            dry_run: false,
+            template: None,
+            extensions: None,
+            recursive: false,
+            companion_extensions: None,
+            incomplete_suffixes: None,
+            skip_empty_files: true,
+            skip_samples: true,
+            sample_size_limit: SAMPLE_SIZE_LIMIT,
+            skip_hidden_files: true,
+            title_source: TitleSource::default(),
+            tmdb_api_key: None,
+            omdb_api_key: None,
+            no_cache: false,
+            cache_ttl: DEFAULT_CACHE_TTL,
+            cache_path: None,
+            fetch_attempts: DEFAULT_FETCH_ATTEMPTS,
+            absolute_numbering: false,
+            episodes_per_season: Vec::new(),
+            date_based: false,
+            date_order: DateOrder::default(),
+            preserve_timestamps: true,
+            max_filename_bytes: DEFAULT_MAX_FILENAME_BYTES,
+            show_name: None,
+            organize_into_folders: false,
+            write_nfo: false,
+            custom_pattern: None,
+            separator: '_',
         }
     }
 
@@ -611,12 +2988,271 @@ impl ConfigBuilder {    pub fn new() -> Self {
     pub fn file_type(mut self, file_type: FileType) -> Self {
         self.file_type = Some(file_type);
         self
-    }    pub fn build(self) -> Result<RenameConfig> {
+    }
+
+    /// Zero-pads movie part numbers (`-part01` instead of `-part1`).
+    pub fn pad_part_numbers(mut self, pad: bool) -> Self {
+        self.pad_part_numbers = pad;
+        self
+    }
+
+    /// Chooses how colliding target names are disambiguated.
+    pub fn disambiguation(mut self, style: DisambiguationStyle) -> Self {
+        self.disambiguation = style;
+        self
+    }
+
+    /// Chooses what happens when a target already exists on disk. Skips by default.
+    pub fn on_conflict(mut self, strategy: ConflictStrategy) -> Self {
+        self.on_conflict = strategy;
+        self
+    }
+
+    /// Skips files whose names match any of these patterns during scans.
+    pub fn exclude_patterns(mut self, patterns: Vec<Regex>) -> Self {
+        self.exclude_patterns = patterns;
+        self
+    }
+
+    /// Controls apostrophes in titles (`Cat's`). Kept by default.
+    pub fn apostrophes(mut self, handling: SymbolHandling) -> Self {
+        self.apostrophes = handling;
+        self
+    }
+
+    /// Controls ampersands in titles (`Law & Order`). Kept by default.
+    pub fn ampersands(mut self, handling: SymbolHandling) -> Self {
+        self.ampersands = handling;
+        self
+    }
+
+    /// Controls how titles taken from filenames are capitalized. Title Case by default.
+    pub fn casing(mut self, casing: Casing) -> Self {
+        self.casing = casing;
+        self
+    }
+
+    /// Transliterates titles to ASCII, for filesystems and players that mishandle
+    /// accents and smart punctuation. Off by default; titles are always NFC-normalized.
+    pub fn transliterate(mut self, transliterate: bool) -> Self {
+        self.transliterate = transliterate;
+        self
+    }
+
+    /// Lowercases extensions in new names (`.MKV` to `.mkv`). On by default; when
+    /// off, the extension keeps the case it came in with.
+    pub fn normalize_extension_case(mut self, normalize: bool) -> Self {
+        self.normalize_extension_case = normalize;
+        self
+    }
+
+    /// Reports renames as successful without touching the filesystem.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets the output naming template, e.g. `{show} - S{season}E{episode} - {title}.{ext}`.
+    pub fn template(mut self, template: String) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Overrides the accepted video extensions (defaults to [`VIDEO_EXTENSIONS`]).
+    pub fn extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = Some(extensions);
+        self
+    }
+
+    /// Also scans subdirectories, e.g. `Season 01/` and `Season 02/` under a show root.
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Overrides which sidecar extensions follow a video's rename (defaults to
+    /// [`COMPANION_EXTENSIONS`]). An empty list disables companion renaming.
+    pub fn companion_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.companion_extensions = Some(extensions);
+        self
+    }
+
+    /// Overrides which suffixes mark an unfinished download (defaults to
+    /// [`INCOMPLETE_SUFFIXES`]). An empty list scans such files like any other.
+    pub fn incomplete_suffixes(mut self, suffixes: Vec<String>) -> Self {
+        self.incomplete_suffixes = Some(suffixes);
+        self
+    }
+
+    /// Whether zero-byte files are left out of scans. On by default.
+    pub fn skip_empty_files(mut self, skip: bool) -> Self {
+        self.skip_empty_files = skip;
+        self
+    }
+
+    /// Whether sample clips such as `show-sample.mkv` are left out of scans. On by default.
+    pub fn skip_samples(mut self, skip: bool) -> Self {
+        self.skip_samples = skip;
+        self
+    }
+
+    /// Size in bytes from which a file named like a sample is scanned anyway, so a film
+    /// with "sample" in its title is not mistaken for one (defaults to [`SAMPLE_SIZE_LIMIT`]).
+    pub fn sample_size_limit(mut self, bytes: u64) -> Self {
+        self.sample_size_limit = bytes;
+        self
+    }
+
+    /// Whether dotfiles and dot-directories are left out of scans. On by default.
+    pub fn skip_hidden_files(mut self, skip: bool) -> Self {
+        self.skip_hidden_files = skip;
+        self
+    }
+
+    /// Chooses where episode titles come from. The id passed to [`Self::imdb`] is
+    /// interpreted as a series id for that source.
+    pub fn title_source(mut self, source: TitleSource) -> Self {
+        self.title_source = source;
+        self
+    }
+
+    pub fn tmdb_api_key(mut self, api_key: Option<String>) -> Self {
+        self.tmdb_api_key = api_key;
+        self
+    }
+
+    /// Your own OMDb key, used by [`TitleSource::Omdb`].
+    pub fn omdb_api_key(mut self, api_key: Option<String>) -> Self {
+        self.omdb_api_key = api_key;
+        self
+    }
+
+    /// Always fetch episode titles from the network, e.g. after they were corrected upstream.
+    pub fn no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+
+    /// How long cached episode titles are reused (defaults to [`DEFAULT_CACHE_TTL`]).
+    pub fn cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Where the title cache lives (defaults to [`TitleCache::default_path`]).
+    pub fn cache_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.cache_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Attempts per episode-title request (defaults to [`DEFAULT_FETCH_ATTEMPTS`]).
+    pub fn fetch_attempts(mut self, attempts: u32) -> Self {
+        self.fetch_attempts = attempts.max(1);
+        self
+    }
+
+    /// Falls back to bare episode numbers (`Show - 135.mkv`) for TV shows.
+    pub fn absolute_numbering(mut self, enabled: bool) -> Self {
+        self.absolute_numbering = enabled;
+        self
+    }
+
+    /// Episode counts per season for mapping absolute numbers, e.g. `[24, 24, 12]`.
+    /// Seasons with no episodes are ignored.
+    pub fn episodes_per_season(mut self, counts: Vec<u32>) -> Self {
+        self.episodes_per_season = counts.into_iter().filter(|&count| count > 0).collect();
+        self
+    }
+
+    /// Falls back to air dates (`Show.2023.11.05.mkv`) for TV shows.
+    pub fn date_based(mut self, enabled: bool) -> Self {
+        self.date_based = enabled;
+        self
+    }
+
+    /// How year-last dates are read (defaults to [`DateOrder::DayFirst`]).
+    pub fn date_order(mut self, order: DateOrder) -> Self {
+        self.date_order = order;
+        self
+    }
+
+    /// Keeps each file's access and modification times across the rename. On by default.
+    pub fn preserve_timestamps(mut self, enabled: bool) -> Self {
+        self.preserve_timestamps = enabled;
+        self
+    }
+
+    /// Caps the length of generated filenames (defaults to [`DEFAULT_MAX_FILENAME_BYTES`]).
+    pub fn max_filename_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_filename_bytes = max_bytes;
+        self
+    }
+
+    /// Prefixes episode names with the series name, e.g. `Breaking_Bad_S01E01_Pilot.mkv`.
+    /// A blank name is ignored.
+    pub fn show_name(mut self, show_name: Option<String>) -> Self {
+        self.show_name = show_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        self
+    }
+
+    /// Moves episodes into `Season NN` folders, e.g. `Show/Season 01/...`. Off by default,
+    /// so files are renamed where they are.
+    pub fn organize_into_folders(mut self, organize: bool) -> Self {
+        self.organize_into_folders = organize;
+        self
+    }
+
+    /// Writes minimal `.nfo` metadata after each rename so Jellyfin has titles without
+    /// looking them up. Off by default.
+    pub fn write_nfo(mut self, write_nfo: bool) -> Self {
+        self.write_nfo = write_nfo;
+        self
+    }
+
+    /// A regex with named groups `title`, `season`, `episode` and `extension` (and
+    /// optionally `show`), tried before the built-in episode patterns. Blank clears it.
+    pub fn custom_pattern(mut self, pattern: String) -> Self {
+        self.custom_pattern = Some(pattern).filter(|pattern| !pattern.trim().is_empty());
+        self
+    }
+
+    /// The character put between words in the built-in formats, e.g. `' '` for
+    /// `The Matrix (1999).mkv` or `'.'` for `The.Matrix.(1999).mkv`. Defaults to `'_'`;
+    /// templates keep their own separators.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+    pub fn build(self) -> Result<RenameConfig> {
         let directory = self.directory
             .ok_or_else(|| anyhow::anyhow!("Directory is required"))?;
         
         let file_type = self.file_type
             .ok_or_else(|| anyhow::anyhow!("File type is required"))?;
+
+        if let Some(template) = &self.template {
+            validate_template(template)?;
+        }
+        if let Some(pattern) = &self.custom_pattern {
+            compile_custom_pattern(pattern)?;
+        }
+        if self.separator.is_control() || sanitize_filename(&self.separator.to_string()) != self.separator.to_string() {
+            return Err(anyhow::anyhow!("Separator {:?} can't be used in filenames", self.separator));
+        }
+
+        let extensions: Vec<String> = match self.extensions {
+            Some(extensions) => extensions
+                .into_iter()
+                .map(|ext| ext.trim_start_matches('.').to_string())
+                .filter(|ext| !ext.is_empty())
+                .collect(),
+            None => VIDEO_EXTENSIONS.iter().map(|ext| ext.to_string()).collect(),
+        };
+        if extensions.is_empty() {
+            return Err(anyhow::anyhow!("At least one video extension is required"));
+        }
           let (season, season_num) = if file_type == FileType::TvShow {
             let season = self.season
                 .ok_or_else(|| anyhow::anyhow!("Season is required for TV shows"))?;
@@ -624,7 +3260,11 @@ impl ConfigBuilder {    pub fn new() -> Self {
                 .ok_or_else(|| anyhow::anyhow!("Season number is required for TV shows"))?;
             (season, season_num)
         } else {
-            (String::from("S01"), 1)
+            // Auto mode uses a season when given; episode filenames carry their own anyway
+            match (self.season, self.season_num) {
+                (Some(season), Some(season_num)) => (season, season_num),
+                _ => (String::from("S01"), 1),
+            }
         };
 
         Ok(RenameConfig {
@@ -635,6 +3275,47 @@ impl ConfigBuilder {    pub fn new() -> Self {
             use_imdb: self.use_imdb,
             imdb_id: self.imdb_id,
             file_type,
+            pad_part_numbers: self.pad_part_numbers,
+            disambiguation: self.disambiguation,
+            on_conflict: self.on_conflict,
+            exclude_patterns: self.exclude_patterns,
+            apostrophes: self.apostrophes,
+            ampersands: self.ampersands,
+            casing: self.casing,
+            transliterate: self.transliterate,
+            normalize_extension_case: self.normalize_extension_case,
+            dry_run: self.dry_run,
+            template: self.template,
+            extensions,
+            recursive: self.recursive,
+            companion_extensions: self.companion_extensions
+                .map(|extensions| extensions.into_iter().map(|ext| ext.trim_start_matches('.').to_string()).collect())
+                .unwrap_or_else(|| COMPANION_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()),
+            incomplete_suffixes: self.incomplete_suffixes
+                .map(|suffixes| suffixes.into_iter().map(|suffix| suffix.trim_start_matches('.').to_string()).collect())
+                .unwrap_or_else(|| INCOMPLETE_SUFFIXES.iter().map(|suffix| suffix.to_string()).collect()),
+            skip_empty_files: self.skip_empty_files,
+            skip_samples: self.skip_samples,
+            sample_size_limit: self.sample_size_limit,
+            skip_hidden_files: self.skip_hidden_files,
+            title_source: self.title_source,
+            tmdb_api_key: self.tmdb_api_key,
+            omdb_api_key: self.omdb_api_key,
+            no_cache: self.no_cache,
+            cache_ttl: self.cache_ttl,
+            cache_path: self.cache_path.unwrap_or_else(TitleCache::default_path),
+            fetch_attempts: self.fetch_attempts,
+            absolute_numbering: self.absolute_numbering,
+            episodes_per_season: self.episodes_per_season,
+            date_based: self.date_based,
+            date_order: self.date_order,
+            preserve_timestamps: self.preserve_timestamps,
+            max_filename_bytes: self.max_filename_bytes,
+            show_name: self.show_name,
+            organize_into_folders: self.organize_into_folders,
+            write_nfo: self.write_nfo,
+            custom_pattern: self.custom_pattern,
+            separator: self.separator,
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,134 @@
+//! Per-file outcomes of a run, shared by the TUI and headless mode, and the
+//! post-run summary of what a batch changed, as CSV or Markdown, for record-keeping.
+
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessingStatus {
+    Pending,
+    Processing,
+    Success,
+    Error,
+    Skipped,
+    /// Held back before processing because its target name is already taken;
+    /// `error_message` says by what.
+    Conflict,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessingStats {
+    pub total: usize,
+    pub processed: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// File name used when no report path is configured, placed in the target directory.
+pub const DEFAULT_REPORT_FILE_NAME: &str = "rename-report.csv";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Csv,
+    Markdown,
+}
+
+impl ReportFormat {
+    /// Markdown for `.md` and `.markdown` paths, CSV for anything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("md") | Some("markdown") => ReportFormat::Markdown,
+            _ => ReportFormat::Csv,
+        }
+    }
+}
+
+/// One file's line in the report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportEntry {
+    pub original_name: String,
+    pub new_name: String,
+    pub status: ProcessingStatus,
+    pub error_message: Option<String>,
+}
+
+/// `directory/rename-report.csv`.
+pub fn default_report_path(directory: &Path) -> PathBuf {
+    directory.join(DEFAULT_REPORT_FILE_NAME)
+}
+
+pub fn status_label(status: &ProcessingStatus) -> &'static str {
+    match status {
+        ProcessingStatus::Pending => "Pending",
+        ProcessingStatus::Processing => "Processing",
+        ProcessingStatus::Success => "Success",
+        ProcessingStatus::Error => "Error",
+        ProcessingStatus::Skipped => "Skipped",
+        ProcessingStatus::Conflict => "Conflict",
+    }
+}
+
+/// Renders every entry followed by the run's totals.
+pub fn render_report(entries: &[ReportEntry], stats: &ProcessingStats, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Csv => render_csv(entries, stats),
+        ReportFormat::Markdown => render_markdown(entries, stats),
+    }
+}
+
+/// Writes the report to `path`, in the format its extension asks for.
+pub fn write_report(path: &Path, entries: &[ReportEntry], stats: &ProcessingStats) -> std::io::Result<()> {
+    std::fs::write(path, render_report(entries, stats, ReportFormat::from_path(path)))
+}
+
+fn render_csv(entries: &[ReportEntry], stats: &ProcessingStats) -> String {
+    let mut csv = String::from("original_name,new_name,status,error_message\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&entry.original_name),
+            csv_field(&entry.new_name),
+            status_label(&entry.status),
+            csv_field(entry.error_message.as_deref().unwrap_or_default()),
+        ));
+    }
+    // Totals follow as their own small table after a blank line
+    csv.push_str("\ntotal,processed,successful,failed,skipped\n");
+    csv.push_str(&format!(
+        "{},{},{},{},{}\n",
+        stats.total, stats.processed, stats.successful, stats.failed, stats.skipped
+    ));
+    csv
+}
+
+fn render_markdown(entries: &[ReportEntry], stats: &ProcessingStats) -> String {
+    let mut markdown = String::from("# Rename report\n\n| Original name | New name | Status | Error |\n| --- | --- | --- | --- |\n");
+    for entry in entries {
+        markdown.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            markdown_cell(&entry.original_name),
+            markdown_cell(&entry.new_name),
+            status_label(&entry.status),
+            markdown_cell(entry.error_message.as_deref().unwrap_or_default()),
+        ));
+    }
+    markdown.push_str(&format!(
+        "\n**Totals:** {} files, {} processed, {} successful, {} failed, {} skipped\n",
+        stats.total, stats.processed, stats.successful, stats.failed, stats.skipped
+    ));
+    markdown
+}
+
+/// Quotes a field containing a comma, quote or line break, doubling inner quotes.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Keeps a value on one table row: pipes are escaped and line breaks become spaces.
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
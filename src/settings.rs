@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the settings file looked up in the working directory and the user's config directory.
+pub const SETTINGS_FILE_NAME: &str = "jellyfin-renamer.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileTypeSetting {
+    Tv,
+    Movie,
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderSetting {
+    None,
+    Imdb,
+    Tmdb,
+    Omdb,
+}
+
+/// Defaults read from `jellyfin-renamer.toml`. Every key is optional; the
+/// command line and anything entered in the TUI take precedence.
+///
+/// ```toml
+/// directory = "/media/shows/Incoming"
+/// file_type = "tv"
+/// template = "{show} - S{season}E{episode} - {title}.{ext}"
+/// provider = "tmdb"
+/// tmdb_api_key = "..."
+/// processing_delay_ms = 0
+/// write_report = true
+/// incomplete_suffixes = ["part", "!qB", "tmp"]
+/// report_path = "/var/log/jellyfin-renamer/report.md"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub directory: Option<String>,
+    pub file_type: Option<FileTypeSetting>,
+    pub template: Option<String>,
+    pub provider: Option<ProviderSetting>,
+    pub imdb_id: Option<String>,
+    pub tmdb_api_key: Option<String>,
+    pub omdb_api_key: Option<String>,
+    pub processing_delay_ms: Option<u64>,
+    /// Write a summary report after each run.
+    pub write_report: Option<bool>,
+    /// Where the report goes; `.md` selects Markdown, anything else CSV.
+    pub report_path: Option<String>,
+    /// Suffixes marking downloads still in progress, replacing the built-in list.
+    pub incomplete_suffixes: Option<Vec<String>>,
+}
+
+impl Settings {
+    /// Loads the first settings file found, falling back to built-in defaults
+    /// when none exists or the one found cannot be parsed.
+    pub fn load() -> Self {
+        Self::search_paths()
+            .iter()
+            .find(|path| path.is_file())
+            .and_then(|path| Self::from_path(path))
+            .unwrap_or_default()
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        toml::from_str(&contents).ok()
+    }
+
+    /// The working directory first, then the platform config directory.
+    pub fn search_paths() -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from(SETTINGS_FILE_NAME)];
+        if let Some(config_dir) = config_dir() {
+            paths.push(config_dir.join("jellyfin-renamer").join(SETTINGS_FILE_NAME));
+        }
+        paths
+    }
+}
+
+/// `%APPDATA%` on Windows, otherwise `$XDG_CONFIG_HOME` or `~/.config`.
+fn config_dir() -> Option<PathBuf> {
+    let from_env = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+
+    if cfg!(windows) {
+        from_env("APPDATA")
+    } else {
+        from_env("XDG_CONFIG_HOME").or_else(|| from_env("HOME").map(|home| home.join(".config")))
+    }
+}
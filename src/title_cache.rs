@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// How long cached episode titles are trusted by default.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: u64,
+    titles: Vec<String>,
+}
+
+/// Episode titles stored on disk as JSON, keyed by series id and season.
+///
+/// Any problem reading the file (missing, corrupt, unreadable) is treated as a
+/// cache miss so callers fall back to the network.
+#[derive(Debug, Clone)]
+pub struct TitleCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl TitleCache {
+    pub fn new(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self { path: path.into(), ttl }
+    }
+
+    /// The cache file shared by every run, under the system temp directory.
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("jellyfin_rename").join("title_cache.json")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn key(source: &str, series_id: &str, season: u32) -> String {
+        format!("{}:{}:{}", source, series_id, season)
+    }
+
+    fn read_entries(&self) -> HashMap<String, CacheEntry> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns cached titles that are younger than the TTL.
+    pub fn load(&self, source: &str, series_id: &str, season: u32) -> Option<Vec<String>> {
+        let entry = self.read_entries().remove(&Self::key(source, series_id, season))?;
+        let age = now_secs().saturating_sub(entry.fetched_at);
+        if age > self.ttl.as_secs() || entry.titles.is_empty() {
+            return None;
+        }
+        Some(entry.titles)
+    }
+
+    pub fn store(&self, source: &str, series_id: &str, season: u32, titles: &[String]) -> Result<()> {
+        let mut entries = self.read_entries();
+        entries.insert(Self::key(source, series_id, season), CacheEntry {
+            fetched_at: now_secs(),
+            titles: titles.to_vec(),
+        });
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
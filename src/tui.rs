@@ -1,7 +1,10 @@
 pub mod app;
 pub mod events;
+pub mod keymap;
+pub mod last_run;
 pub mod models;
 pub mod rendering;
+pub mod undo_log;
 pub mod utils;
 
 pub use events::run_tui;
\ No newline at end of file
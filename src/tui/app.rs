@@ -1,15 +1,38 @@
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
-use std::fs;
+use regex::Regex;
 use ratatui::widgets::{ListState, ScrollbarState};
 use crossterm::event::KeyCode;
 use tokio;
 
 use crate::rename_engine::{
-    RenameEngine, FileRename, ConfigBuilder, 
-    extract_season_from_directory, extract_season_from_filename, FileType
+    RenameEngine, FileRename, MatchKind, ConfigBuilder, EpisodeTitleProvider, ImdbProvider, TmdbProvider, OmdbProvider, TitleSource, RenameOutcome,
+    detect_file_type, extract_season_from_directory, extract_season_from_filename, extract_year_from_filename, compile_custom_pattern, move_file, remove_empty_dirs, restore_from_trash, season_folder_path, FileType
 };
-use super::models::{FileItem, ProcessingStatus, ConfigInputMode, ProcessingStats, UndoOperation};
+use crate::error::RenameError;
+use crate::settings::{FileTypeSetting, ProviderSetting, Settings};
+use super::models::{FileItem, ConfigInputMode, UndoOperation, NameEditor, RenameDialog, SortMode, COLLISION_MESSAGE};
+use super::keymap::{confirm_action, Action};
+use super::last_run::LastRun;
+use crate::report::{default_report_path, write_report, ProcessingStats, ProcessingStatus, ReportEntry};
+use super::undo_log::{next_batch_key, UndoBatch, UndoLog};
+use crate::paths::expand_path;
+use super::utils::{file_manager_command, open_in_file_manager, Clipboard};
+
+/// How long typing has to pause before the previews are rebuilt.
+pub const REFRESH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A run begun by [`App::start_processing`]: the files it covers, how far it has
+/// got, and what to report once it finishes.
+#[derive(Debug, Default)]
+struct ProcessingRun {
+    targets: Vec<usize>,
+    position: usize,
+    held_back: usize,
+    cross_device_copies: usize,
+    undo_log_error: Option<String>,
+}
 
 #[derive(Debug)]
 pub struct App {
@@ -18,26 +41,87 @@ pub struct App {
     pub list_state: ListState,
     pub current_processing: Option<usize>,
     pub processing_progress: f64,
+    processing_run: Option<ProcessingRun>,
     pub show_help: bool,
     pub show_preview: bool,
     pub show_config: bool,
     pub config_input_mode: ConfigInputMode,
     pub scroll_state: ScrollbarState,
     pub start_time: Option<Instant>,
+    /// When the last batch finished, so the elapsed time stops counting.
+    pub finish_time: Option<Instant>,
     pub finished: bool,
     pub stats: ProcessingStats,
     pub rename_engine: Option<RenameEngine>,
     pub directory_input: String,
+    /// Why the typed directory was refused; keeps the user on the Directory step.
+    pub directory_error: Option<String>,
     pub season_input: String,
     pub year_input: String,    pub movie_years: Vec<String>,
     pub current_movie_index: usize,
     pub imdb_id_input: String,
     pub use_imdb: bool,
+    pub title_source: TitleSource,
+    pub tmdb_api_key_input: String,
+    pub omdb_api_key_input: String,
+    /// Series name put in front of episode names; blank leaves it out.
+    pub show_name_input: String,
+    /// Regex tried before the built-in episode patterns; blank uses only the built-ins.
+    pub custom_pattern_input: String,
     pub undo_operations: Vec<UndoOperation>,
+    pub redo_operations: Vec<UndoOperation>,
+    pub undo_log: UndoLog,
+    pub undo_batch_created_at: u64,
     pub needs_refresh: bool,
+    /// When the pending refresh was last asked for; it runs once input pauses.
+    pub refresh_requested_at: Option<Instant>,
+    /// Quiet period after the last edit before previews are rebuilt.
+    pub refresh_debounce: Duration,
     pub status_message: Option<String>,
     pub status_message_time: Option<Instant>,
     pub file_type: FileType,
+    pub use_per_file_seasons: bool,
+    pub exclude_patterns: Vec<Regex>,
+    pub dry_run: bool,
+    /// Move episodes into `Season NN` folders instead of renaming them in place.
+    pub organize_into_folders: bool,
+    /// Write `.nfo` metadata files after each rename.
+    pub write_nfo: bool,
+    /// Word separator for the built-in name formats, cycled with W on the Confirm step.
+    pub separator: char,
+    /// Write a summary of each run's renames, toggled with R on the Confirm step.
+    pub write_report: bool,
+    /// Where the summary goes; `None` puts `rename-report.csv` in the target directory.
+    pub report_path: Option<PathBuf>,
+    /// Unfinished-download suffixes from the settings file; `None` keeps the engine's defaults.
+    pub incomplete_suffixes: Option<Vec<String>>,
+    pub filter: String,
+    pub filter_editing: bool,
+    pub name_edit: Option<NameEditor>,
+    /// Quick rename offered when a single file is opened, before the guided setup.
+    pub rename_dialog: Option<RenameDialog>,
+    /// Season being typed after pressing S on the file list, applied to every file on Enter.
+    pub season_edit: Option<String>,
+    pub sort_mode: SortMode,
+    /// Naming template passed to the engine; `None` keeps the built-in formats.
+    pub template: Option<String>,
+    /// Pause between renames; zero for real runs, opt-in for demos and screenshots.
+    pub processing_delay: Duration,
+    /// Error shown in a popup until the next key press.
+    pub error_popup: Option<String>,
+    /// Asking the user to confirm quitting with work outstanding.
+    pub confirm_quit: bool,
+    /// Asking the user to confirm starting a run from the file list.
+    pub confirm_process: bool,
+    pub clipboard: Clipboard,
+    /// File type guessed from the filenames, pre-selected on the first config step.
+    pub detected_file_type: Option<FileType>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl App {
@@ -51,33 +135,69 @@ impl App {
             list_state,
             current_processing: None,
             processing_progress: 0.0,
+            processing_run: None,
             show_help: false,
             show_preview: true,
             show_config: true,
             config_input_mode: ConfigInputMode::FileType,
             scroll_state: ScrollbarState::default(),
             start_time: None,
+            finish_time: None,
             finished: false,
             stats: ProcessingStats::default(),
             rename_engine: None,
             directory_input: String::new(),
+            directory_error: None,
             season_input: String::new(),
             year_input: String::new(),
             movie_years: Vec::new(),
             current_movie_index: 0,
             imdb_id_input: String::new(),
             use_imdb: false,
+            title_source: TitleSource::default(),
+            tmdb_api_key_input: String::new(),
+            omdb_api_key_input: String::new(),
+            show_name_input: String::new(),
+            custom_pattern_input: String::new(),
             undo_operations: Vec::new(),
+            redo_operations: Vec::new(),
+            undo_log: UndoLog::new(UndoLog::default_path()),
+            undo_batch_created_at: 0,
             needs_refresh: false,
+            refresh_requested_at: None,
+            refresh_debounce: REFRESH_DEBOUNCE,
             status_message: None,
             status_message_time: None,
             file_type: FileType::TvShow, // Default to TV shows
+            use_per_file_seasons: false,
+            exclude_patterns: Vec::new(),
+            dry_run: false,
+            organize_into_folders: false,
+            write_nfo: false,
+            separator: '_',
+            write_report: false,
+            report_path: None,
+            incomplete_suffixes: None,
+            filter: String::new(),
+            filter_editing: false,
+            name_edit: None,
+            rename_dialog: None,
+            season_edit: None,
+            sort_mode: SortMode::default(),
+            template: None,
+            processing_delay: Duration::ZERO,
+            error_popup: None,
+            confirm_quit: false,
+            confirm_process: false,
+            clipboard: Clipboard::default(),
+            detected_file_type: None,
         }
     }
 
     pub fn with_directory(directory: String) -> Self {
         let mut app = Self::new();
         app.directory_input = directory.clone();
+        app.detect_file_type();
         
         // Don't auto-detect season until file type is selected
         // Season detection will happen when user selects TV shows
@@ -112,7 +232,12 @@ impl App {
                         status: ProcessingStatus::Pending,
                         error_message: None,
                         episode_number: 0,
+                        season_number: extract_season_from_filename(filename).unwrap_or(1),
                         episode_title: String::new(),
+                        provider_title: None,
+                        match_kind: MatchKind::Unmatched,
+                        checked: false,
+                        manually_edited: false,
                     });
                 }
             }        }
@@ -128,11 +253,12 @@ impl App {
         if !app.files.is_empty() {
             app.config_input_mode = ConfigInputMode::FileType;
         }
+        app.detect_file_type();
         
         app
     }
 
-    pub async fn scan_directory(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn scan_directory(&mut self) -> Result<(), RenameError> {
         if let Some(engine) = &self.rename_engine {
             let file_renames = engine.scan_directory()?;
             self.files = file_renames.into_iter().map(|fr| FileItem {
@@ -142,25 +268,159 @@ impl App {
                 status: if fr.needs_rename { ProcessingStatus::Pending } else { ProcessingStatus::Skipped },
                 error_message: None,
                 episode_number: fr.episode_number,
+                season_number: fr.season_number,
                 episode_title: fr.episode_title.clone(),
+                provider_title: engine.provider_title(fr.episode_number).map(|_| fr.episode_title.clone()),
+                match_kind: fr.match_kind,
+                checked: false,
+                manually_edited: false,
             }).collect();
 
             self.stats = ProcessingStats {
                 total: self.files.len(),
                 ..Default::default()
             };
+            self.detect_collisions();
 
             if !self.files.is_empty() {
-                self.list_state.select(Some(0));
+                self.select_file(0);
                 self.show_config = false;
             }
         }
         Ok(())
     }
 
-    pub async fn create_rename_engine(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Fills in defaults from a settings file. Values already set, such as a
+    /// directory given on the command line, are left alone.
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        if self.directory_input.is_empty() {
+            if let Some(directory) = &settings.directory {
+                self.directory_input = directory.clone();
+            }
+        }
+
+        // A configured file type wins over the guess from the filenames
+        if let Some(file_type) = settings.file_type {
+            self.set_file_type_setting(file_type);
+        }
+
+        if self.template.is_none() {
+            self.template = settings.template.clone();
+        }
+
+        if let Some(provider) = settings.provider {
+            self.set_provider(provider);
+        }
+
+        if self.imdb_id_input.is_empty() {
+            if let Some(imdb_id) = &settings.imdb_id {
+                self.imdb_id_input = imdb_id.clone();
+            }
+        }
+        if self.tmdb_api_key_input.is_empty() {
+            if let Some(key) = &settings.tmdb_api_key {
+                self.tmdb_api_key_input = key.clone();
+            }
+        }
+        if self.omdb_api_key_input.is_empty() {
+            if let Some(key) = &settings.omdb_api_key {
+                self.omdb_api_key_input = key.clone();
+            }
+        }
+
+        if let Some(write_report) = settings.write_report {
+            self.write_report = write_report;
+        }
+        if self.report_path.is_none() {
+            self.report_path = settings.report_path.as_ref().map(PathBuf::from);
+        }
+        if settings.incomplete_suffixes.is_some() {
+            self.incomplete_suffixes = settings.incomplete_suffixes.clone();
+        }
+
+        if let Some(delay_ms) = settings.processing_delay_ms {
+            self.processing_delay = Duration::from_millis(delay_ms);
+        }
+    }
+
+    /// Picks up where the previous run left off: its directory, unless one was given or it
+    /// no longer exists, and its file type and title source. Files passed on the command
+    /// line bring their own directory and type, so nothing is restored for them.
+    pub fn apply_last_run(&mut self, last_run: &LastRun) {
+        if !self.files.is_empty() {
+            return;
+        }
+
+        if self.directory_input.is_empty() {
+            if let Some(directory) = last_run.directory.as_ref().filter(|directory| Path::new(directory).is_dir()) {
+                self.directory_input = directory.clone();
+            }
+        }
+        if let Some(file_type) = last_run.file_type {
+            self.set_file_type_setting(file_type);
+        }
+        if let Some(provider) = last_run.provider {
+            self.set_provider(provider);
+        }
+    }
+
+    /// This run's choices for the next launch, once a directory was actually used.
+    pub fn last_run(&self) -> Option<LastRun> {
+        if self.rename_engine.is_none() || !Path::new(&self.directory_input).is_dir() {
+            return None;
+        }
+
+        let file_type = match self.file_type {
+            FileType::TvShow => FileTypeSetting::Tv,
+            FileType::Movie => FileTypeSetting::Movie,
+            FileType::Auto => FileTypeSetting::Auto,
+        };
+        let provider = match (self.use_imdb, &self.title_source) {
+            (false, _) => ProviderSetting::None,
+            (true, TitleSource::Imdb) => ProviderSetting::Imdb,
+            (true, TitleSource::Tmdb) => ProviderSetting::Tmdb,
+            (true, TitleSource::Omdb) => ProviderSetting::Omdb,
+        };
+        Some(LastRun {
+            directory: Some(self.directory_input.clone()),
+            file_type: Some(file_type),
+            provider: Some(provider),
+        })
+    }
+
+    fn set_file_type_setting(&mut self, file_type: FileTypeSetting) {
+        self.file_type = match file_type {
+            FileTypeSetting::Tv => FileType::TvShow,
+            FileTypeSetting::Movie => FileType::Movie,
+            FileTypeSetting::Auto => FileType::Auto,
+        };
+        self.detected_file_type = None;
+    }
+
+    fn set_provider(&mut self, provider: ProviderSetting) {
+        match provider {
+            ProviderSetting::None => self.use_imdb = false,
+            ProviderSetting::Imdb => {
+                self.use_imdb = true;
+                self.title_source = TitleSource::Imdb;
+            }
+            ProviderSetting::Tmdb => {
+                self.use_imdb = true;
+                self.title_source = TitleSource::Tmdb;
+            }
+            ProviderSetting::Omdb => {
+                self.use_imdb = true;
+                self.title_source = TitleSource::Omdb;
+            }
+        }
+    }
+
+    pub async fn create_rename_engine(&mut self) -> Result<(), RenameError> {
+        // A pasted `~/Media/Show` or `$HOME/Media` names the real directory
+        self.directory_input = expand_path(&self.directory_input);
+
         // Ensure season input is properly formatted for TV shows
-        if self.file_type == FileType::TvShow {
+        if self.file_type.has_episodes() {
             if !self.season_input.starts_with('S') && !self.season_input.starts_with('s') {
                 // Convert raw number to S format (e.g., "2" to "S02")
                 if let Ok(season_num) = self.season_input.parse::<u32>() {
@@ -171,10 +431,27 @@ impl App {
         
         let config = ConfigBuilder::new()
             .directory(&self.directory_input)
-            .file_type(self.file_type.clone());
+            .file_type(self.file_type.clone())
+            .exclude_patterns(self.exclude_patterns.clone())
+            .dry_run(self.dry_run)
+            .organize_into_folders(self.organize_into_folders)
+            .write_nfo(self.write_nfo)
+            .separator(self.separator);
+
+        let config = match &self.template {
+            Some(template) => config.template(template.clone()),
+            None => config,
+        };
+
+        let config = match &self.incomplete_suffixes {
+            Some(suffixes) => config.incomplete_suffixes(suffixes.clone()),
+            None => config,
+        };
         
-        let config = if self.file_type == FileType::TvShow {
+        let config = if self.file_type.has_episodes() {
             config.season(self.season_input.clone())
+                .show_name(Some(self.show_name_input.clone()))
+                .custom_pattern(self.custom_pattern_input.clone())
         } else {
             config
         };
@@ -183,63 +460,570 @@ impl App {
         // For multiple movies, we'll handle individual years during processing
         let config = if self.files.len() == 1 { 
             config.year(if self.year_input.is_empty() { None } else { Some(self.year_input.clone()) })
-        } else if self.file_type == FileType::TvShow && !self.year_input.is_empty() { 
+        } else if self.file_type.has_episodes() && !self.year_input.is_empty() { 
             config.year(Some(self.year_input.clone()))
         } else { 
             config.year(None)
         };
         
-        let config = if self.file_type == FileType::TvShow && self.files.len() > 1 && self.use_imdb && !self.imdb_id_input.is_empty() { 
+        let config = if self.file_type.has_episodes() && self.files.len() > 1 && self.use_imdb && !self.imdb_id_input.is_empty() { 
             config.imdb(Some(self.imdb_id_input.clone()))
+                .title_source(self.title_source)
+                .tmdb_api_key(Some(self.tmdb_api_key_input.trim().to_string()).filter(|key| !key.is_empty()))
+                .omdb_api_key(Some(self.omdb_api_key_input.trim().to_string()).filter(|key| !key.is_empty()))
         } else { 
             config.imdb(None)
         };
         
-        let config = config.build()?;
+        let config = config.build().map_err(|e| RenameError::InvalidConfig(e.to_string()))?;
 
-        let mut engine = RenameEngine::new(config)?;
-        if let Some(err_msg) = engine.fetch_imdb_titles().await? {
-            self.set_status_message(format!("IMDb: {}", err_msg));
+        // Refreshes reuse the engine, and with it titles fetched for the same series and season
+        let engine = match &mut self.rename_engine {
+            Some(engine) => {
+                engine.reconfigure(config)?;
+                engine
+            }
+            None => self.rename_engine.insert(RenameEngine::new(config)?),
+        };
+        if let Some(err_msg) = engine.fetch_titles_if_needed().await? {
+            self.set_status_message(RenameError::Scrape(err_msg).to_string());
         }
-        
-        self.rename_engine = Some(engine);
         Ok(())
     }
 
+    pub async fn test_imdb_connection(&mut self) {
+        if self.imdb_id_input.trim().is_empty() {
+            self.set_status_message("Enter a series ID before testing the connection".to_string());
+            return;
+        }
+
+        match self.title_source {
+            TitleSource::Imdb => {
+                let provider = ImdbProvider::new(self.imdb_id_input.trim());
+                self.test_provider_connection(&provider).await;
+            }
+            TitleSource::Tmdb => {
+                if self.tmdb_api_key_input.trim().is_empty() {
+                    self.set_status_message("Enter a TMDB API key before testing the connection".to_string());
+                    return;
+                }
+                let provider = TmdbProvider::new(self.tmdb_api_key_input.trim(), self.imdb_id_input.trim());
+                self.test_provider_connection(&provider).await;
+            }
+            TitleSource::Omdb => {
+                if self.omdb_api_key_input.trim().is_empty() {
+                    self.set_status_message("Enter an OMDb API key before testing the connection".to_string());
+                    return;
+                }
+                let provider = OmdbProvider::new(self.omdb_api_key_input.trim(), self.imdb_id_input.trim());
+                self.test_provider_connection(&provider).await;
+            }
+        }
+    }
+
+    /// Performs a quick fetch with the given provider and reports the outcome
+    /// as a status message, leaving the config flow untouched.
+    pub async fn test_provider_connection<P: EpisodeTitleProvider>(&mut self, provider: &P) {
+        let season_num = self.season_input.trim_start_matches("S").trim_start_matches("s").parse::<u32>().unwrap_or(1);
+
+        let message = match provider.titles(season_num).await {
+            Ok(titles) if !titles.is_empty() => {
+                let found = titles.iter().filter(|title| !title.is_empty()).count();
+                format!("Connection OK: found {} episodes for season {}", found, season_num)
+            }
+            Ok(_) => format!("Connection failed: no episodes found for season {}", season_num),
+            Err(e) => format!("Connection failed: {}", e),
+        };
+
+        self.set_status_message(message);
+    }
+
     pub fn next(&mut self) {
-        if self.files.is_empty() {
+        let visible = self.visible_indices();
+        if visible.is_empty() {
             return;
         }
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i >= self.files.len() - 1 {
+        let position = match self.selected_position(&visible) {
+            Some(position) => {
+                if position >= visible.len() - 1 {
                     0
                 } else {
-                    i + 1
+                    position + 1
                 }
             }
             None => 0,
         };
-        self.list_state.select(Some(i));
-        self.selected_index = i;
+        self.select_file(visible[position]);
     }
 
     pub fn previous(&mut self) {
-        if self.files.is_empty() {
+        let visible = self.visible_indices();
+        if visible.is_empty() {
             return;
         }
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.files.len() - 1
+        let position = match self.selected_position(&visible) {
+            Some(position) => {
+                if position == 0 {
+                    visible.len() - 1
                 } else {
-                    i - 1
+                    position - 1
                 }
             }
             None => 0,
         };
-        self.list_state.select(Some(i));
-        self.selected_index = i;
+        self.select_file(visible[position]);
+    }
+
+    fn select_file(&mut self, index: usize) {
+        self.list_state.select(Some(index));
+        self.selected_index = index;
+        self.sync_scroll_state();
+    }
+
+    /// Points the scrollbar at the selection's place among the files shown.
+    pub fn sync_scroll_state(&mut self) {
+        let visible = self.visible_indices();
+        self.scroll_state = self.scroll_state
+            .content_length(visible.len())
+            .position(self.selected_position(&visible).unwrap_or(0));
+    }
+
+    /// Position of the selected file within `visible`, if it is shown at all.
+    pub fn selected_position(&self, visible: &[usize]) -> Option<usize> {
+        let selected = self.list_state.selected()?;
+        visible.iter().position(|&index| index == selected)
+    }
+
+    /// Indices into `files` that match the filter, case-insensitively on either name.
+    pub fn visible_indices(&self) -> Vec<usize> {
+        let query = self.filter.to_lowercase();
+        self.files.iter()
+            .enumerate()
+            .filter(|(_, file)| {
+                query.is_empty()
+                    || file.original_name.to_lowercase().contains(&query)
+                    || file.new_name.to_lowercase().contains(&query)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn start_filter(&mut self) {
+        self.filter_editing = true;
+    }
+
+    pub fn finish_filter(&mut self) {
+        self.filter_editing = false;
+    }
+
+    pub fn push_filter_char(&mut self, c: char) {
+        self.filter.push(c);
+        self.keep_selection_visible();
+    }
+
+    pub fn pop_filter_char(&mut self) {
+        self.filter.pop();
+        self.keep_selection_visible();
+    }
+
+    /// Drops the filter and shows every file again.
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.filter_editing = false;
+        self.sync_scroll_state();
+    }
+
+    fn keep_selection_visible(&mut self) {
+        let visible = self.visible_indices();
+        if self.selected_position(&visible).is_none() {
+            if let Some(&first) = visible.first() {
+                self.select_file(first);
+                return;
+            }
+        }
+        self.sync_scroll_state();
+    }
+
+    pub fn toggle_checked(&mut self) {
+        if let Some(file) = self.list_state.selected().and_then(|i| self.files.get_mut(i)) {
+            file.checked = !file.checked;
+        }
+    }
+
+    /// Flips the selected file between pending and user-skipped. Files whose name
+    /// already matches stay skipped since there is nothing to rename.
+    pub fn toggle_skipped(&mut self) {
+        if let Some(file) = self.list_state.selected().and_then(|i| self.files.get_mut(i)) {
+            if file.original_name == file.new_name {
+                return;
+            }
+            file.status = match file.status {
+                ProcessingStatus::Pending => ProcessingStatus::Skipped,
+                ProcessingStatus::Skipped => ProcessingStatus::Pending,
+                ref other => other.clone(),
+            };
+        }
+    }
+
+    /// Where `file` will end up, accounting for season folders when they are enabled.
+    pub fn target_path(&self, file: &FileItem) -> String {
+        if !(self.organize_into_folders && self.file_type.has_episodes() && file.episode_number > 0) {
+            return file.target_path();
+        }
+        let parent = Path::new(&file.original_path).parent().unwrap_or(Path::new(""));
+        season_folder_path(parent, file.season_number)
+            .join(&file.new_name)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Copies the selected file's new name, or its full target path, to the clipboard.
+    pub fn copy_selected_name(&mut self, full_path: bool) {
+        let Some(file) = self.list_state.selected().and_then(|i| self.files.get(i)) else { return };
+        let text = if full_path {
+            self.target_path(file)
+        } else {
+            file.new_name.clone()
+        };
+
+        let message = match self.clipboard.set_text(&text) {
+            Ok(()) => format!("Copied {} to the clipboard", text),
+            Err(e) => format!("Could not copy to the clipboard: {}", e),
+        };
+        self.set_status_message(message);
+    }
+
+    /// Pre-selects TV or movies from the selected files, or from the directory's
+    /// contents when no files were passed in. The user can still switch.
+    pub fn detect_file_type(&mut self) {
+        let filenames: Vec<String> = if !self.files.is_empty() {
+            self.files.iter().map(|file| file.original_name.clone()).collect()
+        } else {
+            std::fs::read_dir(&self.directory_input)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| entry.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        self.detected_file_type = detect_file_type(&filenames);
+        if let Some(file_type) = &self.detected_file_type {
+            self.file_type = file_type.clone();
+        }
+    }
+
+    /// Config steps where typed characters belong to a text field rather than shortcuts.
+    pub fn is_text_input_mode(&self) -> bool {
+        self.show_config && matches!(
+            self.config_input_mode,
+            ConfigInputMode::Directory
+                | ConfigInputMode::Season
+                | ConfigInputMode::ShowName
+                | ConfigInputMode::CustomPattern
+                | ConfigInputMode::Year
+                | ConfigInputMode::MovieYears
+                | ConfigInputMode::ImdbId
+                | ConfigInputMode::TmdbApiKey
+                | ConfigInputMode::OmdbApiKey
+        )
+    }
+
+    /// Series name suggested from the directory: the folder itself, or its parent
+    /// when the folder is a season folder such as `Season 02`.
+    pub fn suggested_show_name(&self) -> Option<String> {
+        let directory = Path::new(&self.directory_input);
+        let name = directory.file_name()?.to_str()?;
+        let name = if extract_season_from_directory(name).is_some() {
+            directory.parent()?.file_name()?.to_str()?
+        } else {
+            name
+        };
+        Some(name.to_string()).filter(|name| !name.trim().is_empty())
+    }
+
+    /// Time spent on the current or last batch.
+    pub fn elapsed(&self) -> Option<Duration> {
+        let start = self.start_time?;
+        Some(self.finish_time.unwrap_or_else(Instant::now).duration_since(start))
+    }
+
+    /// Opens the folder holding the selected file in the platform file manager.
+    pub fn open_selected_folder(&mut self) {
+        let Some(file) = self.list_state.selected().and_then(|i| self.files.get(i)) else { return };
+        let Some(directory) = Path::new(&file.original_path).parent().map(Path::to_path_buf) else { return };
+
+        let message = match open_in_file_manager(&directory) {
+            Ok(()) => format!("Opened {}", directory.display()),
+            Err(e) => format!("Could not open the folder with {}: {}", file_manager_command(), e),
+        };
+        self.set_status_message(message);
+    }
+
+    /// Excludes (or includes again) every visible file, so an active filter limits
+    /// the change to the files it shows. Files with nothing to rename are left alone.
+    pub fn set_visible_skipped(&mut self, skipped: bool) {
+        for index in self.visible_indices() {
+            let file = &mut self.files[index];
+            if file.original_name == file.new_name {
+                continue;
+            }
+            file.status = match (&file.status, skipped) {
+                (ProcessingStatus::Pending, true) => ProcessingStatus::Skipped,
+                (ProcessingStatus::Skipped, false) => ProcessingStatus::Pending,
+                (other, _) => other.clone(),
+            };
+        }
+    }
+
+    /// Starts editing the selected file's proposed new name.
+    pub fn start_name_edit(&mut self) {
+        if let Some(index) = self.list_state.selected() {
+            if let Some(file) = self.files.get(index) {
+                self.name_edit = Some(NameEditor::new(index, &file.new_name));
+            }
+        }
+    }
+
+    pub fn cancel_name_edit(&mut self) {
+        self.name_edit = None;
+    }
+
+    /// Applies the edited name and marks the file so refreshes keep it.
+    pub fn confirm_name_edit(&mut self) {
+        let Some(editor) = self.name_edit.take() else { return };
+        let new_name = editor.buffer.trim().to_string();
+
+        if new_name.is_empty() || new_name.contains(['/', '\\']) {
+            self.set_status_message("Invalid name: it must be non-empty and cannot contain path separators".to_string());
+            self.name_edit = Some(editor);
+            return;
+        }
+
+        if let Some(file) = self.files.get_mut(editor.index) {
+            file.new_name = new_name;
+            file.manually_edited = true;
+            file.error_message = None;
+            file.status = if file.original_name == file.new_name {
+                ProcessingStatus::Skipped
+            } else {
+                ProcessingStatus::Pending
+            };
+        }
+    }
+
+    /// Offers a quick rename of `path`, prefilled with its current name.
+    pub fn show_rename_dialog(&mut self, path: &str) {
+        self.rename_dialog = Some(RenameDialog::new(path));
+    }
+
+    /// Closes the quick rename, leaving the guided setup for the same file.
+    pub fn cancel_rename_dialog(&mut self) {
+        self.rename_dialog = None;
+    }
+
+    /// Renames the file to the typed name and records it for undo. Returns whether the
+    /// rename happened; on failure the error is shown and the dialog stays open.
+    pub fn confirm_rename_dialog(&mut self) -> bool {
+        let Some(dialog) = self.rename_dialog.as_mut() else { return false };
+        if let Err(e) = dialog.operation.update_new_name(&dialog.editor.buffer) {
+            self.show_error(&e);
+            return false;
+        }
+
+        let new_path = match dialog.operation.execute() {
+            RenameOutcome::Success(new_path) => new_path,
+            outcome => {
+                let message = outcome.error_message().unwrap_or_default();
+                self.set_status_message(format!("Error: {}", message));
+                self.error_popup = Some(message);
+                return false;
+            }
+        };
+
+        let Some(dialog) = self.rename_dialog.take() else { return false };
+        self.undo_operations = vec![UndoOperation {
+            original_path: dialog.operation.get_original_path().to_string_lossy().to_string(),
+            renamed_path: new_path.to_string_lossy().to_string(),
+            original_name: dialog.operation.get_original_name(),
+            new_name: dialog.operation.get_new_name().to_string(),
+            created_dirs: Vec::new(),
+            created_files: Vec::new(),
+            trashed: None,
+        }];
+        self.undo_batch_created_at = next_batch_key(self.undo_batch_created_at);
+        self.persist_undo_log();
+        true
+    }
+
+    /// Opens the season prompt, prefilled with the current season, for TV batches not yet renamed.
+    pub fn start_season_edit(&mut self) {
+        if self.file_type.has_episodes() && !self.files.is_empty() {
+            let current = self.season_input.trim_start_matches(['S', 's']);
+            self.season_edit = Some(current.to_string());
+        }
+    }
+
+    pub fn cancel_season_edit(&mut self) {
+        self.season_edit = None;
+    }
+
+    /// Re-derives every file's new name with the typed season, replacing per-file detection.
+    /// An invalid number keeps the prompt open.
+    pub async fn apply_season_edit(&mut self) -> Result<(), RenameError> {
+        let Some(input) = self.season_edit.take() else { return Ok(()) };
+        let Ok(season_num) = input.trim().trim_start_matches(['S', 's']).parse::<u32>() else {
+            self.set_status_message(format!("Invalid season '{}': enter a number such as 2 or S02", input.trim()));
+            self.season_edit = Some(input);
+            return Ok(());
+        };
+
+        self.season_input = format!("S{:02}", season_num);
+        self.use_per_file_seasons = false;
+        self.refresh_selected_files().await?;
+        self.set_status_message(format!("Season set to {} for {} files", self.season_input, self.files.len()));
+        Ok(())
+    }
+
+    /// Marks files whose target name is shared with another file in the same directory,
+    /// or already taken on disk, as conflicts so they are never renamed. Returns how many
+    /// files collide; earlier collision marks are re-evaluated each time.
+    pub fn detect_collisions(&mut self) -> usize {
+        for file in self.files.iter_mut().filter(|file| file.has_collision()) {
+            file.status = ProcessingStatus::Pending;
+            file.error_message = None;
+        }
+
+        let renaming: Vec<usize> = (0..self.files.len())
+            .filter(|&i| self.files[i].status == ProcessingStatus::Pending && self.files[i].original_name != self.files[i].new_name)
+            .collect();
+        let sources: HashSet<PathBuf> = renaming.iter().map(|&i| PathBuf::from(&self.files[i].original_path)).collect();
+
+        let mut groups: HashMap<(PathBuf, String), Vec<usize>> = HashMap::new();
+        for &i in &renaming {
+            let directory = Path::new(&self.files[i].original_path).parent().map(Path::to_path_buf).unwrap_or_default();
+            groups.entry((directory, self.files[i].new_name.to_lowercase())).or_default().push(i);
+        }
+
+        let mut collisions: Vec<(usize, String)> = Vec::new();
+        for ((directory, _), members) in groups {
+            if members.len() > 1 {
+                for &i in &members {
+                    let others: Vec<&str> = members.iter()
+                        .filter(|&&other| other != i)
+                        .map(|&other| self.files[other].original_name.as_str())
+                        .collect();
+                    collisions.push((i, format!(
+                        "{}: {} is also the target of {}",
+                        COLLISION_MESSAGE, self.files[i].new_name, others.join(", ")
+                    )));
+                }
+                continue;
+            }
+
+            let i = members[0];
+            let target = directory.join(&self.files[i].new_name);
+            let is_self = target.to_string_lossy().to_lowercase() == self.files[i].original_path.to_lowercase();
+            if target.exists() && !is_self && !sources.contains(&target) {
+                collisions.push((i, format!("{}: {} already exists on disk", COLLISION_MESSAGE, self.files[i].new_name)));
+            }
+        }
+
+        let count = collisions.len();
+        for (i, message) in collisions {
+            self.files[i].status = ProcessingStatus::Conflict;
+            self.files[i].error_message = Some(message);
+        }
+
+        if count > 0 {
+            self.set_status_message(format!("Warning: {} files have name collisions and will not be renamed", count));
+        }
+        count
+    }
+
+    pub fn collision_count(&self) -> usize {
+        self.files.iter().filter(|file| file.has_collision()).count()
+    }
+
+    pub fn skipped_count(&self) -> usize {
+        self.files.iter().filter(|file| file.is_user_skipped()).count()
+    }
+
+    /// Renames shown in the file list that have not been carried out yet.
+    pub fn pending_rename_count(&self) -> usize {
+        if self.show_config || self.finished {
+            return 0;
+        }
+        self.files.iter()
+            .filter(|file| file.status == ProcessingStatus::Pending && file.original_name != file.new_name)
+            .count()
+    }
+
+    /// Renames the next run would carry out: the checked files, or all of them,
+    /// that are still pending, have a new name and don't collide.
+    pub fn queued_rename_count(&self) -> usize {
+        let checked_only = self.checked_count() > 0;
+        self.files.iter()
+            .filter(|file| !checked_only || file.checked)
+            .filter(|file| file.status == ProcessingStatus::Pending && file.original_name != file.new_name && !file.has_collision())
+            .count()
+    }
+
+    /// Asks before starting a run from the file list, since one key press renames
+    /// the whole batch. Does nothing once the run has finished or while it's going.
+    pub fn request_process(&mut self) {
+        if !self.finished && !self.is_processing() {
+            self.confirm_process = true;
+        }
+    }
+
+    /// Returns `true` when it is safe to quit straight away. Otherwise asks for
+    /// confirmation first, since quitting would abandon a batch.
+    pub fn request_quit(&mut self) -> bool {
+        if self.current_processing.is_some() || self.pending_rename_count() > 0 {
+            self.confirm_quit = true;
+            return false;
+        }
+        true
+    }
+
+    pub fn checked_count(&self) -> usize {
+        self.files.iter().filter(|file| file.checked).count()
+    }
+
+    /// Distinct seasons found in the filenames of the current batch, in ascending order.
+    pub fn detected_seasons(&self) -> Vec<u32> {
+        let mut seasons: Vec<u32> = self.files.iter()
+            .filter_map(|file| extract_season_from_filename(&file.original_name))
+            .collect();
+        seasons.sort_unstable();
+        seasons.dedup();
+        seasons
+    }
+
+    pub fn has_mixed_seasons(&self) -> bool {
+        self.detected_seasons().len() > 1
+    }
+
+    /// Asks for the previews to be rebuilt once typing pauses; without files there is nothing to rebuild.
+    pub fn request_refresh(&mut self) {
+        self.needs_refresh = !self.files.is_empty();
+        self.refresh_requested_at = Some(Instant::now());
+    }
+
+    /// Whether a requested refresh has waited out [`Self::refresh_debounce`] since the last edit.
+    pub fn refresh_due(&self) -> bool {
+        self.needs_refresh
+            && self.refresh_requested_at.is_none_or(|requested| requested.elapsed() >= self.refresh_debounce)
+    }
+
+    /// Switches between forcing the entered season and using each file's own detected season.
+    pub fn toggle_per_file_seasons(&mut self) {
+        self.use_per_file_seasons = !self.use_per_file_seasons;
+        self.request_refresh();
     }
 
     pub fn toggle_help(&mut self) {
@@ -255,6 +1039,16 @@ impl App {
         self.status_message_time = Some(Instant::now());
     }
 
+    /// Reports a failed operation in the status bar and an error popup.
+    pub fn show_error(&mut self, error: &RenameError) {
+        self.set_status_message(format!("Error: {}", error));
+        self.error_popup = Some(error.to_string());
+    }
+
+    pub fn dismiss_error(&mut self) {
+        self.error_popup = None;
+    }
+
     pub fn clear_status_message_if_expired(&mut self) {
         if let (Some(_), Some(time)) = (&self.status_message, self.status_message_time) {
             if time.elapsed() > Duration::from_secs(3) {
@@ -267,11 +1061,13 @@ impl App {
             ConfigInputMode::FileType => {
                 if c == 't' || c == 'T' {
                     self.file_type = FileType::TvShow;
-                    self.auto_detect_season_for_tv_shows();
                     self.advance_config_step();
                 } else if c == 'm' || c == 'M' {
                     self.file_type = FileType::Movie;
                     self.advance_config_step();
+                } else if c == 'a' || c == 'A' {
+                    self.file_type = FileType::Auto;
+                    self.advance_config_step();
                 }
             }
             ConfigInputMode::Directory => {
@@ -279,8 +1075,10 @@ impl App {
                     self.advance_config_step();
                 } else if c == '\x08' {
                     self.directory_input.pop();
+                    self.directory_error = None;
                 } else {
                     self.directory_input.push(c);
+                    self.directory_error = None;
                 }
             }
             ConfigInputMode::Season => {
@@ -289,12 +1087,12 @@ impl App {
                 } else if c == '\x08' {
                     self.season_input.pop();
                     if !self.files.is_empty() {
-                        self.needs_refresh = true;
+                        self.request_refresh();
                     }
                 } else {
                     self.season_input.push(c);
                     if !self.files.is_empty() {
-                        self.needs_refresh = true;
+                        self.request_refresh();
                     }
                 }
             }
@@ -302,7 +1100,7 @@ impl App {
                 if c == '\n' || c == '\r' {
                     if !self.year_input.is_empty() {
                         if let Ok(year) = self.year_input.parse::<u32>() {
-                            if year >= 1900 && year <= 2100 {
+                            if (1900..=2100).contains(&year) {
                                 self.advance_config_step();
                             }
                         }
@@ -312,12 +1110,12 @@ impl App {
                 } else if c == '\x08' {
                     self.year_input.pop();
                     if !self.files.is_empty() {
-                        self.needs_refresh = true;
+                        self.request_refresh();
                     }
                 } else if c.is_ascii_digit() {
                     self.year_input.push(c);
                     if !self.files.is_empty() {
-                        self.needs_refresh = true;
+                        self.request_refresh();
                     }
                 }
             }            ConfigInputMode::MovieYears => {
@@ -325,7 +1123,7 @@ impl App {
                     let current_year = &self.movie_years[self.current_movie_index];
                     if !current_year.is_empty() {
                         if let Ok(year) = current_year.parse::<u32>() {
-                            if year < 1900 || year > 2100 {
+                            if !(1900..=2100).contains(&year) {
                                 return;
                             }
                         } else {
@@ -342,21 +1140,56 @@ impl App {
                     if self.current_movie_index < self.movie_years.len() {
                         self.movie_years[self.current_movie_index].pop();
                         if !self.files.is_empty() {
-                            self.needs_refresh = true;
+                            self.request_refresh();
                         }
                     }
                 } else if c.is_ascii_digit() {
                     if self.current_movie_index < self.movie_years.len() {
                         self.movie_years[self.current_movie_index].push(c);
                         if !self.files.is_empty() {
-                            self.needs_refresh = true;
+                            self.request_refresh();
                         }
                     }
                 }
             }
+            ConfigInputMode::ShowName => {
+                if c == '\n' || c == '\r' {
+                    self.advance_config_step();
+                } else if c == '\x08' {
+                    self.show_name_input.pop();
+                    self.request_refresh();
+                } else {
+                    self.show_name_input.push(c);
+                    self.request_refresh();
+                }
+            }
+            ConfigInputMode::CustomPattern => {
+                if c == '\n' || c == '\r' {
+                    // An invalid pattern would only fail later when the engine is built
+                    match compile_custom_pattern(&self.custom_pattern_input) {
+                        Err(e) if !self.custom_pattern_input.trim().is_empty() => self.set_status_message(e.to_string()),
+                        _ => self.advance_config_step(),
+                    }
+                } else if c == '\x08' {
+                    self.custom_pattern_input.pop();
+                    self.request_refresh();
+                } else {
+                    self.custom_pattern_input.push(c);
+                    self.request_refresh();
+                }
+            }
             ConfigInputMode::ImdbChoice => {
                 if c == 'y' || c == 'Y' {
                     self.use_imdb = true;
+                    self.title_source = TitleSource::Imdb;
+                    self.advance_config_step();
+                } else if c == 't' || c == 'T' {
+                    self.use_imdb = true;
+                    self.title_source = TitleSource::Tmdb;
+                    self.advance_config_step();
+                } else if c == 'o' || c == 'O' {
+                    self.use_imdb = true;
+                    self.title_source = TitleSource::Omdb;
                     self.advance_config_step();
                 } else if c == 'n' || c == 'N' {
                     self.use_imdb = false;
@@ -372,18 +1205,89 @@ impl App {
                     self.imdb_id_input.push(c);
                 }
             }
-            _ => {}
+            ConfigInputMode::TmdbApiKey => {
+                if c == '\n' || c == '\r' {
+                    self.advance_config_step();
+                } else if c == '\x08' {
+                    self.tmdb_api_key_input.pop();
+                } else {
+                    self.tmdb_api_key_input.push(c);
+                }
+            }
+            ConfigInputMode::OmdbApiKey => {
+                if c == '\n' || c == '\r' {
+                    self.advance_config_step();
+                } else if c == '\x08' {
+                    self.omdb_api_key_input.pop();
+                } else {
+                    self.omdb_api_key_input.push(c);
+                }
+            }
+            ConfigInputMode::Confirm => match confirm_action(c) {
+                Some(Action::ToggleDryRun) => self.dry_run = !self.dry_run,
+                Some(Action::ToggleSeasonFolders) if self.file_type.has_episodes() => {
+                    self.organize_into_folders = !self.organize_into_folders;
+                }
+                Some(Action::ToggleNfo) => self.write_nfo = !self.write_nfo,
+                Some(Action::CycleSeparator) => self.cycle_separator(),
+                Some(Action::ToggleReport) => self.write_report = !self.write_report,
+                _ => {}
+            },
+        }
+    }
+
+    /// Steps the word separator through underscore, space and dot.
+    pub fn cycle_separator(&mut self) {
+        self.separator = match self.separator {
+            '_' => ' ',
+            ' ' => '.',
+            _ => '_',
+        };
+        self.request_refresh();
+    }
+
+    /// Fills blank per-movie years with the year found in each filename. Years
+    /// already entered are kept, and files without a year stay blank.
+    pub fn prefill_movie_years(&mut self) {
+        self.movie_years.resize(self.files.len(), String::new());
+        for (year, file) in self.movie_years.iter_mut().zip(&self.files) {
+            if year.is_empty() {
+                *year = extract_year_from_filename(&file.original_name).unwrap_or_default();
+            }
+        }
+    }
+
+    /// Expands `~` and environment variables in the directory field and checks that
+    /// it names an existing directory, recording why not in `directory_error`.
+    pub fn validate_directory_input(&mut self) -> bool {
+        let expanded = expand_path(&self.directory_input);
+        let path = Path::new(&expanded);
+        self.directory_error = if expanded.is_empty() {
+            Some("Enter the directory containing your video files".to_string())
+        } else if !path.exists() {
+            Some(format!("{} does not exist", expanded))
+        } else if !path.is_dir() {
+            Some(format!("{} is not a directory", expanded))
+        } else {
+            None
+        };
+
+        if self.directory_error.is_none() {
+            self.directory_input = expanded;
         }
+        self.directory_error.is_none()
     }
 
     pub fn advance_config_step(&mut self) {
         match self.config_input_mode {
             ConfigInputMode::FileType => {
+                self.auto_detect_season_for_tv_shows();
                 if !self.files.is_empty() {
-                    if self.file_type == FileType::TvShow {
+                    if self.file_type.has_episodes() {
                         self.config_input_mode = ConfigInputMode::Season;
                     } else {
                         if self.files.len() > 1 {
+                            self.prefill_movie_years();
                             self.config_input_mode = ConfigInputMode::MovieYears;
                         } else {
                             self.config_input_mode = ConfigInputMode::Year;
@@ -394,13 +1298,22 @@ impl App {
                 }
             }
             ConfigInputMode::Directory => {
-                if self.file_type == FileType::TvShow {
+                if !self.validate_directory_input() {
+                    return;
+                }
+                if self.file_type.has_episodes() {
                     self.config_input_mode = ConfigInputMode::Season;
                 } else {
                     self.config_input_mode = ConfigInputMode::Year;
                 }
             }
             ConfigInputMode::Season => {
+                self.config_input_mode = ConfigInputMode::ShowName;
+            }
+            ConfigInputMode::ShowName => {
+                self.config_input_mode = ConfigInputMode::CustomPattern;
+            }
+            ConfigInputMode::CustomPattern => {
                 if self.files.len() > 1 {
                     self.config_input_mode = ConfigInputMode::ImdbChoice;
                 } else {
@@ -421,6 +1334,13 @@ impl App {
                 }
             }
             ConfigInputMode::ImdbId => {
+                self.config_input_mode = match self.title_source {
+                    TitleSource::Tmdb => ConfigInputMode::TmdbApiKey,
+                    TitleSource::Omdb => ConfigInputMode::OmdbApiKey,
+                    TitleSource::Imdb => ConfigInputMode::Confirm,
+                };
+            }
+            ConfigInputMode::TmdbApiKey | ConfigInputMode::OmdbApiKey => {
                 self.config_input_mode = ConfigInputMode::Confirm;
             }            ConfigInputMode::Confirm => {
             }
@@ -455,21 +1375,34 @@ impl App {
                 }
                 self.current_movie_index = 0;
             }
-            ConfigInputMode::ImdbChoice => {
+            ConfigInputMode::ShowName => {
                 self.config_input_mode = ConfigInputMode::Season;
             }
+            ConfigInputMode::CustomPattern => {
+                self.config_input_mode = ConfigInputMode::ShowName;
+            }
+            ConfigInputMode::ImdbChoice => {
+                self.config_input_mode = ConfigInputMode::CustomPattern;
+            }
             ConfigInputMode::ImdbId => {
                 self.config_input_mode = ConfigInputMode::ImdbChoice;
             }
+            ConfigInputMode::TmdbApiKey | ConfigInputMode::OmdbApiKey => {
+                self.config_input_mode = ConfigInputMode::ImdbId;
+            }
             ConfigInputMode::Confirm => {
-                if self.file_type == FileType::TvShow && self.files.len() > 1 {
-                    if self.use_imdb {
+                if self.file_type.has_episodes() && self.files.len() > 1 {
+                    if self.use_imdb && self.title_source == TitleSource::Tmdb {
+                        self.config_input_mode = ConfigInputMode::TmdbApiKey;
+                    } else if self.use_imdb && self.title_source == TitleSource::Omdb {
+                        self.config_input_mode = ConfigInputMode::OmdbApiKey;
+                    } else if self.use_imdb {
                         self.config_input_mode = ConfigInputMode::ImdbId;
                     } else {
                         self.config_input_mode = ConfigInputMode::ImdbChoice;
                     }
-                } else if self.file_type == FileType::TvShow && self.files.len() == 1 {
-                    self.config_input_mode = ConfigInputMode::Season;
+                } else if self.file_type.has_episodes() && self.files.len() == 1 {
+                    self.config_input_mode = ConfigInputMode::CustomPattern;
                 } else if self.file_type == FileType::Movie && self.files.len() > 1 {
                     self.config_input_mode = ConfigInputMode::MovieYears;
                 } else {
@@ -519,65 +1452,210 @@ impl App {
         }
     }
 
-    pub async fn process_files(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(engine) = &self.rename_engine {
-            self.start_time = Some(Instant::now());
-            let total_files = self.files.len();
-            
-            for index in 0..total_files {
-                self.current_processing = Some(index);
-                self.files[index].status = ProcessingStatus::Processing;
-                self.processing_progress = (index as f64) / (total_files as f64);                let file_rename = FileRename {
-                    original_path: PathBuf::from(&self.files[index].original_path),
-                    original_name: self.files[index].original_name.clone(),
-                    new_name: self.files[index].new_name.clone(),
-                    episode_number: self.files[index].episode_number,
-                    season_number: 1,
-                    episode_title: self.files[index].episode_title.clone(),
-                    needs_rename: self.files[index].original_name != self.files[index].new_name,
-                };
+    /// Renames the checked files (or all of them) in one go. The TUI instead calls
+    /// [`App::start_processing`] and then [`App::process_next_file`] once per tick,
+    /// so the progress bar is redrawn and keys are read between files.
+    pub async fn process_files(&mut self) -> Result<(), RenameError> {
+        self.start_processing();
+        while self.is_processing() {
+            self.process_next_file().await;
+            if self.is_processing() && !self.processing_delay.is_zero() {
+                tokio::time::sleep(self.processing_delay).await;
+            }
+        }
+        Ok(())
+    }
 
-                if !file_rename.needs_rename {
-                    self.files[index].status = ProcessingStatus::Skipped;
-                    self.stats.processed += 1;
-                    continue;
-                }
+    /// Whether a run begun by [`App::start_processing`] still has files to rename.
+    pub fn is_processing(&self) -> bool {
+        self.processing_run.is_some()
+    }
 
-                let result = engine.rename_file(&file_rename).await;
-                  if result.success {
-                    self.files[index].status = ProcessingStatus::Success;
-                    self.stats.successful += 1;
-                    
-                    let new_path = PathBuf::from(&self.files[index].original_path)
-                        .parent()
-                        .unwrap()
-                        .join(&self.files[index].new_name);
-                    
-                    self.undo_operations.push(UndoOperation {
-                        original_path: self.files[index].original_path.clone(),
-                        renamed_path: new_path.to_string_lossy().to_string(),
-                        original_name: self.files[index].original_name.clone(),
-                        new_name: self.files[index].new_name.clone(),
-                    });
-                } else {
-                    self.files[index].status = ProcessingStatus::Error;
-                    self.files[index].error_message = result.error_message;
-                    self.stats.failed += 1;
-                }
-                
+    /// Sets up a run over the checked files (or all of them) without renaming
+    /// anything yet; [`App::process_next_file`] does one file per call.
+    pub fn start_processing(&mut self) {
+        if self.is_processing() {
+            return;
+        }
+        // Pre-flight: nothing is renamed onto a name another file needs or that already exists
+        self.detect_collisions();
+
+        if self.rename_engine.is_none() {
+            return;
+        }
+        self.start_time = Some(Instant::now());
+        self.finish_time = None;
+        // A new run supersedes whatever was last undone, and starts its own undo batch
+        self.redo_operations.clear();
+        self.undo_operations.clear();
+        self.undo_batch_created_at = next_batch_key(self.undo_batch_created_at);
+
+        // When some files are checked, only those are processed and the rest stay untouched
+        let targets: Vec<usize> = if self.checked_count() > 0 {
+            (0..self.files.len()).filter(|&i| self.files[i].checked).collect()
+        } else {
+            (0..self.files.len()).collect()
+        };
+        self.stats.total = targets.len();
+        self.processing_progress = 0.0;
+        self.current_processing = targets.first().copied();
+
+        let run = ProcessingRun { targets, ..ProcessingRun::default() };
+        if run.targets.is_empty() {
+            self.finish_processing(run);
+        } else {
+            self.processing_run = Some(run);
+        }
+    }
+
+    /// Renames the next file of the current run, and wraps the run up after the last one.
+    pub async fn process_next_file(&mut self) {
+        let Some(mut run) = self.processing_run.take() else { return };
+        if let Some(&index) = run.targets.get(run.position) {
+            self.process_file_at(index, &mut run).await;
+            run.position += 1;
+        }
+
+        self.processing_progress = run.position as f64 / run.targets.len() as f64;
+        self.current_processing = run.targets.get(run.position).copied();
+        if self.current_processing.is_some() {
+            self.processing_run = Some(run);
+        } else {
+            self.finish_processing(run);
+        }
+    }
+
+    async fn process_file_at(&mut self, index: usize, run: &mut ProcessingRun) {
+        let Some(engine) = &self.rename_engine else { return };
+
+        // Files the user skipped are left untouched and get no undo entry
+        if self.files[index].status == ProcessingStatus::Skipped {
+            self.stats.skipped += 1;
+            self.stats.processed += 1;
+            return;
+        }
+
+        if self.files[index].has_collision() {
+            run.held_back += 1;
+            self.stats.failed += 1;
+            self.stats.processed += 1;
+            return;
+        }
+
+        self.files[index].status = ProcessingStatus::Processing;
+        let file_rename = FileRename {
+            original_path: PathBuf::from(&self.files[index].original_path),
+            original_name: self.files[index].original_name.clone(),
+            new_name: self.files[index].new_name.clone(),
+            episode_number: self.files[index].episode_number,
+            season_number: self.files[index].season_number,
+            episode_title: self.files[index].episode_title.clone(),
+            needs_rename: self.files[index].original_name != self.files[index].new_name,
+            match_kind: self.files[index].match_kind,
+        };
+
+        if !file_rename.needs_rename {
+            self.files[index].status = ProcessingStatus::Skipped;
+            self.stats.skipped += 1;
+            self.stats.processed += 1;
+            return;
+        }
+
+        let result = engine.rename_file(&file_rename).await;
+        if result.cross_device {
+            run.cross_device_copies += 1;
+        }
+        if let RenameOutcome::Success(new_path) = &result.outcome {
+            self.files[index].status = ProcessingStatus::Success;
+            self.stats.successful += 1;
+
+            // Nothing changed on disk, so there is nothing to undo
+            if self.dry_run {
                 self.stats.processed += 1;
+                return;
+            }
 
-                tokio::time::sleep(Duration::from_millis(100)).await;
+            self.undo_operations.push(UndoOperation {
+                original_path: self.files[index].original_path.clone(),
+                renamed_path: new_path.to_string_lossy().to_string(),
+                original_name: self.files[index].original_name.clone(),
+                new_name: self.files[index].new_name.clone(),
+                created_dirs: result.created_dirs.iter().map(|dir| dir.to_string_lossy().to_string()).collect(),
+                created_files: result.created_files.iter().map(|file| file.to_string_lossy().to_string()).collect(),
+                trashed: result.trashed.contains(new_path).then(|| new_path.to_string_lossy().to_string()),
+            });
+
+            // Sidecars are undone as their own operations so they move back too
+            for (companion_original, companion_renamed) in result.companions {
+                self.undo_operations.push(UndoOperation {
+                    original_path: companion_original.to_string_lossy().to_string(),
+                    renamed_path: companion_renamed.to_string_lossy().to_string(),
+                    original_name: companion_original.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    new_name: companion_renamed.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    created_dirs: Vec::new(),
+                    created_files: Vec::new(),
+                    trashed: result.trashed.contains(&companion_renamed).then(|| companion_renamed.to_string_lossy().to_string()),
+                });
             }
 
-            self.current_processing = None;
-            self.processing_progress = 1.0;
-            self.finished = true;
+            // Written after every rename so a crash mid-batch can still be undone
+            if let Err(e) = self.save_undo_log() {
+                run.undo_log_error = Some(e.to_string());
+            }
+        } else if let RenameOutcome::Skipped(_) = &result.outcome {
+            // The target appeared on disk after the pre-flight check
+            self.files[index].status = ProcessingStatus::Skipped;
+            self.files[index].error_message = result.error_message();
+            self.stats.skipped += 1;
+        } else {
+            self.files[index].status = ProcessingStatus::Error;
+            self.files[index].error_message = result.error_message();
+            self.stats.failed += 1;
+        }
+
+        self.stats.processed += 1;
+    }
+
+    fn finish_processing(&mut self, run: ProcessingRun) {
+        self.current_processing = None;
+        self.processing_progress = 1.0;
+        self.finished = true;
+        self.finish_time = Some(Instant::now());
+
+        if run.held_back > 0 {
+            self.set_status_message(RenameError::Collision(run.held_back).to_string());
+        }
+        if run.cross_device_copies > 0 {
+            self.set_status_message(format!(
+                "{} file(s) were on another filesystem and were copied, then removed",
+                run.cross_device_copies
+            ));
+        }
+        if let Some(e) = run.undo_log_error {
+            self.set_status_message(format!("Warning: could not save undo log: {}", e));
+        }
+        if self.write_report {
+            self.write_summary_report(&run.targets);
+        }
+    }
+
+    /// Where the summary report is written: the configured path, or the target directory.
+    pub fn summary_report_path(&self) -> PathBuf {
+        self.report_path.clone().unwrap_or_else(|| default_report_path(Path::new(&self.directory_input)))
+    }
+
+    /// Writes the summary report for the files a run covered. A failed write is
+    /// reported in the status bar; the renames themselves stand.
+    fn write_summary_report(&mut self, targets: &[usize]) {
+        let entries: Vec<ReportEntry> = targets.iter().map(|&index| ReportEntry::from(&self.files[index])).collect();
+        let path = self.summary_report_path();
+        match write_report(&path, &entries, &self.stats) {
+            Ok(()) => self.set_status_message(format!("Report written to {}", path.display())),
+            Err(e) => self.set_status_message(format!("Warning: could not write report to {}: {}", path.display(), e)),
         }
-        Ok(())
     }
 
-    pub async fn process_selected_files(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn process_selected_files(&mut self) -> Result<(), RenameError> {
         if let Some(engine) = &self.rename_engine {
             // Store files length before mutable iteration to avoid borrow checker issues
             let files_len = self.files.len();
@@ -600,27 +1678,36 @@ impl App {
                     
                     // Process with individual year if needed
                     if let Some(file_rename) = engine.process_file_with_year(filename, file_year)? {
+                        if engine.provider_title(file_rename.episode_number).is_some() {
+                            file_item.provider_title = Some(file_rename.episode_title.clone());
+                        }
                         file_item.new_name = file_rename.new_name;
                         file_item.episode_number = file_rename.episode_number;
+                        file_item.season_number = file_rename.season_number;
                         file_item.episode_title = file_rename.episode_title;
+                        file_item.match_kind = file_rename.match_kind;
+                    } else {
+                        // Nothing matched: the file keeps its name and is flagged, not queued
+                        file_item.match_kind = MatchKind::Unmatched;
+                        file_item.status = ProcessingStatus::Skipped;
                     }
-                    // If no pattern matches, keep original name
                 }            }
 
             // Sort files by episode number for TV shows
-            if self.file_type == FileType::TvShow {
+            if self.file_type.has_episodes() {
                 self.sort_files_by_episode();
             }
+            self.detect_collisions();
 
             if !self.files.is_empty() {
-                self.list_state.select(Some(0));
+                self.select_file(0);
                 self.show_config = false;
             }
         }
         Ok(())
     }
 
-    pub async fn refresh_selected_files(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn refresh_selected_files(&mut self) -> Result<(), RenameError> {
         // Only refresh if we have selected files and a rename engine
         if self.files.is_empty() || self.rename_engine.is_none() {
             return Ok(());
@@ -643,20 +1730,43 @@ impl App {
         if let Some(engine) = &self.rename_engine {
             // Reprocess each file with the updated season
             for file_item in &mut self.files {
+                // Names typed in by hand are kept as they are
+                if file_item.manually_edited {
+                    continue;
+                }
+
                 let path = std::path::Path::new(&file_item.original_path);
                 if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
+                    let season_num = if self.use_per_file_seasons {
+                        extract_season_from_filename(filename).unwrap_or(manual_season_num)
+                    } else {
+                        manual_season_num
+                    };
+
                     // Reset to original state first
                     file_item.new_name = file_item.original_name.clone();
                     file_item.episode_number = 0;
                     file_item.episode_title = String::new();
+                    file_item.match_kind = MatchKind::Unmatched;
                     file_item.status = ProcessingStatus::Pending;
 
-                    // Process with manual season override
-                    if let Some(file_rename) = engine.process_file_with_manual_season(filename, manual_season_num)? {
+                    // Process with manual season override. Titles for the new season win; without
+                    // them the provider's earlier title is kept and only the season changes
+                    let mut result = engine.process_file_with_manual_season(filename, season_num)?;
+                    if let Some(file_rename) = &result {
+                        if engine.provider_title(file_rename.episode_number).is_some() {
+                            file_item.provider_title = Some(file_rename.episode_title.clone());
+                        } else if let Some(title) = file_item.provider_title.as_deref() {
+                            result = engine.process_file_with_season_and_title(filename, season_num, Some(title))?;
+                        }
+                    }
+                    if let Some(file_rename) = result {
                         // Update file item with values from the rename result
                         file_item.new_name = file_rename.new_name;
                         file_item.episode_number = file_rename.episode_number;
+                        file_item.season_number = file_rename.season_number;
                         file_item.episode_title = file_rename.episode_title;
+                        file_item.match_kind = file_rename.match_kind;
                         
                         // Check if rename is actually needed
                         file_item.status = if file_rename.needs_rename { 
@@ -664,30 +1774,83 @@ impl App {
                         } else { 
                             ProcessingStatus::Skipped 
                         };
+                    } else {
+                        file_item.status = ProcessingStatus::Skipped;
                     }                }
             }
         }
 
         // Sort files by episode number for TV shows
-        if self.file_type == FileType::TvShow {
+        if self.file_type.has_episodes() {
             self.sort_files_by_episode();
         }
+        self.detect_collisions();
+
+        // A single forced season is wrong when the batch holds several
+        if !self.use_per_file_seasons && self.has_mixed_seasons() {
+            let seasons: Vec<String> = self.detected_seasons().iter().map(|s| s.to_string()).collect();
+            self.set_status_message(format!(
+                "Warning: files span seasons {} - press Tab to use each file's detected season",
+                seasons.join(", ")
+            ));
+        }
 
         Ok(())
     }
 
-    pub async fn undo_renames(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        if self.undo_operations.is_empty() {
+    pub async fn undo_renames(&mut self) -> Result<(), RenameError> {
+        self.undo_renames_scoped(None).await
+    }
+
+    /// Reverts only the renames whose files live directly in `directory`, leaving the rest of the history intact.
+    pub async fn undo_renames_in_directory(&mut self, directory: &Path) -> Result<(), RenameError> {
+        self.undo_renames_scoped(Some(directory)).await
+    }
+
+    /// Undoes the renames in the directory of the currently selected file. With no
+    /// file listed, as after a relaunch, the directory of the most recent rename is used.
+    pub async fn undo_selected_directory(&mut self) -> Result<(), RenameError> {
+        let directory = self.list_state.selected()
+            .and_then(|i| self.files.get(i))
+            .map(|file| file.original_path.as_str())
+            .or_else(|| self.undo_operations.last().map(|undo_op| undo_op.renamed_path.as_str()))
+            .and_then(|path| Path::new(path).parent().map(Path::to_path_buf));
+
+        match directory {
+            Some(directory) => self.undo_renames_in_directory(&directory).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn undo_renames_scoped(&mut self, directory: Option<&Path>) -> Result<(), RenameError> {
+        let in_scope = |path: &str| match directory {
+            Some(directory) => Path::new(path).parent() == Some(directory),
+            None => true,
+        };
+
+        let (to_undo, remaining): (Vec<UndoOperation>, Vec<UndoOperation>) = self.undo_operations
+            .drain(..)
+            .partition(|undo_op| in_scope(&undo_op.renamed_path) || in_scope(&undo_op.original_path));
+        self.undo_operations = remaining;
+
+        if to_undo.is_empty() {
             return Ok(());
         }
 
         let mut undo_errors = Vec::new();
         let mut successful_undos = 0;
+        let mut undone = Vec::new();
 
-        for undo_op in self.undo_operations.iter().rev() {
-            match fs::rename(&undo_op.renamed_path, &undo_op.original_path) {
+        for undo_op in to_undo.into_iter().rev() {
+            match move_file(Path::new(&undo_op.renamed_path), Path::new(&undo_op.original_path)) {
                 Ok(_) => {
                     successful_undos += 1;
+                    if let Some(trashed) = &undo_op.trashed {
+                        if let Err(e) = restore_from_trash(Path::new(trashed)) {
+                            undo_errors.push(format!("Failed to restore {} from the trash: {}", trashed, e));
+                        }
+                    }
+                    undone.push(undo_op);
                 }
                 Err(e) => {
                     undo_errors.push(format!("Failed to undo {}: {}", undo_op.new_name, e));
@@ -695,13 +1858,41 @@ impl App {
             }
         }
 
-        // Clear undo operations after performing undo
-        self.undo_operations.clear();
-        
-        // Reset ALL file statuses and names properly (not just successful ones)
-        for file in &mut self.files {
+        // Generated .nfo files are deleted (redo does not rewrite them), then season
+        // folders go once the files moved into them are back out
+        for undo_op in undone.iter_mut() {
+            for created_file in undo_op.created_files.drain(..) {
+                if let Err(e) = std::fs::remove_file(&created_file) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        undo_errors.push(format!("Failed to remove {}: {}", created_file, e));
+                    }
+                }
+            }
+            let created_dirs: Vec<PathBuf> = undo_op.created_dirs.iter().map(PathBuf::from).collect();
+            remove_empty_dirs(&created_dirs);
+        }
+
+        // Kept in forward order so redo replays the renames as they originally happened
+        undone.reverse();
+        self.redo_operations = undone;
+        self.persist_undo_log();
+
+        // Files listed under their renamed paths (e.g. after a relaunch) go back to their original paths
+        for undo_op in &self.redo_operations {
+            if let Some(file) = self.files.iter_mut().find(|file| file.original_path == undo_op.renamed_path) {
+                file.original_path = undo_op.original_path.clone();
+                file.original_name = undo_op.original_name.clone();
+            }
+        }
+
+        let reverted_files = self.files.iter()
+            .filter(|file| file.status == ProcessingStatus::Success && in_scope(&file.original_path))
+            .count();
+
+        // Reset file statuses and names properly (not just successful ones) within the undone scope
+        for file in self.files.iter_mut().filter(|file| in_scope(&file.original_path)) {
             // Reset status to pending for all files that were processed
-            if file.status == ProcessingStatus::Success || file.status == ProcessingStatus::Error || file.status == ProcessingStatus::Skipped {
+            if matches!(file.status, ProcessingStatus::Success | ProcessingStatus::Error | ProcessingStatus::Skipped | ProcessingStatus::Conflict) {
                 file.status = ProcessingStatus::Pending;
             }
             // Reset new_name back to original_name for all files
@@ -714,41 +1905,41 @@ impl App {
         
         // Reprocess files with the rename engine to recalculate new names
         if let Some(engine) = &self.rename_engine {
-            for file_item in &mut self.files {
+            for file_item in self.files.iter_mut().filter(|file| in_scope(&file.original_path)) {
                 let path = std::path::Path::new(&file_item.original_path);
                 if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
-                    // Try different processing methods to recalculate new names
-                    if let Some(file_rename) = engine.process_file_standard(filename)? {
-                        file_item.new_name = file_rename.new_name;
-                        file_item.episode_number = file_rename.episode_number;
-                        file_item.episode_title = file_rename.episode_title;
-                        file_item.status = if file_rename.needs_rename { ProcessingStatus::Pending } else { ProcessingStatus::Skipped };
-                    } else if let Some(file_rename) = engine.process_file_flexible(filename)? {
-                        file_item.new_name = file_rename.new_name;
-                        file_item.episode_number = file_rename.episode_number;
-                        file_item.episode_title = file_rename.episode_title;
-                        file_item.status = if file_rename.needs_rename { ProcessingStatus::Pending } else { ProcessingStatus::Skipped };
-                    } else if let Some(file_rename) = engine.process_file_movie(filename)? {
+                    // Recalculate new names with the patterns for the configured file type
+                    if let Some(file_rename) = engine.process_file(filename)? {
                         file_item.new_name = file_rename.new_name;
                         file_item.episode_number = file_rename.episode_number;
+                        file_item.season_number = file_rename.season_number;
                         file_item.episode_title = file_rename.episode_title;
+                        file_item.match_kind = file_rename.match_kind;
                         file_item.status = if file_rename.needs_rename { ProcessingStatus::Pending } else { ProcessingStatus::Skipped };
                     }
                 }
             }
         }
-          self.finished = false;
+
+        // Renames outside the undone directory are still on disk, so the run stays finished
+        if self.undo_operations.is_empty() {
+            self.finished = false;
+            self.processing_progress = 0.0;
+            self.stats.successful = 0;
+            self.stats.failed = 0;
+            self.stats.skipped = 0;
+            self.stats.processed = 0;
+        } else {
+            self.stats.successful = self.stats.successful.saturating_sub(reverted_files);
+        }
         self.current_processing = None;
-        self.processing_progress = 0.0;
-        self.stats.successful = 0;
-        self.stats.failed = 0;
-        self.stats.processed = 0;
         
         if !self.files.is_empty() {
             let selected = self.list_state.selected().unwrap_or(0);
             if selected >= self.files.len() {
-                self.list_state.select(Some(0));
-                self.selected_index = 0;
+                self.select_file(0);
+            } else {
+                self.sync_scroll_state();
             }
         }
         
@@ -759,8 +1950,93 @@ impl App {
         }
         
         Ok(())
+    }
+
+    /// Loads the last session's renames in the open directory from the undo log so `u`
+    /// can revert them after a relaunch. Renames already held by this run are kept.
+    pub fn load_last_session(&mut self) {
+        if self.directory_input.is_empty() || !self.undo_operations.is_empty() {
+            return;
+        }
+        if let Some(batch) = self.undo_log.last_batch_in(Path::new(&self.directory_input)) {
+            self.set_status_message(format!(
+                "Loaded {} renames from the last session - press u to undo them",
+                batch.operations.len()
+            ));
+            self.undo_batch_created_at = batch.created_at;
+            self.undo_operations = batch.operations;
+        }
+    }
+
+    /// Saves the current batch to the undo log, dropping it once nothing is left to undo.
+    fn save_undo_log(&self) -> anyhow::Result<()> {
+        self.undo_log.save_batch(&UndoBatch {
+            created_at: self.undo_batch_created_at,
+            operations: self.undo_operations.clone(),
+        })
+    }
+
+    fn persist_undo_log(&mut self) {
+        if let Err(e) = self.save_undo_log() {
+            self.set_status_message(format!("Warning: could not save undo log: {}", e));
+        }
+    }
+
+    /// Re-applies the renames reverted by the last undo, in their original order.
+    pub fn redo_renames(&mut self) {
+        if self.redo_operations.is_empty() {
+            return;
+        }
+
+        let mut redo_errors = Vec::new();
+        let mut successful_redos = 0;
+
+        for redo_op in std::mem::take(&mut self.redo_operations) {
+            // Undo removed the season folders this rename created
+            if let Some(dir) = redo_op.created_dirs.last() {
+                if let Err(e) = std::fs::create_dir_all(dir) {
+                    redo_errors.push(format!("Failed to redo {}: {}", redo_op.new_name, e));
+                    continue;
+                }
+            }
+            // The file the rename replaced goes back to the trash first
+            if let Some(trashed) = &redo_op.trashed {
+                if Path::new(trashed).exists() {
+                    if let Err(e) = trash::delete(trashed) {
+                        redo_errors.push(format!("Failed to redo {}: {}", redo_op.new_name, e));
+                        continue;
+                    }
+                }
+            }
+            if let Err(e) = move_file(Path::new(&redo_op.original_path), Path::new(&redo_op.renamed_path)) {
+                redo_errors.push(format!("Failed to redo {}: {}", redo_op.new_name, e));
+                continue;
+            }
+            successful_redos += 1;
+
+            if let Some(file) = self.files.iter_mut().find(|file| file.original_path == redo_op.original_path) {
+                file.new_name = redo_op.new_name.clone();
+                file.status = ProcessingStatus::Success;
+                file.error_message = None;
+                self.stats.successful += 1;
+                self.stats.processed += 1;
+            }
+            self.undo_operations.push(redo_op);
+        }
+        self.persist_undo_log();
+
+        if !self.undo_operations.is_empty() {
+            self.finished = true;
+            self.processing_progress = 1.0;
+        }
+
+        if redo_errors.is_empty() {
+            self.set_status_message(format!("Successfully redid {} rename operations", successful_redos));
+        } else {
+            self.set_status_message(format!("Redid {} operations with {} errors", successful_redos, redo_errors.len()));
+        }
     }    pub fn auto_detect_season_for_tv_shows(&mut self) {
-        if self.file_type != FileType::TvShow {
+        if !self.file_type.has_episodes() {
             return;
         }
         
@@ -800,6 +2076,31 @@ impl App {
         }
     }
 
+    /// Switches to the next sort mode and reorders the list, keeping the same file selected.
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+
+        let selected_path = self.list_state.selected()
+            .and_then(|i| self.files.get(i))
+            .map(|file| file.original_path.clone());
+
+        match self.sort_mode {
+            SortMode::OriginalName => self.files.sort_by(|a, b| a.original_name.cmp(&b.original_name)),
+            SortMode::Episode => self.files.sort_by(|a, b| {
+                // Files without an episode number go last
+                (a.episode_number == 0, a.episode_number, &a.original_name)
+                    .cmp(&(b.episode_number == 0, b.episode_number, &b.original_name))
+            }),
+            SortMode::Status => self.files.sort_by(|a, b| {
+                (status_rank(&a.status), &a.original_name).cmp(&(status_rank(&b.status), &b.original_name))
+            }),
+        }
+
+        if let Some(index) = selected_path.and_then(|path| self.files.iter().position(|file| file.original_path == path)) {
+            self.select_file(index);
+        }
+    }
+
     fn sort_files_by_episode(&mut self) {
         self.files.sort_by(|a, b| {
             match (a.episode_number, b.episode_number) {
@@ -813,3 +2114,14 @@ impl App {
         });
     }
 }
+
+/// Errors and conflicts first since they need attention, finished files last.
+fn status_rank(status: &ProcessingStatus) -> u8 {
+    match status {
+        ProcessingStatus::Error | ProcessingStatus::Conflict => 0,
+        ProcessingStatus::Pending => 1,
+        ProcessingStatus::Processing => 2,
+        ProcessingStatus::Skipped => 3,
+        ProcessingStatus::Success => 4,
+    }
+}
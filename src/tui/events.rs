@@ -9,12 +9,22 @@ use ratatui::{
     backend::CrosstermBackend,
     Terminal,
 };
+use regex::Regex;
 
+use crate::rename_engine::compile_custom_pattern;
+use crate::settings::Settings;
 use super::app::App;
+use super::last_run::LastRunStore;
+use super::keymap::{main_action, Action};
 use super::models::ConfigInputMode;
 use super::rendering::ui;
 
-pub async fn run_tui(directory: Option<String>, selected_files: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run_tui(
+    directory: Option<String>,
+    selected_files: Vec<String>,
+    exclude_patterns: Vec<Regex>,
+    processing_delay: Option<Duration>,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -23,14 +33,28 @@ pub async fn run_tui(directory: Option<String>, selected_files: Vec<String>) ->
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and run it
-    let mut app = if !selected_files.is_empty() {
+    let mut app = if let [file] = selected_files.as_slice() {
+        // A lone file gets a quick rename first; Esc falls through to the guided setup
+        let mut app = App::with_selected_files(selected_files.clone());
+        app.show_rename_dialog(file);
+        app
+    } else if !selected_files.is_empty() {
         App::with_selected_files(selected_files)
     } else if let Some(dir) = directory {
         App::with_directory(dir)
     } else {
         App::new()
     };
-    
+    app.exclude_patterns = exclude_patterns;
+    // Resume the last run first: its directory beats the settings file's, while a file
+    // type or provider set in the settings file still wins
+    let last_run_store = LastRunStore::new(LastRunStore::default_path());
+    app.apply_last_run(&last_run_store.load());
+    app.apply_settings(&Settings::load());
+    if let Some(delay) = processing_delay {
+        app.processing_delay = delay;
+    }
+
     let res = run_app(&mut terminal, &mut app).await;
 
     // Restore terminal
@@ -42,6 +66,12 @@ pub async fn run_tui(directory: Option<String>, selected_files: Vec<String>) ->
     )?;
     terminal.show_cursor()?;
 
+    if let Some(last_run) = app.last_run() {
+        if let Err(err) = last_run_store.save(&last_run) {
+            eprintln!("Could not remember this run's directory: {err}");
+        }
+    }
+
     if let Err(err) = res {
         println!("{err:?}");
     }
@@ -59,125 +89,328 @@ async fn run_app<B: ratatui::backend::Backend>(
         
         terminal.draw(|f| ui(f, app))?;
 
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+        // A run in progress only waits out the optional delay before its next file
+        let timeout = if app.is_processing() { app.processing_delay } else { Duration::from_millis(100) };
+        if event::poll(timeout)? {
+            let event = event::read()?;
+            // Repaint from scratch at the new size straight away rather than on the next tick,
+            // so the old frame's leftovers don't linger
+            if let Event::Resize(..) = event {
+                terminal.autoresize()?;
+                terminal.clear()?;
+                continue;
+            }
+            if let Event::Key(key) = event {
                 if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            if app.show_help {
+                    // Any key dismisses an error popup
+                    if app.error_popup.is_some() {
+                        app.dismiss_error();
+                        continue;
+                    }
+
+                    // Quitting with work outstanding needs a second confirmation
+                    if app.confirm_quit {
+                        match key.code {
+                            KeyCode::Char('y') | KeyCode::Char('q') | KeyCode::Enter => return Ok(()),
+                            _ => app.confirm_quit = false,
+                        }
+                        continue;
+                    }
+
+                    // Starting a run renames the whole batch, so it's confirmed first
+                    if app.confirm_process {
+                        app.confirm_process = false;
+                        if matches!(key.code, KeyCode::Char('y') | KeyCode::Enter) {
+                            // The renames run one per tick below, so the progress bar keeps up
+                            app.start_processing();
+                        }
+                        continue;
+                    }
+
+                    // The quick rename of a lone file exits once it succeeds
+                    if let Some(dialog) = app.rename_dialog.as_mut() {
+                        let editor = &mut dialog.editor;
+                        match key.code {
+                            KeyCode::Esc => app.cancel_rename_dialog(),
+                            KeyCode::Enter => {
+                                if app.confirm_rename_dialog() {
+                                    return Ok(());
+                                }
+                            }
+                            KeyCode::Backspace => editor.backspace(),
+                            KeyCode::Delete => editor.delete(),
+                            KeyCode::Left => editor.move_left(),
+                            KeyCode::Right => editor.move_right(),
+                            KeyCode::Home => editor.move_home(),
+                            KeyCode::End => editor.move_end(),
+                            KeyCode::Char(c) => editor.insert(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // While editing a name every key goes to the editor
+                    if let Some(editor) = app.name_edit.as_mut() {
+                        match key.code {
+                            KeyCode::Esc => app.cancel_name_edit(),
+                            KeyCode::Enter => app.confirm_name_edit(),
+                            KeyCode::Backspace => editor.backspace(),
+                            KeyCode::Delete => editor.delete(),
+                            KeyCode::Left => editor.move_left(),
+                            KeyCode::Right => editor.move_right(),
+                            KeyCode::Home => editor.move_home(),
+                            KeyCode::End => editor.move_end(),
+                            KeyCode::Char(c) => editor.insert(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // While typing a season only digits (and an S prefix) are taken
+                    if let Some(season) = app.season_edit.as_mut() {
+                        match key.code {
+                            KeyCode::Esc => app.cancel_season_edit(),
+                            KeyCode::Enter => {
+                                if let Err(e) = app.apply_season_edit().await {
+                                    app.show_error(&e);
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                season.pop();
+                            }
+                            KeyCode::Char(c) if c.is_ascii_digit() || c == 'S' || c == 's' => season.push(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // While typing a filter every key edits the query
+                    if app.filter_editing {
+                        match key.code {
+                            KeyCode::Esc => app.clear_filter(),
+                            KeyCode::Enter => app.finish_filter(),
+                            KeyCode::Backspace => app.pop_filter_char(),
+                            KeyCode::Down => app.next(),
+                            KeyCode::Up => app.previous(),
+                            KeyCode::Char(c) => app.push_filter_char(c),
+                            _ => {}
+                        }
+                        continue;
+                    }
+
+                    // Text fields take every character, including ones that are shortcuts elsewhere
+                    if let KeyCode::Char(c) = key.code {
+                        if app.is_text_input_mode() && !app.show_help {
+                            app.handle_config_input(c);
+                            continue;
+                        }
+                    }
+
+                    match main_action(key.code) {
+                        Some(Action::Quit) => {
+                            if key.code == KeyCode::Esc && !app.show_help && !app.filter.is_empty() {
+                                app.clear_filter();
+                            } else if app.show_help {
                                 app.toggle_help();
-                            } else if app.show_config {
-                                return Ok(());
-                            } else {
+                            } else if app.request_quit() {
                                 return Ok(());
                             }
                         }
-                        KeyCode::Char('h') => app.toggle_help(),
-                        KeyCode::Char('p') => {
+                        Some(Action::Filter) if !app.show_config => app.start_filter(),
+                        Some(Action::ToggleHelp) => app.toggle_help(),
+                        Some(Action::TogglePreview) => {
                             if !app.show_config {
                                 app.toggle_preview();
                             }
                         }
-                        KeyCode::Down | KeyCode::Char('j') => {
+                        Some(Action::MoveDown) => {
                             if !app.show_config {
                                 app.next();
                             } else {
                                 app.handle_config_navigation(KeyCode::Down);
                             }
                         }
-                        KeyCode::Up | KeyCode::Char('k') => {
+                        Some(Action::MoveUp) => {
                             if !app.show_config {
                                 app.previous();
                             } else {
                                 app.handle_config_navigation(KeyCode::Up);
                             }
                         }
-                        KeyCode::Left => {
-                            if app.show_config {
-                                app.handle_config_navigation(KeyCode::Left);
-                            }
-                        }
-                        KeyCode::Right => {
-                            if app.show_config {
-                                app.handle_config_navigation(KeyCode::Right);
-                            }
-                        }
-                        KeyCode::Enter => {
+                        Some(Action::Process) if key.code == KeyCode::Enter || !app.show_config => {
                             if app.show_config {
                                 if app.config_input_mode == ConfigInputMode::Confirm {
                                     // Create engine
-                                    if let Err(_e) = app.create_rename_engine().await {
-                                        // Show error
+                                    if let Err(e) = app.create_rename_engine().await {
+                                        app.show_error(&e);
                                         continue;
                                     }
                                     
                                     // Process files based on whether they were pre-selected or scanned
                                     if !app.files.is_empty() {
                                         // Files were pre-selected, process them
-                                        if let Err(_e) = app.process_selected_files().await {
-                                            // Show error
+                                        if let Err(e) = app.process_selected_files().await {
+                                            app.show_error(&e);
                                             continue;
                                         }
                                     } else {
                                         // Scan directory for files
-                                        if let Err(_e) = app.scan_directory().await {
-                                            // Show error
+                                        if let Err(e) = app.scan_directory().await {
+                                            app.show_error(&e);
                                             continue;
                                         }
                                     }
+                                    // The last session's renames in this directory can be undone from the file list
+                                    app.load_last_session();
                                 } else {
                                     app.advance_config_step();
                                 }
-                            } else if !app.finished {
-                                let _ = app.process_files().await;
+                            } else {
+                                app.request_process();
+                            }
+                        }
+                        Some(Action::Undo) => {
+                            // Undo this run's renames, or the last session's loaded from the undo log
+                            if !app.undo_operations.is_empty() && !app.show_config && app.current_processing.is_none() {
+                                if let Err(e) = app.undo_renames().await {
+                                    app.show_error(&e);
+                                }
                             }
                         }
-                        KeyCode::Char('u') => {
-                            // Undo renames if finished and have undo operations
-                            if app.finished && !app.undo_operations.is_empty() && !app.show_config {
-                                let _ = app.undo_renames().await;
+                        Some(Action::UndoDirectory) => {
+                            // Undo only the renames in the selected file's directory, this run's or the last session's
+                            if !app.undo_operations.is_empty() && !app.show_config && app.current_processing.is_none() {
+                                if let Err(e) = app.undo_selected_directory().await {
+                                    app.show_error(&e);
+                                }
                             }
                         }
-                        KeyCode::Char(c) => {
-                            if app.show_config {
-                                app.handle_config_input(c);
+                        Some(Action::CycleSort) if !app.show_config => {
+                            if app.current_processing.is_none() {
+                                app.cycle_sort_mode();
                             }
                         }
-                        KeyCode::Backspace => {
-                            if app.show_config {
-                                // Handle backspace for navigation or text input
-                                match app.config_input_mode {
-                                    ConfigInputMode::Directory | 
-                                    ConfigInputMode::Season | 
-                                    ConfigInputMode::Year | 
-                                    ConfigInputMode::MovieYears | 
-                                    ConfigInputMode::ImdbId => {
-                                        app.handle_config_input('\x08');
+                        Some(Action::EditName) if !app.show_config => {
+                            if !app.finished && app.current_processing.is_none() {
+                                app.start_name_edit();
+                            }
+                        }
+                        Some(Action::OverrideSeason) if !app.show_config => {
+                            if !app.finished && app.current_processing.is_none() {
+                                app.start_season_edit();
+                            }
+                        }
+                        Some(Action::ToggleSkipped) if !app.show_config => {
+                            if !app.finished && app.current_processing.is_none() {
+                                app.toggle_skipped();
+                            }
+                        }
+                        Some(Action::OpenFolder) if !app.show_config => app.open_selected_folder(),
+                        Some(Action::CopyName) if !app.show_config => app.copy_selected_name(false),
+                        Some(Action::CopyPath) if !app.show_config => app.copy_selected_name(true),
+                        Some(Action::SkipAll) if !app.show_config => {
+                            if !app.finished && app.current_processing.is_none() {
+                                app.set_visible_skipped(true);
+                            }
+                        }
+                        Some(Action::IncludeAll) if !app.show_config => {
+                            if !app.finished && app.current_processing.is_none() {
+                                app.set_visible_skipped(false);
+                            }
+                        }
+                        Some(Action::Redo) if !app.show_config => {
+                            // Redo is only offered straight after an undo
+                            if !app.redo_operations.is_empty() {
+                                app.redo_renames();
+                            }
+                        }
+                        Some(Action::ToggleChecked) if !app.show_config => {
+                            if !app.finished && app.current_processing.is_none() {
+                                app.toggle_checked();
+                            }
+                        }
+                        // Everything else belongs to the setup steps
+                        _ => match key.code {
+                            KeyCode::Left => {
+                                if app.show_config {
+                                    app.handle_config_navigation(KeyCode::Left);
+                                }
+                            }
+                            KeyCode::Right => {
+                                if app.show_config {
+                                    app.handle_config_navigation(KeyCode::Right);
+                                }
+                            }
+                            KeyCode::Tab => {
+                                if app.show_config && matches!(app.config_input_mode, ConfigInputMode::ImdbId | ConfigInputMode::TmdbApiKey | ConfigInputMode::OmdbApiKey) {
+                                    app.test_imdb_connection().await;
+                                } else if app.show_config && app.config_input_mode == ConfigInputMode::Season && app.has_mixed_seasons() {
+                                    app.toggle_per_file_seasons();
+                                } else if app.show_config && app.config_input_mode == ConfigInputMode::ShowName {
+                                    if let Some(name) = app.suggested_show_name() {
+                                        app.show_name_input = name;
+                                        app.request_refresh();
                                     }
-                                    _ => {
-                                        app.handle_config_navigation(KeyCode::Backspace);
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                if app.show_config {
+                                    app.handle_config_input(c);
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                if app.show_config {
+                                    // Handle backspace for navigation or text input
+                                    match app.config_input_mode {
+                                        ConfigInputMode::Directory | 
+                                        ConfigInputMode::Season | 
+                                        ConfigInputMode::ShowName |
+                                        ConfigInputMode::CustomPattern |
+                                        ConfigInputMode::Year | 
+                                        ConfigInputMode::MovieYears | 
+                                        ConfigInputMode::ImdbId |
+                                        ConfigInputMode::TmdbApiKey |
+                                        ConfigInputMode::OmdbApiKey => {
+                                            app.handle_config_input('\x08');
+                                        }
+                                        _ => {
+                                            app.handle_config_navigation(KeyCode::Backspace);
+                                        }
                                     }
                                 }
                             }
-                        }
-                        _ => {}
+                            _ => {}
+                        },
                     }
                 }
             }
         }
 
+        if app.is_processing() {
+            app.process_next_file().await;
+        }
+
         // Handle refresh flag for season/year changes
-        if app.needs_refresh && app.show_config {
+        if app.refresh_due() && app.show_config {
             app.needs_refresh = false;
             // Only refresh if we have valid input to avoid infinite refresh
             let should_refresh = match app.config_input_mode {
                 ConfigInputMode::Season => !app.season_input.is_empty(),
+                ConfigInputMode::ShowName => !app.season_input.is_empty(),
+                // Only complete patterns are previewed, not every keystroke of one
+                ConfigInputMode::CustomPattern => {
+                    !app.season_input.is_empty()
+                        && (app.custom_pattern_input.trim().is_empty() || compile_custom_pattern(&app.custom_pattern_input).is_ok())
+                }
                 ConfigInputMode::Year => true, // Year can be empty for single files
+                ConfigInputMode::Confirm => true, // Separator changes
                 _ => false,
             };
             
             if should_refresh {
-                let _ = app.refresh_selected_files().await;
+                if let Err(e) = app.refresh_selected_files().await {
+                    app.show_error(&e);
+                }
             }
         }
 
@@ -0,0 +1,118 @@
+//! The single list of keybindings: `events.rs` dispatches through it and the
+//! help popup is rendered from it, so the two can't drift apart.
+
+use crossterm::event::KeyCode;
+
+/// What a key does in the file list, or on the confirm step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    Process,
+    ToggleChecked,
+    ToggleSkipped,
+    SkipAll,
+    IncludeAll,
+    CopyName,
+    CopyPath,
+    OpenFolder,
+    EditName,
+    OverrideSeason,
+    CycleSort,
+    Undo,
+    UndoDirectory,
+    Redo,
+    Filter,
+    TogglePreview,
+    ToggleHelp,
+    Quit,
+    ToggleDryRun,
+    ToggleSeasonFolders,
+    ToggleNfo,
+    CycleSeparator,
+    ToggleReport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyBinding {
+    pub keys: &'static [KeyCode],
+    pub action: Action,
+    pub description: &'static str,
+}
+
+const fn bind(keys: &'static [KeyCode], action: Action, description: &'static str) -> KeyBinding {
+    KeyBinding { keys, action, description }
+}
+
+/// Keys for moving around the file list.
+pub const NAVIGATION_KEYS: &[KeyBinding] = &[
+    bind(&[KeyCode::Up, KeyCode::Char('k')], Action::MoveUp, "Move up"),
+    bind(&[KeyCode::Down, KeyCode::Char('j')], Action::MoveDown, "Move down"),
+];
+
+/// Keys acting on the file list once the setup steps are done.
+pub const ACTION_KEYS: &[KeyBinding] = &[
+    // Space mirrors Enter on the file list only; the setup steps leave it to text input
+    bind(&[KeyCode::Enter, KeyCode::Char(' ')], Action::Process, "Start processing (asks to confirm)"),
+    bind(&[KeyCode::Char('x')], Action::ToggleChecked, "Check file (only checked files are processed)"),
+    bind(&[KeyCode::Char('s')], Action::ToggleSkipped, "Exclude or include the selected file"),
+    bind(&[KeyCode::Char('a')], Action::SkipAll, "Exclude every listed file"),
+    bind(&[KeyCode::Char('A')], Action::IncludeAll, "Include every listed file"),
+    bind(&[KeyCode::Char('y')], Action::CopyName, "Copy the new name"),
+    bind(&[KeyCode::Char('Y')], Action::CopyPath, "Copy the full target path"),
+    bind(&[KeyCode::Char('f')], Action::OpenFolder, "Open the selected file's folder"),
+    bind(&[KeyCode::Char('e')], Action::EditName, "Edit the selected file's new name (before processing)"),
+    bind(&[KeyCode::Char('S')], Action::OverrideSeason, "Set one season for every file (TV shows, before processing)"),
+    bind(&[KeyCode::Char('o')], Action::CycleSort, "Cycle sort order (name, episode, status)"),
+    bind(&[KeyCode::Char('u')], Action::Undo, "Undo renames (after completion, or the last session's)"),
+    bind(&[KeyCode::Char('U')], Action::UndoDirectory, "Undo renames in the selected file's directory (this run's or the last session's)"),
+    bind(&[KeyCode::Char('r')], Action::Redo, "Redo the renames just undone"),
+    bind(&[KeyCode::Char('/')], Action::Filter, "Filter the file list by name (Esc clears it)"),
+    bind(&[KeyCode::Char('p')], Action::TogglePreview, "Toggle preview panel (file list only)"),
+    bind(&[KeyCode::Char('h'), KeyCode::F(1)], Action::ToggleHelp, "Toggle this help"),
+    bind(&[KeyCode::Char('q'), KeyCode::Esc], Action::Quit, "Quit application"),
+];
+
+/// Toggles on the confirm step that ends the setup.
+pub const CONFIRM_KEYS: &[KeyBinding] = &[
+    bind(&[KeyCode::Char('d'), KeyCode::Char('D')], Action::ToggleDryRun, "Toggle dry run"),
+    bind(&[KeyCode::Char('f'), KeyCode::Char('F')], Action::ToggleSeasonFolders, "Toggle season folders (TV shows)"),
+    bind(&[KeyCode::Char('n'), KeyCode::Char('N')], Action::ToggleNfo, "Toggle .nfo metadata files"),
+    bind(&[KeyCode::Char('w'), KeyCode::Char('W')], Action::CycleSeparator, "Change the word separator"),
+    bind(&[KeyCode::Char('r'), KeyCode::Char('R')], Action::ToggleReport, "Toggle the summary report"),
+];
+
+/// The help popup's sections, in display order.
+pub const HELP_SECTIONS: &[(&str, &[KeyBinding])] = &[
+    ("Navigation", NAVIGATION_KEYS),
+    ("Actions", ACTION_KEYS),
+    ("Confirm step", CONFIRM_KEYS),
+];
+
+fn lookup(bindings: &[KeyBinding], key: KeyCode) -> Option<Action> {
+    bindings.iter().find(|binding| binding.keys.contains(&key)).map(|binding| binding.action)
+}
+
+/// The file-list action bound to `key`, if any.
+pub fn main_action(key: KeyCode) -> Option<Action> {
+    lookup(NAVIGATION_KEYS, key).or_else(|| lookup(ACTION_KEYS, key))
+}
+
+/// The confirm-step toggle bound to `c`, if any.
+pub fn confirm_action(c: char) -> Option<Action> {
+    lookup(CONFIRM_KEYS, KeyCode::Char(c))
+}
+
+/// How a binding's keys are written in the help, e.g. `Up/k` or `h/F1`.
+pub fn key_label(binding: &KeyBinding) -> String {
+    binding.keys.iter().map(|key| match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        other => format!("{:?}", other),
+    }).collect::<Vec<_>>().join("/")
+}
@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{FileTypeSetting, ProviderSetting};
+
+/// Choices from the previous run, offered again on the next launch. Unlike the
+/// settings file this is written by the app itself, so it follows whatever was
+/// used last rather than what the user configured.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LastRun {
+    pub directory: Option<String>,
+    pub file_type: Option<FileTypeSetting>,
+    pub provider: Option<ProviderSetting>,
+}
+
+/// The last run's choices stored on disk as JSON. A missing or corrupt file
+/// reads as an empty [`LastRun`].
+#[derive(Debug, Clone)]
+pub struct LastRunStore {
+    path: PathBuf,
+}
+
+impl LastRunStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The file shared by every run, in the user's config directory next to the
+    /// settings file so it survives the temp directory being cleared.
+    pub fn default_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("jellyfin-renamer")
+            .join("last_run.json")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> LastRun {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, last_run: &LastRun) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(last_run)?)?;
+        Ok(())
+    }
+}
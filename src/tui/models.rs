@@ -1,3 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rename_engine::{MatchKind, RenameOperation};
+use crate::report::{ProcessingStatus, ReportEntry};
+
 #[derive(Debug, Clone)]
 pub struct FileItem {
     pub original_path: String,
@@ -6,16 +11,53 @@ pub struct FileItem {
     pub status: ProcessingStatus,
     pub error_message: Option<String>,
     pub episode_number: u32,
+    pub season_number: u32,
     pub episode_title: String,
+    /// The last title a title provider supplied for this file, kept so a season
+    /// change that finds none does not fall back to the title parsed from the filename.
+    pub provider_title: Option<String>,
+    /// The filename pattern the new name came from, shown in the preview.
+    pub match_kind: MatchKind,
+    pub checked: bool,
+    pub manually_edited: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ProcessingStatus {
-    Pending,
-    Processing,
-    Success,
-    Error,
-    Skipped,
+/// Prefix of the message given to files whose target name is already taken.
+pub const COLLISION_MESSAGE: &str = "Name collision";
+
+impl FileItem {
+    pub fn has_collision(&self) -> bool {
+        self.status == ProcessingStatus::Conflict
+    }
+
+    /// Skipped because the user excluded it, rather than because its name already matches.
+    pub fn is_user_skipped(&self) -> bool {
+        self.status == ProcessingStatus::Skipped && self.original_name != self.new_name
+    }
+
+    /// No pattern matched, so the file would keep its name. A name typed by hand counts as a match.
+    pub fn is_unmatched(&self) -> bool {
+        self.match_kind == MatchKind::Unmatched && !self.manually_edited
+    }
+
+    /// Where the file ends up: its new name in its current directory.
+    pub fn target_path(&self) -> String {
+        std::path::Path::new(&self.original_path)
+            .parent()
+            .map(|parent| parent.join(&self.new_name).to_string_lossy().to_string())
+            .unwrap_or_else(|| self.new_name.clone())
+    }
+}
+
+impl From<&FileItem> for ReportEntry {
+    fn from(file: &FileItem) -> Self {
+        Self {
+            original_name: file.original_name.clone(),
+            new_name: file.new_name.clone(),
+            status: file.status.clone(),
+            error_message: file.error_message.clone(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -23,26 +65,133 @@ pub enum ConfigInputMode {
     FileType,
     Directory,
     Season,
+    ShowName,
+    CustomPattern,
     Year,
     MovieYears, // New mode for individual movie year input
     ImdbChoice,
     ImdbId,
+    TmdbApiKey,
+    OmdbApiKey,
     Confirm,
 }
 
-#[derive(Debug, Default)]
-pub struct ProcessingStats {
-    pub total: usize,
-    pub processed: usize,
-    pub successful: usize,
-    pub failed: usize,
+/// Order of the main file list, cycled from the TUI.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SortMode {
+    OriginalName,
+    #[default]
+    Episode,
+    Status,
 }
 
-#[derive(Debug, Clone)]
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::OriginalName => SortMode::Episode,
+            SortMode::Episode => SortMode::Status,
+            SortMode::Status => SortMode::OriginalName,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::OriginalName => "name",
+            SortMode::Episode => "episode",
+            SortMode::Status => "status",
+        }
+    }
+}
+
+/// In-progress edit of one file's new name, with the cursor counted in characters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameEditor {
+    pub index: usize,
+    pub buffer: String,
+    pub cursor: usize,
+}
+
+impl NameEditor {
+    pub fn new(index: usize, name: &str) -> Self {
+        Self { index, buffer: name.to_string(), cursor: name.chars().count() }
+    }
+
+    fn byte_offset(&self, cursor: usize) -> usize {
+        self.buffer.char_indices().nth(cursor).map(|(offset, _)| offset).unwrap_or(self.buffer.len())
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let offset = self.byte_offset(self.cursor);
+        self.buffer.insert(offset, c);
+        self.cursor += 1;
+    }
+
+    /// Removes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let offset = self.byte_offset(self.cursor);
+            self.buffer.remove(offset);
+        }
+    }
+
+    /// Removes the character under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.buffer.chars().count() {
+            let offset = self.byte_offset(self.cursor);
+            self.buffer.remove(offset);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.buffer.chars().count());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.buffer.chars().count();
+    }
+}
+
+/// Quick rename of a single file passed on the command line, shown before the
+/// guided setup. The editor holds the stem; the extension is kept.
+#[derive(Debug)]
+pub struct RenameDialog {
+    pub operation: RenameOperation,
+    pub editor: NameEditor,
+}
+
+impl RenameDialog {
+    pub fn new(path: &str) -> Self {
+        let operation = RenameOperation::new(path);
+        let stem = std::path::Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Self { operation, editor: NameEditor::new(0, &stem) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UndoOperation {
     pub original_path: String,
     pub renamed_path: String,
-    #[allow(dead_code)]
     pub original_name: String,
     pub new_name: String,
+    /// Season folders created for this rename, outermost first; undo removes them once empty.
+    #[serde(default)]
+    pub created_dirs: Vec<String>,
+    /// `.nfo` files written for this rename; undo deletes them.
+    #[serde(default)]
+    pub created_files: Vec<String>,
+    /// A file this rename replaced, now in the trash; undo puts it back.
+    #[serde(default)]
+    pub trashed: Option<String>,
 }
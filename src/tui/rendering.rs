@@ -1,5 +1,5 @@
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Margin},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{
@@ -9,10 +9,12 @@ use ratatui::{
     Frame,
 };
 
-use crate::rename_engine::FileType;
+use crate::rename_engine::{compile_custom_pattern, FileType, MatchKind, TitleSource};
+use crate::report::ProcessingStatus;
 use super::app::App;
-use super::models::{ProcessingStatus, ConfigInputMode};
-use super::utils::centered_rect;
+use super::keymap::{key_label, HELP_SECTIONS};
+use super::models::ConfigInputMode;
+use super::utils::{centered_rect, estimate_remaining, format_mm_ss};
 
 pub fn ui(f: &mut Frame, app: &App) {
     let size = f.area();
@@ -21,9 +23,35 @@ pub fn ui(f: &mut Frame, app: &App) {
         render_config_screen(f, size, app);
     } else {
         render_main_screen(f, size, app);
-    }    if app.show_help {
+    }
+
+    if app.show_help {
         render_help_popup(f, app);
     }
+
+    if app.name_edit.is_some() {
+        render_name_edit_popup(f, app);
+    }
+
+    if app.rename_dialog.is_some() {
+        render_rename_dialog_popup(f, app);
+    }
+
+    if app.season_edit.is_some() {
+        render_season_edit_popup(f, app);
+    }
+
+    if app.confirm_quit {
+        render_quit_confirm_popup(f, app);
+    }
+
+    if app.confirm_process {
+        render_process_confirm_popup(f, app);
+    }
+
+    if app.error_popup.is_some() {
+        render_error_popup(f, app);
+    }
 }
 
 pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
@@ -45,7 +73,7 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
         );    f.render_widget(header, chunks[0]);
 
     let has_multiple_files = app.files.len() > 1;
-    let is_tv_show = app.file_type == FileType::TvShow;
+    let is_tv_show = app.file_type.has_episodes();
     let is_multiple_movies = app.file_type == FileType::Movie && has_multiple_files;
     
     let mut form_constraints = vec![
@@ -55,6 +83,8 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
     
     if is_tv_show {
         form_constraints.push(Constraint::Length(3));
+        form_constraints.push(Constraint::Length(3));
+        form_constraints.push(Constraint::Length(3));
     }
     
     // Year input only for single movies, never for TV shows
@@ -71,9 +101,14 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
         if app.use_imdb || app.config_input_mode == ConfigInputMode::ImdbId {
             form_constraints.push(Constraint::Length(3));
         }
+        if app.use_imdb && matches!(app.title_source, TitleSource::Tmdb | TitleSource::Omdb) {
+            form_constraints.push(Constraint::Length(3));
+        }
     }
     
-    form_constraints.push(Constraint::Length(3));
+    // Confirm box holds the action, the dry-run toggle and any collision warning
+    let confirm_lines = 7 + usize::from(is_tv_show) + usize::from(app.collision_count() > 0);
+    form_constraints.push(Constraint::Length(confirm_lines as u16));
     form_constraints.push(Constraint::Min(1));
     
     let form_chunks = Layout::default()
@@ -81,13 +116,17 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
         .constraints(form_constraints)
         .split(chunks[1]);    let mut current_chunk_index = 0;
 
-    let file_type_text = if app.config_input_mode == ConfigInputMode::FileType {
-        "Press T for TV Shows, M for Movies"
+    let file_type_name = match app.file_type {
+        FileType::TvShow => "TV Shows",
+        FileType::Movie => "Movies",
+        FileType::Auto => "TV Shows and Movies",
+    };
+    let file_type_text = if app.config_input_mode != ConfigInputMode::FileType {
+        file_type_name.to_string()
+    } else if app.detected_file_type.is_some() {
+        format!("Press T for TV Shows, M for Movies, A for both, ENTER for {} (detected)", file_type_name)
     } else {
-        match app.file_type {
-            FileType::TvShow => "TV Shows",
-            FileType::Movie => "Movies",
-        }
+        format!("Press T for TV Shows, M for Movies, A for both, ENTER for {}", file_type_name)
     };
     
     let file_type_input = Paragraph::new(file_type_text)
@@ -110,6 +149,10 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
     current_chunk_index += 1;
 
     // Directory input
+    let directory_title = match &app.directory_error {
+        Some(error) => format!("Directory Path - {}", error),
+        None => "Directory Path".to_string(),
+    };
     let directory_input = Paragraph::new(app.directory_input.as_str())
         .style(if app.config_input_mode == ConfigInputMode::Directory {
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
@@ -119,8 +162,10 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Directory Path")
-                .border_style(if app.config_input_mode == ConfigInputMode::Directory {
+                .title(directory_title)
+                .border_style(if app.directory_error.is_some() {
+                    Style::default().fg(Color::Red)
+                } else if app.config_input_mode == ConfigInputMode::Directory {
                     Style::default().fg(Color::Yellow)
                 } else {
                     Style::default().fg(Color::Gray)
@@ -155,22 +200,81 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
                 Block::default()
                     .borders(Borders::ALL)
                     .title(season_title)
-                    .border_style(if app.config_input_mode == ConfigInputMode::Season {
-                        if app.season_input.is_empty() {
-                            Style::default().fg(Color::Red) // Red border if empty and focused
-                        } else {
-                            Style::default().fg(Color::Green) // Green border if auto-detected and focused
-                        }
+                    .border_style(if app.season_input.is_empty() {
+                        Style::default().fg(Color::Red) // Red border if empty
                     } else {
-                        if app.season_input.is_empty() {
-                            Style::default().fg(Color::Red) // Red border if empty
-                        } else {
-                            Style::default().fg(Color::Green) // Green border if filled
-                        }
+                        Style::default().fg(Color::Green) // Green border if auto-detected or filled
                     }),
             );
         f.render_widget(season_input, form_chunks[current_chunk_index]);
         current_chunk_index += 1;
+
+        // Show name prefix (optional)
+        let show_name_active = app.config_input_mode == ConfigInputMode::ShowName;
+        let show_name_display = if !app.show_name_input.is_empty() {
+            app.show_name_input.clone()
+        } else if let Some(suggestion) = app.suggested_show_name().filter(|_| show_name_active) {
+            format!("[Tab to use \"{}\"]", suggestion)
+        } else {
+            "[No prefix]".to_string()
+        };
+
+        let show_name_input = Paragraph::new(show_name_display.as_str())
+            .style(if show_name_active {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Show Name (optional)")
+                    .border_style(if show_name_active {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    }),
+            );
+        f.render_widget(show_name_input, form_chunks[current_chunk_index]);
+        current_chunk_index += 1;
+
+        // Custom episode pattern (optional)
+        let pattern_active = app.config_input_mode == ConfigInputMode::CustomPattern;
+        let pattern_error = if app.custom_pattern_input.trim().is_empty() {
+            None
+        } else {
+            compile_custom_pattern(&app.custom_pattern_input).err()
+        };
+        let pattern_display = if app.custom_pattern_input.is_empty() {
+            "[Built-in patterns only]".to_string()
+        } else {
+            app.custom_pattern_input.clone()
+        };
+        let pattern_title = match &pattern_error {
+            Some(e) => format!("Custom Pattern - {}", e),
+            None => "Custom Pattern (optional)".to_string(),
+        };
+
+        let pattern_input = Paragraph::new(pattern_display.as_str())
+            .style(if pattern_active {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(pattern_title)
+                    .border_style(if pattern_error.is_some() {
+                        Style::default().fg(Color::Red)
+                    } else if pattern_active {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    }),
+            );
+        f.render_widget(pattern_input, form_chunks[current_chunk_index]);
+        current_chunk_index += 1;
     }
 
     // Year input for single movies only (removed TV show condition)
@@ -256,9 +360,13 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
     // IMDb choice (only for TV shows with multiple files)
     if is_tv_show && has_multiple_files {
         let imdb_text = if app.config_input_mode == ConfigInputMode::ImdbChoice {
-            "Press y for Yes, n for No"
+            "Press y for IMDb, t for TMDB, o for OMDb, n for No"
+        } else if app.use_imdb && app.title_source == TitleSource::Tmdb {
+            "TMDB"
+        } else if app.use_imdb && app.title_source == TitleSource::Omdb {
+            "OMDb"
         } else if app.use_imdb {
-            "Yes"
+            "IMDb"
         } else {
             "No"
         };
@@ -272,7 +380,7 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Use IMDb for episode titles?")
+                    .title("Fetch episode titles?")
                     .border_style(if app.config_input_mode == ConfigInputMode::ImdbChoice {
                         Style::default().fg(Color::Yellow)
                     } else {
@@ -296,7 +404,11 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("IMDb ID (e.g., tt0944947)")
+                    .title(if app.title_source == TitleSource::Tmdb {
+                        "TMDB series ID (e.g., 1396)"
+                    } else {
+                        "IMDb ID (e.g., tt0944947)"
+                    })
                     .border_style(if app.config_input_mode == ConfigInputMode::ImdbId {
                         Style::default().fg(Color::Yellow)
                     } else {
@@ -307,13 +419,77 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
         current_chunk_index += 1;
     }
 
+    // API key input (only when TMDB or OMDb is the title source)
+    if is_tv_show && has_multiple_files && app.use_imdb && matches!(app.title_source, TitleSource::Tmdb | TitleSource::Omdb) {
+        let (key_mode, key_text, key_title) = if app.title_source == TitleSource::Tmdb {
+            (ConfigInputMode::TmdbApiKey, app.tmdb_api_key_input.as_str(), "TMDB API key")
+        } else {
+            (ConfigInputMode::OmdbApiKey, app.omdb_api_key_input.as_str(), "OMDb API key")
+        };
+        let key_style = if app.config_input_mode == key_mode {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+
+        // Keys are secrets; show one `*` per character so typos still line up
+        let key_input = Paragraph::new("*".repeat(key_text.chars().count()))
+            .style(key_style)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(key_title)
+                    .border_style(if app.config_input_mode == key_mode {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    }),
+            );
+        f.render_widget(key_input, form_chunks[current_chunk_index]);
+        current_chunk_index += 1;
+    }
+
     // Confirm button
     if app.config_input_mode == ConfigInputMode::Confirm {
-        let confirm_text = if app.files.is_empty() {
+        let action_text = if app.files.is_empty() {
             "Press ENTER to scan directory and start"
         } else {
             "Press ENTER to process selected files"
         };
+        let dry_run_text = if app.dry_run {
+            "Dry run: ON - nothing will be renamed (D to toggle)"
+        } else {
+            "Dry run: OFF (D to toggle)"
+        };
+        let mut confirm_text = vec![Line::from(action_text), Line::from(dry_run_text)];
+        confirm_text.push(Line::from(if app.write_nfo {
+            "NFO metadata: ON - .nfo files are written next to each file (N to toggle)"
+        } else {
+            "NFO metadata: OFF (N to toggle)"
+        }));
+        confirm_text.push(Line::from(match app.separator {
+            ' ' => "Word separator: space (W to change)",
+            '.' => "Word separator: dot (W to change)",
+            _ => "Word separator: underscore (W to change)",
+        }));
+        confirm_text.push(Line::from(if app.write_report {
+            format!("Summary report: ON - written to {} (R to toggle)", app.summary_report_path().display())
+        } else {
+            "Summary report: OFF (R to toggle)".to_string()
+        }));
+        if is_tv_show {
+            confirm_text.push(Line::from(if app.organize_into_folders {
+                "Season folders: ON - episodes move into Season NN (F to toggle)"
+            } else {
+                "Season folders: OFF (F to toggle)"
+            }));
+        }
+        if app.collision_count() > 0 {
+            confirm_text.push(Line::from(Span::styled(
+                format!("{} files have name collisions and will be left alone (← Back to adjust)", app.collision_count()),
+                Style::default().fg(Color::Red),
+            )));
+        }
         
         let confirm = Paragraph::new(confirm_text)
             .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
@@ -329,31 +505,58 @@ pub fn render_config_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
 
     // Instructions - Update to include navigation hints
     let instructions = match app.config_input_mode {
-        ConfigInputMode::FileType => "Choose file type: T for TV Shows, M for Movies",
-        ConfigInputMode::Directory => "Enter the directory path containing your video files (← Back)",
+        ConfigInputMode::FileType => "Choose file type: T for TV Shows, M for Movies, A to decide per file",
+        ConfigInputMode::Directory => "Enter the directory path containing your video files; ~ and $VARIABLES are expanded (← Back)",
         ConfigInputMode::Season => {
-            if app.season_input.is_empty() {
-                "Season number is REQUIRED (e.g., S01, S1, 1, or 01) (← Back)"
+            if app.has_mixed_seasons() {
+                if app.use_per_file_seasons {
+                    "Using each file's detected season - Tab to force the entered season (← Back)"
+                } else {
+                    "Files span several seasons! Tab to use each file's detected season (← Back)"
+                }
+            } else if app.season_input.is_empty() {
+                "Season number is REQUIRED (e.g., S01, S1, 1, or 01; S00 for specials) (← Back)"
             } else {
                 "Season auto-detected! Press Enter to continue or type to edit (← Back)"
             }
         },
+        ConfigInputMode::ShowName => "Prefix episode names with the show name, or leave blank to skip (Tab for suggestion, ← Back)",
+        ConfigInputMode::CustomPattern => "Regex with named groups title, season, episode and extension, tried first; blank to skip (← Back)",
         ConfigInputMode::Year => {
-            if app.file_type == FileType::TvShow && app.files.len() == 1 {
+            if app.file_type.has_episodes() && app.files.len() == 1 {
                 "Year is REQUIRED for single TV episodes (e.g., 2023) (← Back)"
             } else {
                 "Enter year or leave blank (press Enter to skip) (← Back)"
             }
         },
         ConfigInputMode::MovieYears => "Enter year for each movie (optional) (↑/↓ or ←/→ to navigate, ← Back)",
-        ConfigInputMode::ImdbChoice => "Would you like to fetch episode titles from IMDb? (← Back)",
-        ConfigInputMode::ImdbId => "Enter the IMDb series ID (found in the URL) (← Back)",
-        ConfigInputMode::Confirm => "Review your settings and press Enter to continue (← Back)",
+        ConfigInputMode::ImdbChoice => "Fetch episode titles from IMDb, TMDB or OMDb? (← Back)",
+        ConfigInputMode::ImdbId => {
+            if app.title_source == TitleSource::Tmdb {
+                "Enter the TMDB series ID (found in the URL) (← Back)"
+            } else {
+                "Enter the IMDb series ID (found in the URL), Tab to test it (← Back)"
+            }
+        },
+        ConfigInputMode::TmdbApiKey => "Enter your TMDB API key, Tab to test it (← Back)",
+        ConfigInputMode::OmdbApiKey => "Enter your OMDb API key (omdbapi.com/apikey.aspx), Tab to test it (← Back)",
+        ConfigInputMode::Confirm => {
+            if is_tv_show {
+                "Review your settings, D to toggle dry run, F for season folders, N for .nfo files, W for the separator, R for a report, Enter to continue (← Back)"
+            } else {
+                "Review your settings, D to toggle dry run, N for .nfo files, W for the separator, R for a report, Enter to continue (← Back)"
+            }
+        },
+    };
+
+    let status_line = match &app.status_message {
+        Some(status_msg) => Line::from(Span::styled(status_msg.clone(), Style::default().fg(Color::Cyan))),
+        None => Line::from(""),
     };
 
     let help_lines = vec![
         Line::from(instructions),
-        Line::from(""),
+        status_line,
         Line::from("Navigation: ← Back | Enter: Next/Confirm | Esc: Quit"),
     ];
 
@@ -384,6 +587,8 @@ pub fn render_main_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &App)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(10),
+            // Status messages get their own line so they never hide the progress
+            Constraint::Length(u16::from(app.status_message.is_some())),
             Constraint::Length(3),
         ])
         .split(chunks[0]);
@@ -394,8 +599,10 @@ pub fn render_main_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &App)
     // File list
     render_file_list(f, left_chunks[1], app);
 
+    render_status_message(f, left_chunks[2], app);
+
     // Status bar
-    render_status_bar(f, left_chunks[2], app);
+    render_status_bar(f, left_chunks[3], app);
 
     // Preview panel (if enabled)
     if app.show_preview && chunks.len() > 1 {
@@ -404,12 +611,18 @@ pub fn render_main_screen(f: &mut Frame, area: ratatui::layout::Rect, app: &App)
 }
 
 pub fn render_header(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
-    let title = if app.finished {
-        "Jellyfin Rename Tool - Completed!"
+    let title = if app.finished && app.dry_run {
+        "Jellyfin Rename Tool - Dry Run Completed".to_string()
+    } else if app.finished {
+        "Jellyfin Rename Tool - Completed!".to_string()
     } else if app.current_processing.is_some() {
-        "Jellyfin Rename Tool - Processing..."
+        "Jellyfin Rename Tool - Processing...".to_string()
+    } else if app.file_type.has_episodes() && !app.files.is_empty() {
+        // The season the previews were built with, which S overrides
+        let season = if app.use_per_file_seasons { "per-file seasons" } else { app.season_input.as_str() };
+        format!("Jellyfin Rename Tool - {}", season)
     } else {
-        "Jellyfin Rename Tool"
+        "Jellyfin Rename Tool".to_string()
     };
 
     let header = Paragraph::new(title)
@@ -425,21 +638,27 @@ pub fn render_header(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
 }
 
 pub fn render_file_list(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
-    let items: Vec<ListItem> = app
-        .files
+    let visible = app.visible_indices();
+    let items: Vec<ListItem> = visible
         .iter()
-        .enumerate()
-        .map(|(i, file)| {
+        .map(|&i| {
+            let file = &app.files[i];
             let (icon, color) = match file.status {
+                ProcessingStatus::Pending | ProcessingStatus::Skipped if file.is_unmatched() => ("[NO MATCH]", Color::LightRed),
+                ProcessingStatus::Skipped if file.is_user_skipped() => ("[EXCLUDED]", Color::DarkGray),
                 ProcessingStatus::Pending => ("[PENDING]", Color::Yellow),
                 ProcessingStatus::Processing => ("[PROCESSING]", Color::Blue),
                 ProcessingStatus::Success => ("[SUCCESS]", Color::Green),
                 ProcessingStatus::Error => ("[ERROR]", Color::Red),
                 ProcessingStatus::Skipped => ("[SKIPPED]", Color::Gray),
+                ProcessingStatus::Conflict => ("[CONFLICT]", Color::Magenta),
             };
 
+            let checkbox = if file.checked { "[x] " } else { "[ ] " };
+
             let line = if app.current_processing == Some(i) {
                 Line::from(vec![
+                    Span::raw(checkbox),
                     Span::styled(format!("{} ", icon), Style::default().fg(color)),
                     Span::styled(
                         file.original_name.clone(),
@@ -448,6 +667,7 @@ pub fn render_file_list(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
                 ])
             } else {
                 Line::from(vec![
+                    Span::raw(checkbox),
                     Span::styled(format!("{} ", icon), Style::default().fg(color)),
                     Span::styled(file.original_name.clone(), Style::default().fg(Color::White)),
                 ])
@@ -457,10 +677,18 @@ pub fn render_file_list(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
         })
         .collect();
 
+    let title = if app.filter_editing {
+        format!("Filter: {}_ (Enter to keep, Esc to clear)", app.filter)
+    } else if !app.filter.is_empty() {
+        format!("Files to Process [sort: {}] - filter: {} (/ to edit, Esc to clear)", app.sort_mode.label(), app.filter)
+    } else {
+        format!("Files to Process [sort: {}]", app.sort_mode.label())
+    };
+
     let files_list = List::new(items)
         .block(
             Block::default()
-                .title("Files to Process")
+                .title(title)
                 .borders(Borders::ALL)
                 .style(Style::default().fg(Color::White))
                 .border_style(Style::default().fg(Color::Blue)),
@@ -473,10 +701,14 @@ pub fn render_file_list(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
         )
         .highlight_symbol("> ");
 
-    f.render_stateful_widget(files_list, area, &mut app.list_state.clone());
+    // The list only holds the visible files, so select by position within them
+    let mut list_state = app.list_state.clone();
+    list_state.select(app.selected_position(&visible));
+    f.render_stateful_widget(files_list, area, &mut list_state);
 
-    // Render scrollbar
-    if app.files.len() > area.height as usize - 2 {
+    // Render scrollbar, when the list overflows and there is room for one inside the borders
+    let inner_height = area.height.saturating_sub(2) as usize;
+    if inner_height > 0 && visible.len() > inner_height {
         let scrollbar = Scrollbar::default()
             .orientation(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("^"))
@@ -493,6 +725,17 @@ pub fn render_file_list(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
     }
 }
 
+/// The current status message on a line of its own above the progress bar,
+/// until it expires.
+pub fn render_status_message(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
+    if let Some(status_msg) = &app.status_message {
+        let message = Paragraph::new(status_msg.as_str())
+            .style(Style::default().fg(Color::Cyan))
+            .alignment(Alignment::Center);
+        f.render_widget(message, area);
+    }
+}
+
 pub fn render_status_bar(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -500,34 +743,54 @@ pub fn render_status_bar(f: &mut Frame, area: ratatui::layout::Rect, app: &App)
         .split(area);
 
     // Progress bar
-    let progress_label = if let Some(status_msg) = &app.status_message {
-        // Show status message instead of progress when available
-        status_msg.clone()
+    let progress_label = if app.finished && app.dry_run {
+        format!("Dry run: {} files would be renamed", app.stats.successful)
     } else if app.finished {
-        format!("Complete! {} successful, {} failed", app.stats.successful, app.stats.failed)
+        let label = format!("Complete! {} successful, {} failed, {} skipped", app.stats.successful, app.stats.failed, app.stats.skipped);
+        match app.elapsed() {
+            Some(elapsed) => format!("{} in {}", label, format_mm_ss(elapsed)),
+            None => label,
+        }
     } else if app.current_processing.is_some() {
-        format!("Processing... {}/{}", app.stats.processed + 1, app.stats.total)
+        let label = format!("Processing... {}/{}", app.stats.processed + 1, app.stats.total);
+        let elapsed = app.elapsed().unwrap_or_default();
+        match estimate_remaining(elapsed, app.stats.processed, app.stats.total) {
+            Some(remaining) => format!("{} - {} elapsed, ~{} left", label, format_mm_ss(elapsed), format_mm_ss(remaining)),
+            None => format!("{} - {} elapsed", label, format_mm_ss(elapsed)),
+        }
+    } else if app.checked_count() > 0 {
+        format!("Ready to process {} of {} files (checked only)", app.checked_count(), app.stats.total)
+    } else if app.collision_count() > 0 {
+        format!("Ready to process {} files ({} name collisions)", app.stats.total, app.collision_count())
+    } else if app.skipped_count() > 0 {
+        format!("Ready to process {} of {} files ({} excluded)", app.stats.total.saturating_sub(app.skipped_count()), app.stats.total, app.skipped_count())
     } else {
         format!("Ready to process {} files", app.stats.total)
     };
 
-    let progress_style = if app.status_message.is_some() {
-        Style::default().fg(Color::Cyan) // Use different color for status messages
+    let progress_label = if app.filter.is_empty() {
+        progress_label
     } else {
-        Style::default().fg(Color::Green)
+        format!("{} - showing {} of {}", progress_label, app.visible_indices().len(), app.files.len())
     };
 
     let progress = Gauge::default()
         .block(Block::default().borders(Borders::ALL).title("Progress"))
-        .gauge_style(progress_style)
+        .gauge_style(Style::default().fg(Color::Green))
         .percent((app.processing_progress * 100.0) as u16)
         .label(progress_label);
 
     f.render_widget(progress, chunks[0]);
 
     // Controls hint
-    let controls_text = if app.finished && !app.undo_operations.is_empty() {
+    let controls_text = if !app.redo_operations.is_empty() && app.finished {
+        "Press u to undo, r to redo, h for help, q to quit"
+    } else if !app.redo_operations.is_empty() {
+        "Press r to redo, ENTER to start, h for help, q to quit"
+    } else if app.finished && !app.undo_operations.is_empty() {
         "Press u to undo, h for help, q to quit"
+    } else if !app.undo_operations.is_empty() {
+        "Press u to undo last session, ENTER to start, h for help, q to quit"
     } else {
         "Press ENTER to start, h for help, q to quit"
     };
@@ -555,9 +818,27 @@ pub fn render_preview_panel(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
                     file.original_name.clone(),
                     Style::default().fg(Color::Red),
                 )),
+                Line::from(Span::styled(
+                    file.original_path.clone(),
+                    Style::default().fg(Color::DarkGray),
+                )),
                 Line::from(""),
                 Line::from(format!("Episode: {}", file.episode_number)),
                 Line::from(format!("Title: {}", file.episode_title)),
+                match file.match_kind {
+                    MatchKind::Unmatched if file.manually_edited => Line::from(Span::styled(
+                        "No pattern matched - name typed by hand",
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                    MatchKind::Unmatched => Line::from(Span::styled(
+                        "No pattern matched - name unchanged",
+                        Style::default().fg(Color::LightRed),
+                    )),
+                    kind => Line::from(Span::styled(
+                        format!("Matched: {}", kind.description()),
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                },
             ]))
             .block(
                 Block::default()
@@ -573,15 +854,53 @@ pub fn render_preview_panel(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
             let new_style = match file.status {
                 ProcessingStatus::Success => Style::default().fg(Color::Green),
                 ProcessingStatus::Error => Style::default().fg(Color::Red),
+                ProcessingStatus::Conflict => Style::default().fg(Color::Magenta),
                 _ => Style::default().fg(Color::Yellow),
             };
 
             let mut new_lines = vec![
                 Line::from("New:"),
                 Line::from(Span::styled(file.new_name.clone(), new_style)),
+                Line::from(Span::styled(app.target_path(file), Style::default().fg(Color::DarkGray))),
             ];
 
-            if let Some(error) = &file.error_message {
+            if file.manually_edited {
+                new_lines.push(Line::from(Span::styled(
+                    "(edited by hand)",
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+
+            if file.is_unmatched() {
+                new_lines.push(Line::from(""));
+                new_lines.push(Line::from(Span::styled(
+                    "Nothing will change - add a custom pattern (Custom Pattern step or --pattern) to rename it",
+                    Style::default().fg(Color::LightRed),
+                )));
+            } else if file.is_user_skipped() {
+                new_lines.push(Line::from(""));
+                new_lines.push(Line::from(Span::styled(
+                    "Excluded by you - press s to include it again",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            } else if file.status == ProcessingStatus::Skipped {
+                new_lines.push(Line::from(""));
+                new_lines.push(Line::from(Span::styled(
+                    "Already named correctly - nothing to rename",
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+
+            if file.has_collision() {
+                new_lines.push(Line::from(""));
+                new_lines.push(Line::from(Span::styled(
+                    format!("Conflict: {} is already taken and will not be renamed", app.target_path(file)),
+                    Style::default().fg(Color::Magenta),
+                )));
+                if let Some(reason) = &file.error_message {
+                    new_lines.push(Line::from(Span::styled(reason.clone(), Style::default().fg(Color::Magenta))));
+                }
+            } else if let Some(error) = &file.error_message {
                 new_lines.push(Line::from(""));
                 new_lines.push(Line::from(Span::styled(
                     format!("Error: {}", error),
@@ -603,25 +922,206 @@ pub fn render_preview_panel(f: &mut Frame, area: ratatui::layout::Rect, app: &Ap
     }
 }
 
-pub fn render_help_popup(f: &mut Frame, _app: &App) {
-    let popup_area = centered_rect(60, 50, f.area());
+pub fn render_name_edit_popup(f: &mut Frame, app: &App) {
+    let Some(editor) = &app.name_edit else { return };
 
-    let help_text = vec![
+    let area = centered_rect(70, 20, f.area());
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(3) / 2,
+        height: area.height.min(3),
+        ..area
+    };
+
+    let input = Paragraph::new(editor.buffer.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .title("Edit new name (Enter to save, Esc to cancel)")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(input, popup_area);
+    f.set_cursor_position(Position::new(
+        popup_area.x + 1 + editor.cursor as u16,
+        popup_area.y + 1,
+    ));
+}
+
+pub fn render_rename_dialog_popup(f: &mut Frame, app: &App) {
+    let Some(dialog) = &app.rename_dialog else { return };
+
+    let area = centered_rect(70, 20, f.area());
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(3) / 2,
+        height: area.height.min(3),
+        ..area
+    };
+
+    let extension = dialog.operation.get_extension();
+    let text = if extension.is_empty() {
+        Line::from(dialog.editor.buffer.as_str())
+    } else {
         Line::from(vec![
-            Span::styled("Jellyfin Rename Tool - Help", Style::default().add_modifier(Modifier::BOLD))
+            Span::raw(dialog.editor.buffer.as_str()),
+            Span::styled(format!(".{}", extension), Style::default().fg(Color::DarkGray)),
+        ])
+    };
+    let input = Paragraph::new(text)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .title(format!("Rename {} (Enter to rename, Esc for the full setup)", dialog.operation.get_original_name()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(input, popup_area);
+    f.set_cursor_position(Position::new(
+        popup_area.x + 1 + dialog.editor.cursor as u16,
+        popup_area.y + 1,
+    ));
+}
+
+pub fn render_season_edit_popup(f: &mut Frame, app: &App) {
+    let Some(season) = &app.season_edit else { return };
+
+    let area = centered_rect(50, 20, f.area());
+    let popup_area = Rect {
+        y: area.y + area.height.saturating_sub(3) / 2,
+        height: area.height.min(3),
+        ..area
+    };
+
+    let input = Paragraph::new(season.as_str())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .title("Season for every file (Enter to apply, Esc to cancel)")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(input, popup_area);
+    f.set_cursor_position(Position::new(
+        popup_area.x + 1 + season.chars().count() as u16,
+        popup_area.y + 1,
+    ));
+}
+
+pub fn render_quit_confirm_popup(f: &mut Frame, app: &App) {
+    let popup_area = centered_rect(50, 25, f.area());
+
+    let reason = if app.current_processing.is_some() {
+        "Renaming is still in progress.".to_string()
+    } else {
+        format!("{} rename(s) have not been applied yet.", app.pending_rename_count())
+    };
+
+    let text = vec![
+        Line::from(reason),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y/q/Enter", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(" quit anyway   "),
+            Span::styled("any other key", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(" keep working"),
         ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title("Quit?")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(paragraph, popup_area);
+}
+
+pub fn render_process_confirm_popup(f: &mut Frame, app: &App) {
+    let popup_area = centered_rect(50, 25, f.area());
+
+    let text = vec![
+        Line::from(format!("Rename {} file(s) now?", app.queued_rename_count())),
         Line::from(""),
-        Line::from("Navigation:"),
-        Line::from("  Up/k    - Move up"),
-        Line::from("  Down/j  - Move down"),
+        Line::from(vec![
+            Span::styled("y/Enter", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(" start   "),
+            Span::styled("any other key", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(" cancel"),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title("Start processing?")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(paragraph, popup_area);
+}
+
+pub fn render_error_popup(f: &mut Frame, app: &App) {
+    let Some(message) = &app.error_popup else { return };
+
+    let popup_area = centered_rect(60, 25, f.area());
+
+    let text = vec![
+        Line::from(message.as_str()),
         Line::from(""),
-        Line::from("Actions:"),
-        Line::from("  Enter   - Start processing"),
-        Line::from("  Space   - Start processing"),
-        Line::from("  u       - Undo renames (after completion)"),
-        Line::from("  p       - Toggle preview panel"),
-        Line::from("  h/F1    - Toggle this help"),
-        Line::from("  q/Esc   - Quit application"),
+        Line::from(vec![
+            Span::styled("Press any key to dismiss", Style::default().fg(Color::Gray))
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(Color::Red))
+        .block(
+            Block::default()
+                .title("Error")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red)),
+        )
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(paragraph, popup_area);
+}
+
+/// The keybinding section of the help popup, one line per binding in [`HELP_SECTIONS`].
+pub fn help_lines() -> Vec<String> {
+    let mut lines = Vec::new();
+    for (title, bindings) in HELP_SECTIONS {
+        lines.push(String::new());
+        lines.push(format!("{}:", title));
+        for binding in *bindings {
+            lines.push(format!("  {:<11} - {}", key_label(binding), binding.description));
+        }
+    }
+    lines
+}
+
+pub fn render_help_popup(f: &mut Frame, _app: &App) {
+    let popup_area = centered_rect(60, 70, f.area());
+
+    let mut help_text = vec![
+        Line::from(vec![
+            Span::styled("Jellyfin Rename Tool - Help", Style::default().add_modifier(Modifier::BOLD))
+        ]),
+    ];
+    help_text.extend(help_lines().into_iter().map(Line::from));
+    help_text.extend([
         Line::from(""),
         Line::from("Features:"),
         Line::from("• Fetches episode titles from IMDb"),
@@ -634,7 +1134,7 @@ pub fn render_help_popup(f: &mut Frame, _app: &App) {
         Line::from(vec![
             Span::styled("Press Esc or h to close", Style::default().fg(Color::Gray))
         ]),
-    ];
+    ]);
 
     let paragraph = Paragraph::new(help_text)
         .block(
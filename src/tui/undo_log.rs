@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::models::UndoOperation;
+
+/// The renames made by one processing run, stamped with when the run started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoBatch {
+    /// Milliseconds since the Unix epoch; also the key the batch is saved under.
+    pub created_at: u64,
+    pub operations: Vec<UndoOperation>,
+}
+
+impl UndoBatch {
+    /// Whether any of the batch's renames happened inside `directory`.
+    pub fn touches(&self, directory: &Path) -> bool {
+        self.operations.iter().any(|operation| {
+            Path::new(&operation.original_path).starts_with(directory)
+                || Path::new(&operation.renamed_path).starts_with(directory)
+        })
+    }
+}
+
+/// A key for a new batch: the current time in milliseconds, moved past `previous`
+/// so two runs started within the same millisecond don't share one.
+pub fn next_batch_key(previous: u64) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    now.max(previous + 1)
+}
+
+/// Undo batches stored on disk as JSON, oldest first, so renames can be
+/// reverted after the app is restarted.
+///
+/// A missing or corrupt file reads as an empty log.
+#[derive(Debug, Clone)]
+pub struct UndoLog {
+    path: PathBuf,
+}
+
+impl UndoLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The log file shared by every run, in the user's config directory next to
+    /// the last-run file so undo history survives a reboot.
+    pub fn default_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("jellyfin-renamer")
+            .join("undo_log.json")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn batches(&self) -> Vec<UndoBatch> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// The most recent batch, i.e. the last session's renames.
+    pub fn last_batch(&self) -> Option<UndoBatch> {
+        self.batches().pop()
+    }
+
+    /// The most recent batch with renames inside `directory`. The log is shared by
+    /// every run, so a batch made in another folder is not offered here.
+    pub fn last_batch_in(&self, directory: &Path) -> Option<UndoBatch> {
+        self.batches().into_iter().rev().find(|batch| batch.touches(directory))
+    }
+
+    /// Writes `batch`, replacing any batch with the same timestamp. A batch with
+    /// no operations left is dropped, and the file is removed once no batches remain.
+    pub fn save_batch(&self, batch: &UndoBatch) -> Result<()> {
+        let mut batches = self.batches();
+        batches.retain(|existing| existing.created_at != batch.created_at);
+        if !batch.operations.is_empty() {
+            batches.push(batch.clone());
+            batches.sort_by_key(|existing| existing.created_at);
+        }
+
+        if batches.is_empty() {
+            if self.path.exists() {
+                fs::remove_file(&self.path)?;
+            }
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&batches)?)?;
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use ratatui::layout::{Constraint, Direction, Layout};
 
 /// Creates a centered rectangle with the given percentage dimensions
@@ -20,3 +22,65 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: ratatui::layout::Rect) -
         ])
         .split(popup_layout[1])[1]
 }
+
+/// The system clipboard, opened on first use. The handle is kept for the whole
+/// session because on X11 copied text disappears once its owner is dropped.
+#[derive(Default)]
+pub struct Clipboard(Option<arboard::Clipboard>);
+
+impl std::fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Clipboard").field(&self.0.is_some()).finish()
+    }
+}
+
+impl Clipboard {
+    /// Fails with a readable message when there is no clipboard, e.g. over SSH.
+    pub fn set_text(&mut self, text: &str) -> Result<(), String> {
+        if self.0.is_none() {
+            self.0 = Some(arboard::Clipboard::new().map_err(|e| e.to_string())?);
+        }
+        match self.0.as_mut() {
+            Some(clipboard) => clipboard.set_text(text.to_string()).map_err(|e| e.to_string()),
+            None => Err("clipboard unavailable".to_string()),
+        }
+    }
+}
+
+/// The platform's "open this with the default application" command.
+pub fn file_manager_command() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Opens `directory` in the file manager without waiting for it. The child's
+/// output is discarded so it can't draw over the TUI.
+pub fn open_in_file_manager(directory: &std::path::Path) -> std::io::Result<()> {
+    std::process::Command::new(file_manager_command())
+        .arg(directory)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Formats a duration as `mm:ss`; minutes keep counting past an hour.
+pub fn format_mm_ss(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Time left at the average pace so far. `None` until at least one file is done.
+pub fn estimate_remaining(elapsed: Duration, processed: usize, total: usize) -> Option<Duration> {
+    if processed == 0 || processed >= total {
+        return None;
+    }
+    let per_file = elapsed.div_f64(processed as f64);
+    Some(per_file.mul_f64((total - processed) as f64))
+}
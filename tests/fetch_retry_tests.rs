@@ -0,0 +1,86 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use jellyfin_rename::rename_engine::{get_with_retry, EpisodeTitleProvider, ImdbProvider};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serves one canned status per connection, repeating the last one, and counts requests.
+async fn stub_server(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/season", listener.local_addr().unwrap());
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else { return };
+            let hit = counter.fetch_add(1, Ordering::SeqCst);
+            let status = statuses[hit.min(statuses.len() - 1)];
+
+            let mut buffer = [0u8; 1024];
+            let _ = socket.read(&mut buffer).await;
+            let response = format!("HTTP/1.1 {} Stub\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}", status);
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (url, hits)
+}
+
+#[tokio::test]
+async fn test_retries_server_errors_until_success() {
+    let (url, hits) = stub_server(vec![500, 503, 200]).await;
+
+    let response = get_with_retry(&url, 3).await.unwrap();
+
+    assert!(response.status().is_success());
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_not_found_is_not_retried() {
+    let (url, hits) = stub_server(vec![404]).await;
+
+    let err = get_with_retry(&url, 3).await.unwrap_err();
+
+    assert!(err.to_string().contains("404"), "unexpected error: {}", err);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_final_attempt() {
+    let (url, hits) = stub_server(vec![502]).await;
+
+    let err = get_with_retry(&url, 2).await.unwrap_err();
+
+    assert!(err.to_string().contains("502"), "unexpected error: {}", err);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_imdb_provider_reads_the_episodes_page() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requested = Arc::new(std::sync::Mutex::new(String::new()));
+    let request_line = requested.clone();
+
+    tokio::spawn(async move {
+        let Ok((mut socket, _)) = listener.accept().await else { return };
+        let mut buffer = [0u8; 1024];
+        let read = socket.read(&mut buffer).await.unwrap_or(0);
+        *request_line.lock().unwrap() = String::from_utf8_lossy(&buffer[..read]).lines().next().unwrap_or_default().to_string();
+
+        let body = r#"<html><script type="application/ld+json">[
+            {"@type": "TVEpisode", "name": "Pilot"},
+            {"@type": "TVEpisode", "name": "Cat's in the Bag..."}
+        ]</script></html>"#;
+        let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+        let _ = socket.write_all(response.as_bytes()).await;
+    });
+
+    let titles = ImdbProvider::new("tt0903747").with_base_url(base_url).titles(1).await.unwrap();
+
+    assert_eq!(titles, vec!["Pilot", "Cat's in the Bag..."]);
+    assert!(requested.lock().unwrap().contains("/title/tt0903747/episodes/?season=1"));
+}
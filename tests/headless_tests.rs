@@ -0,0 +1,101 @@
+use jellyfin_rename::headless::{self, HeadlessOptions};
+use jellyfin_rename::rename_engine::FileType;
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn test_parse_headless_arguments() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir_arg = dir.path().to_string_lossy().to_string();
+
+    let options = HeadlessOptions::parse(&args(&[
+        "--headless", "--dir", &dir_arg, "--type", "tv", "--season", "S02", "--imdb", "tt0903747", "--dry-run",
+    ])).unwrap();
+    assert_eq!(options.directory, dir.path());
+    assert_eq!(options.file_type, FileType::TvShow);
+    assert_eq!(options.season.as_deref(), Some("S02"));
+    assert_eq!(options.imdb_id.as_deref(), Some("tt0903747"));
+    assert!(options.dry_run);
+
+    assert!(HeadlessOptions::parse(&args(&["--headless"])).is_err(), "--dir is required");
+    assert!(HeadlessOptions::parse(&args(&["--dir", &dir_arg, "--type", "cartoon"])).is_err());
+    assert!(HeadlessOptions::parse(&args(&["--dir", &dir_arg, "--season"])).is_err());
+}
+
+#[tokio::test]
+async fn test_headless_run_renames_and_summarises() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let dir_arg = dir.path().to_string_lossy().to_string();
+
+    let options = HeadlessOptions::parse(&args(&["--dir", &dir_arg, "--season", "S01"])).unwrap();
+    let summary = headless::run(options).await.unwrap();
+
+    assert_eq!(summary.renamed, 2);
+    assert_eq!(summary.failed, 0);
+    assert!(dir.path().join("Pilot_(S01E01).mkv").exists());
+    assert!(dir.path().join("Second_(S01E02).mkv").exists());
+}
+
+#[tokio::test]
+async fn test_headless_dry_run_leaves_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.mkv"), b"video").unwrap();
+    let dir_arg = dir.path().to_string_lossy().to_string();
+
+    let options = HeadlessOptions::parse(&args(&["--dir", &dir_arg, "--dry-run"])).unwrap();
+    let summary = headless::run(options).await.unwrap();
+
+    assert_eq!(summary.renamed, 1);
+    assert!(dir.path().join("Show.S01E01.Pilot.mkv").exists());
+}
+
+#[tokio::test]
+async fn test_headless_report_lists_each_file_and_the_totals() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Show.S01E01.Pilot, Part 1.mkv", "Show.S01E02.Second.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let dir_arg = dir.path().to_string_lossy().to_string();
+
+    let options = HeadlessOptions::parse(&args(&["--report", "--dir", &dir_arg, "--season", "S01"])).unwrap();
+    assert_eq!(options.report, Some(dir.path().join("rename-report.csv")));
+    headless::run(options).await.unwrap();
+
+    let report = std::fs::read_to_string(dir.path().join("rename-report.csv")).unwrap();
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines[0], "original_name,new_name,status,error_message");
+    // A comma in a name gets the field quoted
+    assert!(lines.iter().any(|line| line.starts_with("\"Show.S01E01.Pilot, Part 1.mkv\",")), "{}", report);
+    assert!(lines.contains(&"Show.S01E02.Second.mkv,Second_(S01E02).mkv,Success,"), "{}", report);
+    assert_eq!(&lines[lines.len() - 2..], ["total,processed,successful,failed,skipped", "2,2,2,0,0"]);
+}
+
+#[tokio::test]
+async fn test_plan_json_is_written_without_renaming() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.mkv"), b"video").unwrap();
+    let dir_arg = dir.path().to_string_lossy().to_string();
+    let plan_dir = tempfile::tempdir().unwrap();
+    let plan_path = plan_dir.path().join("plan.json");
+    let plan_arg = plan_path.to_string_lossy().to_string();
+
+    let options = HeadlessOptions::parse(&args(&["--dir", &dir_arg, "--plan-json", &plan_arg])).unwrap();
+    let summary = headless::run(options).await.unwrap();
+
+    assert_eq!(summary.renamed, 0);
+    assert!(dir.path().join("Show.S01E01.Pilot.mkv").exists());
+
+    let plan: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&plan_path).unwrap()).unwrap();
+    let entry = &plan.as_array().unwrap()[0];
+    assert_eq!(entry["original_name"], "Show.S01E01.Pilot.mkv");
+    assert_eq!(entry["new_name"], "Pilot_(S01E01).mkv");
+    assert_eq!(entry["season_number"], 1);
+    assert_eq!(entry["episode_number"], 1);
+    assert_eq!(entry["needs_rename"], true);
+    assert!(entry["original_path"].as_str().unwrap().ends_with("Show.S01E01.Pilot.mkv"));
+}
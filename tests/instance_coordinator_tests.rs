@@ -1,18 +1,17 @@
 // Integration tests for the instance coordinator module
 
-use jellyfin_rename::instance_coordinator::InstanceCoordinator;
+use jellyfin_rename::instance_coordinator::{CoordinatorTiming, InstanceCoordinator};
 use std::env;
+use std::time::Duration;
 
 #[test]
 fn test_coordinator_creation() {
     let _coordinator = InstanceCoordinator::new();
-    assert!(true);
 }
 
 #[test]
 fn test_default_coordinator() {
     let _coordinator = InstanceCoordinator::default();
-    assert!(true);
 }
 
 #[test]
@@ -22,10 +21,249 @@ fn test_coordinator_functionality() {
     let temp_dir = env::temp_dir();
     let test_file = temp_dir.join("test_file.txt").to_string_lossy().to_string();
     
-    let result = coordinator.collect_files_from_instances(&test_file);
-    
-    match result {
-        Some(_files) => assert!(true),
-        None => assert!(true),
+    // Either outcome is valid depending on whether another instance holds the lock
+    let _result = coordinator.collect_files_from_instances(&test_file);
+}
+
+#[test]
+fn test_collection_filters_non_video_files() {
+    let coordinator = InstanceCoordinator::new();
+
+    let paths = vec![
+        "Show.S01E01.mkv".to_string(),
+        "Show.S01E01.nfo".to_string(),
+        "Show.S01E02.MP4".to_string(),
+        "cover.jpg".to_string(),
+        "Show.S01E03.avi".to_string(),
+        "notes".to_string(),
+    ];
+
+    assert_eq!(coordinator.filter_video_files(paths), vec![
+        "Show.S01E01.mkv".to_string(),
+        "Show.S01E02.MP4".to_string(),
+        "Show.S01E03.avi".to_string(),
+    ]);
+}
+
+#[test]
+fn test_collection_extensions_are_configurable() {
+    let coordinator = InstanceCoordinator::new().with_extensions(vec![".srt".to_string()]);
+    assert!(coordinator.accepts_path("Show.S01E01.srt"));
+    assert!(!coordinator.accepts_path("Show.S01E01.mkv"));
+
+    let coordinator = InstanceCoordinator::new().with_extensions(Vec::new());
+    assert!(coordinator.accepts_path("cover.jpg"));
+}
+
+#[test]
+fn test_current_process_is_running() {
+    let coordinator = InstanceCoordinator::new();
+    assert!(coordinator.is_process_running(std::process::id()));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exited_process_is_not_running() {
+    let coordinator = InstanceCoordinator::new();
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let pid = child.id();
+    child.wait().unwrap();
+
+    assert!(!coordinator.is_process_running(pid));
+}
+
+#[test]
+fn test_timing_overrides_are_read_and_clamped() {
+    let timing = CoordinatorTiming::from_vars(|name| match name {
+        "JELLYFIN_RENAME_STABILITY_MS" => Some("3000".to_string()),
+        "JELLYFIN_RENAME_MAX_WAIT_MS" => Some("999999999".to_string()),
+        "JELLYFIN_RENAME_INACTIVITY_MS" => Some("soon".to_string()),
+        _ => None,
+    });
+
+    assert_eq!(timing.stability, Duration::from_millis(3000));
+    assert_eq!(timing.max_wait, Duration::from_millis(300_000));
+    assert_eq!(timing.inactivity, CoordinatorTiming::default().inactivity);
+    assert_eq!(CoordinatorTiming::from_vars(|_| None), CoordinatorTiming::default());
+
+    let coordinator = InstanceCoordinator::new().with_timing(timing);
+    assert_eq!(coordinator.timing(), timing);
+}
+
+#[test]
+fn test_concurrent_instances_hand_every_file_to_a_coordinator() {
+    let dir = tempfile::tempdir().unwrap();
+    let timing = CoordinatorTiming {
+        max_wait: Duration::from_secs(10),
+        stability: Duration::from_millis(300),
+        inactivity: Duration::from_millis(1000),
+    };
+
+    let files: Vec<String> = (1..=8)
+        .map(|episode| {
+            let path = dir.path().join(format!("Show.S01E{:02}.mkv", episode));
+            std::fs::write(&path, b"video").unwrap();
+            path.to_string_lossy().to_string()
+        })
+        .collect();
+
+    let handles: Vec<_> = files
+        .iter()
+        .cloned()
+        .map(|file| {
+            let temp_dir = dir.path().join("coordination");
+            std::thread::spawn(move || {
+                InstanceCoordinator::new()
+                    .with_temp_dir(temp_dir)
+                    .with_timing(timing)
+                    .collect_files_from_instances(&file)
+            })
+        })
+        .collect();
+
+    let results: Vec<Option<Vec<String>>> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+    let coordinated: Vec<&Vec<String>> = results.iter().flatten().collect();
+    assert!(!coordinated.is_empty(), "some instance should have coordinated");
+
+    let mut collected: Vec<String> = coordinated.into_iter().flatten().cloned().collect();
+    collected.sort();
+    assert_eq!(collected, files, "every file should be collected exactly once");
+}
+
+#[test]
+fn test_an_instance_arriving_during_handover_waits_for_the_lock() {
+    use fs2::FileExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let coordination = dir.path().join("coordination");
+    let base = coordination.join("jellyfin_rename");
+    std::fs::create_dir_all(&base).unwrap();
+    let timing = CoordinatorTiming {
+        max_wait: Duration::from_secs(5),
+        stability: Duration::from_millis(200),
+        inactivity: Duration::from_millis(500),
+    };
+    let submit = |file: std::path::PathBuf| {
+        std::fs::write(&file, b"video").unwrap();
+        let temp_dir = coordination.clone();
+        std::thread::spawn(move || {
+            InstanceCoordinator::new()
+                .with_temp_dir(temp_dir)
+                .with_timing(timing)
+                .collect_files_from_instances(&file.to_string_lossy())
+        })
+    };
+
+    // A coordinator that has already moved its list aside but not yet unlocked
+    let lock = std::fs::OpenOptions::new().write(true).create(true).truncate(false)
+        .open(base.join("coordinator.lock")).unwrap();
+    lock.lock_exclusive().unwrap();
+    let late = dir.path().join("Show.S01E05.mkv");
+    let handle = submit(late.clone());
+    std::thread::sleep(Duration::from_millis(300));
+    drop(lock);
+
+    assert_eq!(handle.join().unwrap(), Some(vec![late.to_string_lossy().to_string()]),
+        "the late file must not be left behind in the fresh list");
+
+    // One whose list is claimed while it waits leaves the files to that coordinator
+    let lock = std::fs::OpenOptions::new().write(true).open(base.join("coordinator.lock")).unwrap();
+    lock.lock_exclusive().unwrap();
+    let handle = submit(dir.path().join("Show.S01E06.mkv"));
+    std::thread::sleep(Duration::from_millis(300));
+    std::fs::rename(base.join("files.txt"), base.join("files_claimed.txt")).unwrap();
+    assert_eq!(handle.join().unwrap(), None);
+    drop(lock);
+}
+
+#[test]
+fn test_dropped_folders_expand_to_their_video_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let season_one = dir.path().join("Season 1");
+    let season_two = dir.path().join("Season 2");
+    std::fs::create_dir_all(season_one.join("Extras")).unwrap();
+    std::fs::create_dir_all(&season_two).unwrap();
+    for file in [
+        season_one.join("Show.S01E02.mkv"),
+        season_one.join("Show.S01E01.mkv"),
+        season_one.join("cover.jpg"),
+        season_two.join("Show.S02E01.mp4"),
+        dir.path().join("Show.S03E01.avi"),
+    ] {
+        std::fs::write(file, b"video").unwrap();
     }
+    let path = |path: std::path::PathBuf| path.to_string_lossy().to_string();
+
+    let coordinator = InstanceCoordinator::new();
+    let expanded = coordinator.expand_directories(vec![
+        path(season_one.clone()),
+        path(dir.path().join("Show.S03E01.avi")),
+        path(season_two.clone()),
+        path(season_one.join("Show.S01E01.mkv")),
+    ]);
+
+    assert_eq!(expanded, vec![
+        path(season_one.join("Show.S01E01.mkv")),
+        path(season_one.join("Show.S01E02.mkv")),
+        path(dir.path().join("Show.S03E01.avi")),
+        path(season_two.join("Show.S02E01.mp4")),
+    ]);
+}
+
+#[test]
+fn test_an_instance_can_submit_several_paths() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let media = tempfile::tempdir().unwrap();
+    let paths: Vec<String> = ["Show.S01E01.mkv", "Show.S01E02.mkv"]
+        .iter()
+        .map(|name| {
+            let path = media.path().join(name);
+            std::fs::write(&path, b"video").unwrap();
+            path.to_string_lossy().to_string()
+        })
+        .collect();
+
+    let coordinator = InstanceCoordinator::new()
+        .with_temp_dir(temp_dir.path())
+        .with_timing(CoordinatorTiming {
+            max_wait: Duration::from_secs(5),
+            stability: Duration::from_millis(200),
+            inactivity: Duration::from_millis(500),
+        });
+
+    assert_eq!(coordinator.collect_paths_from_instances(&paths), Some(paths));
+}
+
+#[test]
+fn test_collected_files_keep_submission_order_without_duplicates() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let media = tempfile::tempdir().unwrap();
+    let path = |name: &str| {
+        let path = media.path().join(name);
+        std::fs::write(&path, b"video").unwrap();
+        path.to_string_lossy().to_string()
+    };
+    let submitted = vec![
+        path("Show.S01E03.mkv"),
+        path("Show.S01E01.mkv"),
+        path("Show.S01E10.mkv"),
+        path("Show.S01E01.mkv"),
+        path("Show.S01E02.mkv"),
+    ];
+
+    let coordinator = InstanceCoordinator::new()
+        .with_temp_dir(temp_dir.path())
+        .with_timing(CoordinatorTiming {
+            max_wait: Duration::from_secs(5),
+            stability: Duration::from_millis(200),
+            inactivity: Duration::from_millis(500),
+        });
+
+    let collected = coordinator.collect_paths_from_instances(&submitted).unwrap();
+    assert_eq!(collected, vec![
+        submitted[0].clone(),
+        submitted[1].clone(),
+        submitted[2].clone(),
+        submitted[4].clone(),
+    ]);
 }
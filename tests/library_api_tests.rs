@@ -0,0 +1,30 @@
+use jellyfin_rename::rename_engine::{apply, plan_directory, ConfigBuilder, FileType};
+
+#[tokio::test]
+async fn test_plan_and_apply_without_the_tui() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .build()
+        .unwrap();
+
+    let mut plan = plan_directory(config.clone()).await.unwrap();
+    assert_eq!(plan.len(), 2);
+    assert!(dir.path().join("Show.S01E01.Pilot.mkv").exists(), "planning must not rename");
+
+    // Entries that need no rename still get a result, so results line up with the plan
+    plan.sort_by_key(|file_rename| file_rename.episode_number);
+    plan[1].needs_rename = false;
+
+    let results = apply(&plan, &config).await.unwrap();
+    assert_eq!(results.len(), plan.len());
+    assert!(results.iter().all(|result| result.success()));
+    assert!(dir.path().join("Pilot_(S01E01).mkv").exists());
+    assert!(dir.path().join("Show.S01E02.Second.mkv").exists());
+    assert_eq!(results[1].final_path(), Some(plan[1].original_path.as_path()));
+}
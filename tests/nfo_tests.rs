@@ -0,0 +1,28 @@
+// Tests for the .nfo metadata documents
+
+use jellyfin_rename::nfo::{episode_nfo, movie_nfo, xml_escape};
+
+#[test]
+fn test_xml_escape_handles_reserved_characters() {
+    assert_eq!(xml_escape("Tom & Jerry <\"Live\"> 'n' more"), "Tom &amp; Jerry &lt;&quot;Live&quot;&gt; &apos;n&apos; more");
+    assert_eq!(xml_escape("Plain title"), "Plain title");
+}
+
+#[test]
+fn test_episode_nfo_contains_escaped_fields() {
+    let xml = episode_nfo("Cops & Robbers", 2, 5, Some("2011"));
+    assert!(xml.starts_with("<?xml"));
+    assert!(xml.contains("<episodedetails>"));
+    assert!(xml.contains("<title>Cops &amp; Robbers</title>"));
+    assert!(xml.contains("<season>2</season>"));
+    assert!(xml.contains("<episode>5</episode>"));
+    assert!(xml.contains("<year>2011</year>"));
+}
+
+#[test]
+fn test_movie_nfo_leaves_out_a_missing_year() {
+    let xml = movie_nfo("Heat", None);
+    assert!(xml.contains("<movie>"));
+    assert!(xml.contains("<title>Heat</title>"));
+    assert!(!xml.contains("<year>"));
+}
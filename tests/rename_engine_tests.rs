@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use jellyfin_rename::rename_engine::{
-    sanitize_filename, extract_season_from_directory, scrape_imdb_episodes,
-    ConfigBuilder, RenameEngine, FileType
+    sanitize_filename, assemble_filename, extract_season_from_directory, extract_season_from_filename, scrape_imdb_episodes,
+    parse_imdb_episode_titles, compile_exclude_pattern, normalize_title_symbols, normalize_unicode, parse_tmdb_season, parse_omdb_season, titles_by_episode, map_absolute_episode, detect_file_type, move_file, copy_then_delete, restore_timestamps, season_folder_path, DateOrder, SymbolHandling, ConfigBuilder, RenameEngine, FileType, FileRename, DisambiguationStyle, OperationKind, Casing, RenameOutcome, RenameResult, RenameOperation, ConflictStrategy, MatchKind, TemplateValues, expand_template
 };
 
 #[test]
@@ -10,6 +10,14 @@ fn test_sanitize_filename() {
     assert_eq!(sanitize_filename("Normal_File.Name"), "Normal_File.Name");
 }
 
+#[test]
+fn test_assemble_filename_collapses_trailing_dots() {
+    assert_eq!(assemble_filename("Title.", "mkv"), "Title.mkv");
+    assert_eq!(assemble_filename("Title...", ".mkv"), "Title.mkv");
+    assert_eq!(assemble_filename("Title. ", "mkv"), "Title.mkv");
+    assert_eq!(assemble_filename("Cat's_in_the_Bag..._(S01E02)", "mkv"), "Cat's_in_the_Bag..._(S01E02).mkv");
+}
+
 #[test]
 fn test_extract_season_from_directory() {
     assert_eq!(extract_season_from_directory("Show.S01"), Some(1));
@@ -17,6 +25,84 @@ fn test_extract_season_from_directory() {
     assert_eq!(extract_season_from_directory("Random.Folder"), None);
 }
 
+#[test]
+fn test_specials_directory_is_season_zero() {
+    assert_eq!(extract_season_from_directory("Specials"), Some(0));
+    assert_eq!(extract_season_from_directory("Extras"), Some(0));
+    assert_eq!(extract_season_from_directory("Season 2 Specials"), Some(2));
+    assert_eq!(extract_season_from_filename("Show.Christmas.Special.mkv"), Some(0));
+    assert_eq!(extract_season_from_filename("Show.S00E03.mkv"), Some(0));
+
+    let root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join("Specials")).unwrap();
+    std::fs::write(root.path().join("Specials").join("Show.S00E01.Making.Of.mkv"), b"video").unwrap();
+    let renames = scan_show(root.path(), true);
+
+    assert_eq!(renames.len(), 1);
+    assert_eq!(renames[0].season_number, 0);
+    assert_eq!(renames[0].new_name, "Making_Of_(S00E01).mkv");
+    assert_eq!(season_folder_path(root.path(), 0), root.path().join("Specials"));
+    assert_eq!(season_folder_path(&root.path().join("Specials"), 0), root.path().join("Specials"));
+}
+
+// The first selector only matches the "featured" episode, while the generic
+// title selector matches the full season listing.
+const PARTIAL_SELECTOR_FIXTURE: &str = r#"
+<html><body>
+  <div class="ipc-title ipc-title--base ipc-title--title">
+    <h3 class="ipc-title__text">S1.E1 ∙ Pilot</h3>
+  </div>
+  <article><h4 class="ipc-title__text">S1.E1 ∙ Pilot</h4></article>
+  <article><h4 class="ipc-title__text">S1.E2 ∙ Cat's in the Bag...</h4></article>
+  <article><h4 class="ipc-title__text">S1.E3 ∙ ...And the Bag's in the River</h4></article>
+</body></html>
+"#;
+
+#[test]
+fn test_parse_imdb_titles_prefers_fuller_selector() {
+    let titles = parse_imdb_episode_titles(PARTIAL_SELECTOR_FIXTURE);
+    assert_eq!(titles, vec![
+        "Pilot".to_string(),
+        "Cat's in the Bag...".to_string(),
+        "...And the Bag's in the River".to_string(),
+    ]);
+}
+
+// Structured data disagrees with the visible markup, which must be ignored
+const STRUCTURED_DATA_FIXTURE: &str = r#"
+<html><head>
+  <script id="__NEXT_DATA__" type="application/json">
+    {"props":{"pageProps":{"contentData":{"section":{"episodes":{"items":[
+      {"id":"tt0959621","episode":"1","titleText":"Pilot"},
+      {"id":"tt1054724","episode":"2","titleText":"Cat's in the Bag..."},
+      {"id":"tt1054725","episode":"3","titleText":{"text":"...And the Bag's in the River"}}
+    ]}}}}}}
+  </script>
+</head><body>
+  <h4 class="ipc-title__text">S1.E1 ∙ Stale Markup</h4>
+</body></html>
+"#;
+
+const JSON_LD_FIXTURE: &str = r#"
+<html><head>
+  <script type="application/ld+json">
+    {"@context":"https://schema.org","@type":"TVSeason","episode":[
+      {"@type":"TVEpisode","episodeNumber":1,"name":"Pilot"},
+      {"@type":"TVEpisode","episodeNumber":2,"name":"Cat's in the Bag..."}
+    ]}
+  </script>
+</head><body></body></html>
+"#;
+
+#[test]
+fn test_parse_imdb_titles_prefers_structured_data() {
+    assert_eq!(
+        parse_imdb_episode_titles(STRUCTURED_DATA_FIXTURE),
+        vec!["Pilot", "Cat's in the Bag...", "...And the Bag's in the River"]
+    );
+    assert_eq!(parse_imdb_episode_titles(JSON_LD_FIXTURE), vec!["Pilot", "Cat's in the Bag..."]);
+}
+
 #[tokio::test]
 async fn test_config_builder() {
     let config = ConfigBuilder::new()
@@ -127,3 +213,1489 @@ async fn test_rename_engine_integration() {
         Err(e) => println!("RenameEngine IMDb fetch error: {}", e),
     }
 }
+
+fn movie_engine(pad_part_numbers: bool) -> RenameEngine {
+    let config = ConfigBuilder::new()
+        .directory("/test/movies")
+        .file_type(FileType::Movie)
+        .pad_part_numbers(pad_part_numbers)
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap()
+}
+
+#[test]
+fn test_movie_part_number_unpadded() {
+    let engine = movie_engine(false);
+    let rename = engine.process_file_movie("Film.2009.CD2.avi").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Film_(2009)-part2.avi");
+}
+
+#[test]
+fn test_two_part_movie_scan_keeps_parts_apart() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Film.2009.CD1.avi", "Film.2009.CD2.avi", "Other.2010.Disc1.mkv", "Other.2010.disc-2.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::Movie)
+        .build()
+        .unwrap();
+    let renames = RenameEngine::new(config).unwrap().scan_directory().unwrap();
+
+    let mut names: Vec<&str> = renames.iter().map(|r| r.new_name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec![
+        "Film_(2009)-part1.avi",
+        "Film_(2009)-part2.avi",
+        "Other_(2010)-part1.mkv",
+        "Other_(2010)-part2.mkv",
+    ]);
+}
+
+#[test]
+fn test_movie_part_number_padded() {
+    let engine = movie_engine(true);
+    let rename = engine.process_file_movie("Film.2009.CD2.avi").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Film_(2009)-part02.avi");
+}
+
+fn colliding_movie_renames(style: DisambiguationStyle, dir: &std::path::Path) -> Vec<FileRename> {
+    let config = ConfigBuilder::new()
+        .directory(dir)
+        .file_type(FileType::Movie)
+        .disambiguation(style)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    let filenames = [
+        "Film.2009.1080p.mkv",
+        "Film.2009.720p.mkv",
+        "Film.2009.1080p.x264.mkv",
+        "Film.2009.1080p.HEVC.mkv",
+    ];
+    let mut renames: Vec<FileRename> = filenames.iter().enumerate().map(|(i, filename)| {
+        let file = std::fs::File::create(dir.join(filename)).unwrap();
+        let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000 + i as u64);
+        file.set_modified(modified).unwrap();
+        engine.process_file_movie(filename).unwrap().unwrap()
+    }).collect();
+    assert!(renames.iter().all(|r| r.new_name == "Film_(2009).mkv"));
+
+    engine.disambiguate_targets(&mut renames);
+    renames
+}
+
+fn new_names(renames: &[FileRename]) -> Vec<&str> {
+    renames.iter().map(|r| r.new_name.as_str()).collect()
+}
+
+#[test]
+fn test_disambiguation_numeric() {
+    let dir = tempfile::tempdir().unwrap();
+    let renames = colliding_movie_renames(DisambiguationStyle::Numeric, dir.path());
+    assert_eq!(new_names(&renames), vec![
+        "Film_(2009).mkv",
+        "Film_(2009)_2.mkv",
+        "Film_(2009)_3.mkv",
+        "Film_(2009)_4.mkv",
+    ]);
+}
+
+#[test]
+fn test_disambiguation_resolution() {
+    let dir = tempfile::tempdir().unwrap();
+    let renames = colliding_movie_renames(DisambiguationStyle::Resolution, dir.path());
+    // The fourth file repeats a taken resolution, so it falls back to a numeric suffix
+    assert_eq!(new_names(&renames), vec![
+        "Film_(2009).mkv",
+        "Film_(2009)_720p.mkv",
+        "Film_(2009)_1080p.mkv",
+        "Film_(2009)_2.mkv",
+    ]);
+}
+
+#[test]
+fn test_disambiguation_timestamp() {
+    let dir = tempfile::tempdir().unwrap();
+    let renames = colliding_movie_renames(DisambiguationStyle::Timestamp, dir.path());
+    assert_eq!(new_names(&renames), vec![
+        "Film_(2009).mkv",
+        "Film_(2009)_1700000001.mkv",
+        "Film_(2009)_1700000002.mkv",
+        "Film_(2009)_1700000003.mkv",
+    ]);
+}
+
+#[test]
+fn test_scan_directory_produces_unique_targets() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Film.2009.1080p.mkv", "Film.2009.720p.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::Movie)
+        .build()
+        .unwrap();
+    let renames = RenameEngine::new(config).unwrap().scan_directory().unwrap();
+
+    let mut names = new_names(&renames);
+    names.sort();
+    assert_eq!(names, vec!["Film_(2009).mkv", "Film_(2009)_2.mkv"]);
+}
+
+#[test]
+fn test_scan_directory_skips_excluded_files() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Film.2009.mkv", "Film.2009.Sample.mkv", "sample-film.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::Movie)
+        .exclude_patterns(vec![compile_exclude_pattern("*sample*").unwrap()])
+        .build()
+        .unwrap();
+    let renames = RenameEngine::new(config).unwrap().scan_directory().unwrap();
+
+    let originals: Vec<&str> = renames.iter().map(|r| r.original_name.as_str()).collect();
+    assert_eq!(originals, vec!["Film.2009.mkv"]);
+}
+
+#[test]
+fn test_scan_directory_skips_unfinished_downloads() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Film.2009.mkv", "Other.2010.mkv.part", "Third.2011.mkv.!qB", "Fourth.2012.MKV.CRDOWNLOAD"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    std::fs::File::create(dir.path().join("Empty.2013.mkv")).unwrap();
+    let scan = |builder: ConfigBuilder| {
+        let config = builder.directory(dir.path()).file_type(FileType::Movie).build().unwrap();
+        let mut originals: Vec<String> = RenameEngine::new(config).unwrap()
+            .scan_directory().unwrap()
+            .into_iter().map(|r| r.original_name).collect();
+        originals.sort();
+        originals
+    };
+
+    assert_eq!(scan(ConfigBuilder::new()), vec!["Film.2009.mkv"]);
+    assert_eq!(scan(ConfigBuilder::new().skip_empty_files(false)), vec!["Empty.2013.mkv", "Film.2009.mkv"]);
+
+    // With `part` accepted as a video extension, only the suffix filter keeps the download out
+    let with_part = || ConfigBuilder::new().extensions(vec!["mkv".to_string(), "part".to_string()]);
+    assert_eq!(scan(with_part()), vec!["Film.2009.mkv"]);
+    assert_eq!(scan(with_part().incomplete_suffixes(vec![])), vec!["Film.2009.mkv", "Other.2010.mkv.part"]);
+
+    let engine = RenameEngine::new(ConfigBuilder::new().directory(dir.path()).file_type(FileType::Movie).build().unwrap()).unwrap();
+    assert!(engine.is_incomplete_download("Other.2010.mkv.part"));
+    assert!(engine.is_incomplete_download("Third.2011.mkv.!QB"));
+    assert!(engine.is_incomplete_download("Fourth.2012.MKV.CRDOWNLOAD"));
+    assert!(!engine.is_incomplete_download("Film.2009.mkv"));
+    assert!(!engine.is_incomplete_download("part"));
+}
+
+#[test]
+fn test_scan_directory_skips_samples_and_hidden_files() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Film.2009.mkv", "show-sample.mkv", ".Film.2010.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    std::fs::create_dir(dir.path().join(".trash")).unwrap();
+    std::fs::write(dir.path().join(".trash").join("Other.2011.mkv"), b"video").unwrap();
+    let scan = |builder: ConfigBuilder| {
+        let config = builder.directory(dir.path()).file_type(FileType::Movie).recursive(true).build().unwrap();
+        let mut originals: Vec<String> = RenameEngine::new(config).unwrap()
+            .scan_directory().unwrap()
+            .into_iter().map(|r| r.original_name).collect();
+        originals.sort();
+        originals
+    };
+
+    assert_eq!(scan(ConfigBuilder::new()), vec!["Film.2009.mkv"]);
+    assert!(scan(ConfigBuilder::new().skip_samples(false)).contains(&"show-sample.mkv".to_string()));
+    // A file as large as the limit is a real video, whatever its name
+    assert!(scan(ConfigBuilder::new().sample_size_limit(5)).contains(&"show-sample.mkv".to_string()));
+    assert_eq!(
+        scan(ConfigBuilder::new().skip_hidden_files(false)),
+        vec![".Film.2010.mkv", "Film.2009.mkv", "Other.2011.mkv"]
+    );
+}
+
+#[test]
+fn test_compile_exclude_pattern() {
+    let glob = compile_exclude_pattern("*.part?.mkv").unwrap();
+    assert!(glob.is_match("Film.Part1.mkv"));
+    assert!(!glob.is_match("Film.Part1.mkv.bak"));
+
+    let regex = compile_exclude_pattern(r"re:\btrailer\b").unwrap();
+    assert!(regex.is_match("Film.2009.Trailer.mkv"));
+
+    assert!(compile_exclude_pattern("re:(unclosed").is_err());
+}
+
+#[tokio::test]
+async fn test_rename_file_reports_final_path() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Film.2009.mkv"), b"video").unwrap();
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::Movie)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file_movie("Film.2009.mkv").unwrap().unwrap();
+
+    let result = engine.rename_file(&rename).await;
+
+    assert!(result.success());
+    assert_eq!(result.operation, OperationKind::Rename);
+    let final_path = result.final_path().expect("successful renames report their destination");
+    assert_eq!(final_path, dir.path().join("Film_(2009).mkv"));
+    assert!(final_path.exists());
+    assert!(!rename.original_path.exists());
+}
+
+#[tokio::test]
+async fn test_rename_file_stays_in_the_source_directory() {
+    // Selected files can come from anywhere, not only the engine's own directory
+    let root = tempfile::tempdir().unwrap();
+    let elsewhere = root.path().join("elsewhere");
+    std::fs::create_dir_all(&elsewhere).unwrap();
+    std::fs::write(elsewhere.join("Film.2009.mkv"), b"video").unwrap();
+    let config = ConfigBuilder::new()
+        .directory(root.path())
+        .file_type(FileType::Movie)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let mut rename = engine.process_file_movie("Film.2009.mkv").unwrap().unwrap();
+    rename.original_path = elsewhere.join("Film.2009.mkv");
+
+    let result = engine.rename_file(&rename).await;
+
+    assert_eq!(result.operation, OperationKind::Rename);
+    assert_eq!(result.final_path(), Some(elsewhere.join("Film_(2009).mkv").as_path()));
+    assert!(!root.path().join("Film_(2009).mkv").exists());
+}
+
+#[tokio::test]
+async fn test_rename_file_reports_why_it_failed() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Film.2009.mkv"), b"video").unwrap();
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::Movie)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file_movie("Film.2009.mkv").unwrap().unwrap();
+    std::fs::remove_file(&rename.original_path).unwrap();
+
+    let result = engine.rename_file(&rename).await;
+
+    assert!(!result.success());
+    assert_eq!(result.outcome, RenameOutcome::SourceNotFound(rename.original_path.clone()));
+    assert_eq!(result.final_path(), None);
+    assert!(result.error_message().unwrap().contains("no longer exists"));
+}
+
+#[test]
+fn test_rename_operation_keeps_the_extension() {
+    let dir = tempfile::tempdir().unwrap();
+    let original = dir.path().join("Show.S01E01.mkv");
+    std::fs::write(&original, b"video").unwrap();
+    let mut operation = RenameOperation::new(&original);
+    assert_eq!(operation.get_original_name(), "Show.S01E01.mkv");
+    assert_eq!(operation.get_extension(), "mkv");
+    assert_eq!(operation.get_new_name(), "Show.S01E01.mkv");
+
+    operation.update_new_name("Show S01E01: Pilot ").unwrap();
+    assert_eq!(operation.get_new_name(), "Show S01E01_ Pilot.mkv");
+
+    assert_eq!(operation.execute(), RenameOutcome::Success(dir.path().join("Show S01E01_ Pilot.mkv")));
+    assert!(!original.exists());
+}
+
+#[test]
+fn test_rename_operation_never_replaces_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let original = dir.path().join("a.mkv");
+    std::fs::write(&original, b"a").unwrap();
+    std::fs::write(dir.path().join("b.mkv"), b"b").unwrap();
+    let mut operation = RenameOperation::new(&original);
+    operation.update_new_name("b").unwrap();
+
+    assert_eq!(operation.execute(), RenameOutcome::AlreadyExists(dir.path().join("b.mkv")));
+    assert_eq!(std::fs::read(dir.path().join("b.mkv")).unwrap(), b"b");
+
+    std::fs::remove_file(&original).unwrap();
+    operation.update_new_name("c").unwrap();
+    assert_eq!(operation.execute(), RenameOutcome::SourceNotFound(original));
+}
+
+#[test]
+fn test_rename_operation_rejects_a_blank_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let original = dir.path().join("Show.S01E01.mkv");
+    std::fs::write(&original, b"video").unwrap();
+    let mut operation = RenameOperation::new(&original);
+
+    for blank in ["", "   ", " . "] {
+        assert!(matches!(operation.update_new_name(blank), Err(jellyfin_rename::error::RenameError::InvalidConfig(_))));
+    }
+    assert_eq!(operation.get_new_name(), "Show.S01E01.mkv");
+    assert!(!dir.path().join(".mkv").exists());
+}
+
+#[test]
+fn test_title_symbols_kept_by_default() {
+    let keep = SymbolHandling::default();
+    assert_eq!(normalize_title_symbols("Cat's in the Bag...", &keep, &keep), "Cat's in the Bag...");
+    assert_eq!(normalize_title_symbols("Law & Order", &keep, &keep), "Law & Order");
+}
+
+#[test]
+fn test_title_symbols_stripped() {
+    let keep = SymbolHandling::Keep;
+    let strip = SymbolHandling::Strip;
+    assert_eq!(normalize_title_symbols("Cat's in the Bag...", &strip, &keep), "Cats in the Bag...");
+    assert_eq!(normalize_title_symbols("Cat\u{2019}s in the Bag...", &strip, &keep), "Cats in the Bag...");
+    assert_eq!(normalize_title_symbols("Law & Order", &keep, &strip), "Law Order");
+}
+
+#[test]
+fn test_title_symbols_replaced() {
+    let keep = SymbolHandling::Keep;
+    assert_eq!(
+        normalize_title_symbols("Cat's in the Bag...", &SymbolHandling::Replace("_".to_string()), &keep),
+        "Cat_s in the Bag..."
+    );
+    assert_eq!(
+        normalize_title_symbols("Law & Order", &keep, &SymbolHandling::Replace("and".to_string())),
+        "Law and Order"
+    );
+}
+
+#[test]
+fn test_title_symbol_handling_applies_to_new_names() {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .apostrophes(SymbolHandling::Strip)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    let rename = engine.process_file_standard("Show.S01E02.Cat's.in.the.Bag.mkv").unwrap().unwrap();
+    assert!(!rename.new_name.contains('\''), "unexpected name: {}", rename.new_name);
+    assert!(rename.new_name.contains("Cats"), "unexpected name: {}", rename.new_name);
+}
+
+fn templated_engine(file_type: FileType, template: &str) -> RenameEngine {
+    let config = ConfigBuilder::new()
+        .directory("/test/library")
+        .file_type(file_type)
+        .season("S01".to_string())
+        .template(template.to_string())
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap()
+}
+
+#[test]
+fn test_template_for_episodes() {
+    let engine = templated_engine(FileType::TvShow, "{show} - S{season}E{episode} - {title}.{ext}");
+    let rename = engine.process_file_standard("Breaking.Bad.S01E03.And.the.Bags.in.the.River.mkv").unwrap().unwrap();
+    assert!(rename.new_name.starts_with("Breaking Bad - S01E03 - "), "unexpected name: {}", rename.new_name);
+    assert!(rename.new_name.ends_with(".mkv"), "unexpected name: {}", rename.new_name);
+}
+
+#[test]
+fn test_template_for_movies() {
+    let engine = templated_engine(FileType::Movie, "{title} ({year}){part}.{ext}");
+    let rename = engine.process_file_movie("Film.2009.CD2.avi").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Film (2009)-part2.avi");
+}
+
+#[test]
+fn test_title_ending_in_dots_gets_a_single_dot_before_the_extension() {
+    let mut engine = templated_engine(FileType::TvShow, "{title}.{ext}");
+    engine.set_imdb_titles(vec!["Pilot".to_string(), "Cat's in the Bag...".to_string()]);
+
+    let rename = engine.process_file("Breaking.Bad.S01E02.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Cat's in the Bag.mkv");
+}
+
+#[test]
+fn test_template_drops_empty_optional_placeholders() {
+    let movies = templated_engine(FileType::Movie, "{title} ({year}){part}.{ext}");
+    assert_eq!(movies.process_file_movie("Film.avi").unwrap().unwrap().new_name, "Film.avi");
+
+    let values = TemplateValues { title: "Pilot", season: 1, episode: 1, ext: "mkv", ..Default::default() };
+    assert_eq!(expand_template("{show} - S{season}E{episode} - {title} [{year}].{ext}", &values), "S01E01 - Pilot.mkv");
+    let values = TemplateValues { show: "Show", year: Some("2008"), ..values };
+    assert_eq!(expand_template("{show} - S{season}E{episode} - {title} [{year}].{ext}", &values), "Show - S01E01 - Pilot [2008].mkv");
+}
+
+#[test]
+fn test_template_requires_the_extension() {
+    let result = ConfigBuilder::new()
+        .directory("/test/library")
+        .file_type(FileType::Movie)
+        .template("{title} ({year})".to_string())
+        .build();
+    let err = result.expect_err("a template without {ext} should fail at build time");
+    assert!(err.to_string().contains("{ext}"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_template_rejects_unknown_placeholders() {
+    let result = ConfigBuilder::new()
+        .directory("/test/library")
+        .file_type(FileType::Movie)
+        .template("{title} {foo}.{ext}".to_string())
+        .build();
+    let err = result.expect_err("unknown placeholders should fail at build time");
+    assert!(err.to_string().contains("{foo}"), "unexpected error: {}", err);
+}
+
+fn season_one_engine() -> RenameEngine {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap()
+}
+
+#[test]
+fn test_spelled_out_season_and_episode() {
+    let engine = season_one_engine();
+    for filename in [
+        "Show.Season.2.Episode.3.The.Title.mkv",
+        "Show Season 2 Episode 3 - The Title.mkv",
+        "Show_season_02_episode_03_The_Title.mkv",
+        "Show - Season2-Episode3 - The Title.mkv",
+    ] {
+        assert_eq!(extract_season_from_filename(filename), Some(2), "for {}", filename);
+        assert!(engine.is_episode_filename(filename), "for {}", filename);
+        let rename = engine.process_file_standard(filename).unwrap().unwrap();
+        assert_eq!((rename.season_number, rename.episode_number), (2, 3), "for {}", filename);
+        assert_eq!(rename.new_name, "The_Title_(S02E03).mkv", "for {}", filename);
+    }
+
+    assert!(!engine.is_episode_filename("Show.Seasonal.Episode.3.mkv"));
+}
+
+#[test]
+fn test_same_season_in_another_format_fetches_the_same_titles() {
+    let engine = |season: &str| {
+        let config = ConfigBuilder::new()
+            .directory("/test/shows")
+            .file_type(FileType::TvShow)
+            .season(season.to_string())
+            .imdb(Some("tt0903747".to_string()))
+            .build()
+            .unwrap();
+        RenameEngine::new(config).unwrap()
+    };
+
+    assert!(engine("2").fetches_same_titles_as(&engine("S02")));
+    assert!(!engine("2").fetches_same_titles_as(&engine("S03")));
+    assert!(!engine("2").fetches_same_titles_as(&season_one_engine()));
+}
+
+#[test]
+fn test_renames_record_the_pattern_that_matched() {
+    let engine = season_one_engine();
+    let kind = |filename: &str| engine.process_file_with_manual_season(filename, 1).unwrap().map(|rename| rename.match_kind);
+    assert_eq!(kind("Show.S01E01.Pilot.mkv"), Some(MatchKind::Standard));
+    assert_eq!(kind("Show.Season.1.Episode.1.Pilot.mkv"), Some(MatchKind::Standard));
+    assert_eq!(kind("Show.1x01.Pilot.mkv"), Some(MatchKind::Flexible));
+    assert_eq!(movie_engine(false).process_file_movie("The.Matrix.1999.mkv").unwrap().unwrap().match_kind, MatchKind::Movie);
+}
+
+#[test]
+fn test_multi_episode_file() {
+    let engine = season_one_engine();
+    for filename in ["Show.S01E01E02.Pilot.mkv", "Show.S01E01-E02.Pilot.mkv"] {
+        let rename = engine.process_file_standard(filename).unwrap().unwrap();
+        assert_eq!(rename.new_name, "Pilot_(S01E01-E02).mkv", "for {}", filename);
+        assert_eq!(rename.episode_number, 1);
+    }
+}
+
+#[test]
+fn test_multi_episode_joins_imdb_titles() {
+    let mut engine = season_one_engine();
+    engine.set_imdb_titles(vec!["Pilot".to_string(), "Cat's in the Bag...".to_string()]);
+
+    let rename = engine.process_file_standard("Show.S01E01E02.mkv").unwrap().unwrap();
+    assert_eq!(rename.episode_title, "Pilot & Cat's in the Bag...");
+    assert!(rename.new_name.ends_with("(S01E01-E02).mkv"), "unexpected name: {}", rename.new_name);
+}
+
+#[test]
+fn test_single_episode_unchanged_by_multi_episode_support() {
+    let mut engine = season_one_engine();
+    engine.set_imdb_titles(vec!["Pilot".to_string(), "Second".to_string()]);
+
+    let rename = engine.process_file_standard("Show.S01E02.Whatever.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Second_(S01E02).mkv");
+}
+
+#[test]
+fn test_episode_numbers_are_padded_to_two_digits() {
+    let engine = season_one_engine();
+    let cases = [
+        ("Show.S1E5.Pilot.mkv", "Pilot_(S01E05).mkv"),
+        ("Show.S01E05.Pilot.mkv", "Pilot_(S01E05).mkv"),
+        ("Show.S01E100.Pilot.mkv", "Pilot_(S01E100).mkv"),
+    ];
+    for (filename, expected) in cases {
+        let rename = engine.process_file_standard(filename).unwrap()
+            .unwrap_or_else(|| panic!("{} should match the standard pattern", filename));
+        assert_eq!(rename.new_name, expected, "for {}", filename);
+    }
+}
+
+#[test]
+fn test_flexible_pattern_accepts_short_and_long_episodes() {
+    let engine = season_one_engine();
+    let short = engine.process_file_flexible("Show.1x5.mkv").unwrap().unwrap();
+    assert_eq!(short.episode_number, 5);
+    let long = engine.process_file_flexible("Show.1x100.mkv").unwrap().unwrap();
+    assert_eq!(long.episode_number, 100);
+}
+
+#[test]
+fn test_flexible_pattern_drops_scene_tags_from_the_title() {
+    let engine = season_one_engine();
+    let cases = [
+        ("The.Show.PROPER.1x02.mkv", "The Show"),
+        ("The.Show.iNTERNAL.REPACK.1x02.mkv", "The Show"),
+        ("A.Proper.Job.1x02.mkv", "A Proper Job"),
+    ];
+    for (filename, expected) in cases {
+        let rename = engine.process_file_flexible(filename).unwrap()
+            .unwrap_or_else(|| panic!("{} should match the flexible pattern", filename));
+        assert_eq!(rename.episode_title.trim(), expected, "for {}", filename);
+    }
+}
+
+#[test]
+fn test_additional_video_extensions_are_recognized() {
+    let engine = season_one_engine();
+    for extension in ["m4v", "mov", "wmv", "flv", "webm", "m2ts"] {
+        let filename = format!("Show.S01E02.Second.{}", extension);
+        let rename = engine.process_file_standard(&filename).unwrap()
+            .unwrap_or_else(|| panic!("{} should be recognized", filename));
+        assert_eq!(rename.new_name, format!("Second_(S01E02).{}", extension));
+    }
+}
+
+#[test]
+fn test_extension_case_is_normalized() {
+    let engine = season_one_engine();
+    let rename = engine.process_file_standard("Show.S01E02.Second.MKV").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Second_(S01E02).mkv");
+
+    let movie_engine = movie_engine_with_extension_case(true);
+    let rename = movie_engine.process_file("The.Matrix.1999.1080p.Mp4").unwrap().unwrap();
+    assert_eq!(rename.new_name, "The_Matrix_(1999).mp4");
+
+    // An otherwise correct name is renamed just to fix the extension
+    let rename = movie_engine.process_file("The_Matrix_(1999).MKV").unwrap().unwrap();
+    assert_eq!(rename.new_name, "The_Matrix_(1999).mkv");
+    assert!(rename.needs_rename);
+}
+
+#[test]
+fn test_extension_case_is_preserved() {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .normalize_extension_case(false)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file_standard("Show.S01E02.Second.MKV").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Second_(S01E02).MKV");
+
+    let rename = movie_engine_with_extension_case(false).process_file("The_Matrix_(1999).MKV").unwrap().unwrap();
+    assert!(!rename.needs_rename);
+}
+
+fn movie_engine_with_extension_case(normalize_extension_case: bool) -> RenameEngine {
+    let config = ConfigBuilder::new()
+        .directory("/test/movies")
+        .file_type(FileType::Movie)
+        .normalize_extension_case(normalize_extension_case)
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap()
+}
+
+#[test]
+fn test_extensions_can_be_overridden() {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .extensions(vec![".mkv".to_string()])
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    assert!(engine.process_file_standard("Show.S01E02.Second.mkv").unwrap().is_some());
+    assert!(engine.process_file_standard("Show.S01E02.Second.mp4").unwrap().is_none());
+
+    let empty = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::Movie)
+        .extensions(Vec::new())
+        .build();
+    assert!(empty.is_err());
+}
+
+fn show_tree() -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    let files = [
+        "Show.S01E01.Pilot.mkv",
+        "Season 01/Show.S01E02.Second.mkv",
+        "Season 02/Show.2x03.mkv",
+    ];
+    for file in files {
+        let path = root.path().join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"video").unwrap();
+    }
+    root
+}
+
+fn scan_show(root: &std::path::Path, recursive: bool) -> Vec<FileRename> {
+    let config = ConfigBuilder::new()
+        .directory(root)
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .recursive(recursive)
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap().scan_directory().unwrap()
+}
+
+#[test]
+fn test_recursive_scan_keeps_nested_paths() {
+    let root = show_tree();
+    let renames = scan_show(root.path(), true);
+    assert_eq!(renames.len(), 3);
+
+    let nested = renames.iter().find(|r| r.original_name == "Show.S01E02.Second.mkv").unwrap();
+    assert_eq!(nested.original_path, root.path().join("Season 01").join("Show.S01E02.Second.mkv"));
+}
+
+#[test]
+fn test_recursive_scan_uses_parent_directory_season() {
+    let root = show_tree();
+    let renames = scan_show(root.path(), true);
+
+    let season_two = renames.iter().find(|r| r.original_name == "Show.2x03.mkv").unwrap();
+    assert!(season_two.new_name.contains("S02"), "unexpected name: {}", season_two.new_name);
+}
+
+#[test]
+fn test_scan_is_not_recursive_by_default() {
+    let root = show_tree();
+    let renames = scan_show(root.path(), false);
+
+    let originals: Vec<&str> = renames.iter().map(|r| r.original_name.as_str()).collect();
+    assert_eq!(originals, vec!["Show.S01E01.Pilot.mkv"]);
+}
+
+#[tokio::test]
+async fn test_rename_file_moves_subtitle_companions() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S01E01.Pilot.srt", "Show.S01E01.Pilot.en.srt", "Show.S01E02.Other.srt"] {
+        std::fs::write(dir.path().join(filename), b"data").unwrap();
+    }
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file_standard("Show.S01E01.Pilot.mkv").unwrap().unwrap();
+
+    let result = engine.rename_file(&rename).await;
+
+    assert!(result.success());
+    assert_eq!(result.companions.len(), 2);
+    assert!(dir.path().join("Pilot_(S01E01).mkv").exists());
+    assert!(dir.path().join("Pilot_(S01E01).srt").exists());
+    assert!(dir.path().join("Pilot_(S01E01).en.srt").exists());
+    assert!(dir.path().join("Show.S01E02.Other.srt").exists(), "unrelated subtitles stay put");
+}
+
+#[tokio::test]
+async fn test_rename_file_preserves_modification_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let original = dir.path().join("Show.S01E01.Pilot.mkv");
+    std::fs::write(&original, b"data").unwrap();
+    let mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+    filetime::set_file_mtime(&original, mtime).unwrap();
+
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .preserve_timestamps(true)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file_standard("Show.S01E01.Pilot.mkv").unwrap().unwrap();
+
+    let result = engine.rename_file(&rename).await;
+
+    assert!(result.success());
+    let metadata = std::fs::metadata(dir.path().join("Pilot_(S01E01).mkv")).unwrap();
+    assert_eq!(filetime::FileTime::from_last_modification_time(&metadata), mtime);
+}
+
+#[test]
+fn test_restore_timestamps_after_a_cross_device_copy() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("from.mkv");
+    let to = dir.path().join("to.mkv");
+    std::fs::write(&from, b"data").unwrap();
+    let mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+    filetime::set_file_mtime(&from, mtime).unwrap();
+    let metadata = std::fs::metadata(&from).unwrap();
+
+    // The fallback for cross-device moves writes a fresh file
+    copy_then_delete(&from, &to).unwrap();
+    let copied = std::fs::metadata(&to).unwrap();
+    assert_ne!(filetime::FileTime::from_last_modification_time(&copied), mtime);
+
+    restore_timestamps(&to, &metadata).unwrap();
+    let restored = std::fs::metadata(&to).unwrap();
+    assert_eq!(filetime::FileTime::from_last_modification_time(&restored), mtime);
+}
+
+#[tokio::test]
+async fn test_organize_into_folders_moves_episode_and_sidecar() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S02E01.Pilot.mkv"), b"data").unwrap();
+    std::fs::write(dir.path().join("Show.S02E01.Pilot.en.srt"), b"subs").unwrap();
+
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::TvShow)
+        .season("S02".to_string())
+        .organize_into_folders(true)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file("Show.S02E01.Pilot.mkv").unwrap().unwrap();
+
+    let result = engine.rename_file(&rename).await;
+
+    assert!(result.success(), "{:?}", result.error_message());
+    let season_dir = dir.path().join("Season 02");
+    assert_eq!(result.final_path(), Some(season_dir.join("Pilot_(S02E01).mkv").as_path()));
+    assert_eq!(result.operation, OperationKind::Move);
+    assert_eq!(result.created_dirs, vec![season_dir.clone()]);
+    assert!(season_dir.join("Pilot_(S02E01).en.srt").exists());
+
+    // A directory that already is the season folder is not nested again
+    assert_eq!(season_folder_path(&season_dir, 2), season_dir);
+    assert_eq!(season_folder_path(&season_dir, 3), season_dir.join("Season 03"));
+}
+
+async fn rename_onto_existing_target(strategy: ConflictStrategy) -> (tempfile::TempDir, RenameResult) {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.mkv"), b"new").unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.srt"), b"subs").unwrap();
+    std::fs::write(dir.path().join("Pilot_(S01E01).mkv"), b"old").unwrap();
+
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .on_conflict(strategy)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file("Show.S01E01.Pilot.mkv").unwrap().unwrap();
+    let result = engine.rename_file(&rename).await;
+    (dir, result)
+}
+
+#[tokio::test]
+async fn test_conflict_strategy_skip_leaves_both_files() {
+    let (dir, result) = rename_onto_existing_target(ConflictStrategy::Skip).await;
+
+    assert_eq!(result.outcome, RenameOutcome::Skipped(dir.path().join("Pilot_(S01E01).mkv")));
+    assert!(!result.success());
+    assert_eq!(std::fs::read(dir.path().join("Pilot_(S01E01).mkv")).unwrap(), b"old");
+    assert!(dir.path().join("Show.S01E01.Pilot.mkv").exists());
+}
+
+#[tokio::test]
+async fn test_conflict_strategy_overwrite_replaces_the_target() {
+    let (dir, result) = rename_onto_existing_target(ConflictStrategy::Overwrite).await;
+
+    let target = dir.path().join("Pilot_(S01E01).mkv");
+    let replaced_by = std::fs::read(&target).unwrap();
+    let restored = take_back_from_trash(&target);
+
+    assert!(result.success(), "{:?}", result.error_message());
+    assert_eq!(replaced_by, b"new");
+    assert!(!dir.path().join("Show.S01E01.Pilot.mkv").exists());
+    // The replaced file went to the trash, from where it can be put back
+    assert_eq!(result.trashed, vec![target.clone()]);
+    if let Some(restored) = restored {
+        assert_eq!(restored, b"old");
+    }
+}
+
+#[tokio::test]
+async fn test_conflict_strategy_suffix_picks_a_free_name() {
+    let (dir, result) = rename_onto_existing_target(ConflictStrategy::RenameWithSuffix).await;
+
+    let target = dir.path().join("Pilot_(S01E01) (2).mkv");
+    assert_eq!(result.final_path(), Some(target.as_path()));
+    assert_eq!(std::fs::read(&target).unwrap(), b"new");
+    assert_eq!(std::fs::read(dir.path().join("Pilot_(S01E01).mkv")).unwrap(), b"old");
+    // Sidecars take the suffixed name too
+    assert!(dir.path().join("Pilot_(S01E01) (2).srt").exists());
+}
+
+async fn rename_onto_existing_sidecar(strategy: ConflictStrategy) -> (tempfile::TempDir, RenameResult) {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.mkv"), b"video").unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.srt"), b"new subs").unwrap();
+    std::fs::write(dir.path().join("Pilot_(S01E01).srt"), b"old subs").unwrap();
+
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .on_conflict(strategy)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file("Show.S01E01.Pilot.mkv").unwrap().unwrap();
+    let result = engine.rename_file(&rename).await;
+    (dir, result)
+}
+
+#[tokio::test]
+async fn test_conflict_strategy_skip_leaves_an_existing_sidecar() {
+    let (dir, result) = rename_onto_existing_sidecar(ConflictStrategy::Skip).await;
+
+    // The video has a free target and goes; only its subtitle stays behind
+    assert!(result.success(), "{:?}", result.error_message());
+    assert!(result.companions.is_empty());
+    assert_eq!(std::fs::read(dir.path().join("Pilot_(S01E01).srt")).unwrap(), b"old subs");
+    assert_eq!(std::fs::read(dir.path().join("Show.S01E01.Pilot.srt")).unwrap(), b"new subs");
+}
+
+/// Puts `path` back from the trash, replacing whatever is there now, and returns
+/// its contents; `None` where the trash can't be restored from. Tests call this
+/// before asserting so a failure doesn't leave their files in the real trash.
+#[cfg(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+))]
+fn take_back_from_trash(path: &std::path::Path) -> Option<Vec<u8>> {
+    let _ = std::fs::remove_file(path);
+    jellyfin_rename::rename_engine::restore_from_trash(path).ok()?;
+    std::fs::read(path).ok()
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+)))]
+fn take_back_from_trash(_path: &std::path::Path) -> Option<Vec<u8>> {
+    None
+}
+
+#[tokio::test]
+async fn test_conflict_strategy_overwrite_trashes_a_replaced_sidecar() {
+    let (dir, result) = rename_onto_existing_sidecar(ConflictStrategy::Overwrite).await;
+
+    let sidecar = dir.path().join("Pilot_(S01E01).srt");
+    let replaced_by = std::fs::read(&sidecar).unwrap();
+    let restored = take_back_from_trash(&sidecar);
+
+    assert!(result.success(), "{:?}", result.error_message());
+    assert_eq!(result.trashed, vec![sidecar.clone()]);
+    assert_eq!(replaced_by, b"new subs");
+    if let Some(restored) = restored {
+        assert_eq!(restored, b"old subs");
+    }
+}
+
+#[tokio::test]
+async fn test_conflict_strategy_suffix_applies_to_sidecars() {
+    let (dir, result) = rename_onto_existing_sidecar(ConflictStrategy::RenameWithSuffix).await;
+
+    let suffixed = dir.path().join("Pilot_(S01E01) (2).srt");
+    assert_eq!(result.companions, vec![(dir.path().join("Show.S01E01.Pilot.srt"), suffixed.clone())]);
+    assert_eq!(std::fs::read(&suffixed).unwrap(), b"new subs");
+    assert_eq!(std::fs::read(dir.path().join("Pilot_(S01E01).srt")).unwrap(), b"old subs");
+}
+
+#[test]
+fn test_copy_then_delete_moves_contents() {
+    let source_dir = tempfile::tempdir().unwrap();
+    let target_dir = tempfile::tempdir().unwrap();
+    let source = source_dir.path().join("Show.S01E01.mkv");
+    let target = target_dir.path().join("Pilot_(S01E01).mkv");
+    std::fs::write(&source, b"video data").unwrap();
+
+    copy_then_delete(&source, &target).unwrap();
+
+    assert!(!source.exists());
+    assert_eq!(std::fs::read(&target).unwrap(), b"video data");
+}
+
+#[test]
+fn test_copy_then_delete_keeps_source_on_failure() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("Show.S01E01.mkv");
+    std::fs::write(&source, b"video data").unwrap();
+
+    let result = copy_then_delete(&source, &dir.path().join("missing").join("Pilot.mkv"));
+
+    assert!(result.is_err());
+    assert!(source.exists());
+}
+
+#[test]
+fn test_move_file_renames_on_the_same_filesystem() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("Show.S01E01.mkv");
+    std::fs::write(&source, b"video data").unwrap();
+
+    let cross_device = move_file(&source, &dir.path().join("Pilot_(S01E01).mkv")).unwrap();
+
+    assert!(!cross_device);
+    assert!(dir.path().join("Pilot_(S01E01).mkv").exists());
+}
+
+#[test]
+fn test_long_title_is_truncated_before_the_suffix() {
+    let config = ConfigBuilder::new()
+        .directory(".")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+    let filename = format!("Show.S01E01.{}.mkv", "Long".repeat(75));
+
+    let rename = engine.process_file_standard(&filename).unwrap().unwrap();
+
+    assert!(rename.new_name.len() <= 250, "{} bytes", rename.new_name.len());
+    assert!(rename.new_name.starts_with("LongLong"));
+    assert!(rename.new_name.ends_with("_(S01E01).mkv"), "unexpected name: {}", rename.new_name);
+}
+
+#[test]
+fn test_truncation_respects_char_boundaries() {
+    let config = ConfigBuilder::new()
+        .directory(".")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .max_filename_bytes(40)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    let rename = engine.process_file_standard("Show.S01E01.ÉtéÉtéÉtéÉtéÉtéÉtéÉtéÉtéÉté.mkv").unwrap().unwrap();
+
+    assert!(rename.new_name.len() <= 40, "{} bytes", rename.new_name.len());
+    assert!(rename.new_name.ends_with("_(S01E01).mkv"), "unexpected name: {}", rename.new_name);
+}
+
+#[test]
+fn test_detect_file_type() {
+    assert_eq!(detect_file_type(&["Show.S01E01.mkv", "Show.S01E02.mkv", "Extras.mkv"]), Some(FileType::TvShow));
+    assert_eq!(detect_file_type(&["Show.1x01.Pilot.avi"]), Some(FileType::TvShow));
+    assert_eq!(detect_file_type(&["The.Matrix.1999.1080p.mkv", "Heat.1995.mkv"]), Some(FileType::Movie));
+    assert_eq!(detect_file_type(&["Show.S01E01.mkv", "Movie.One.mkv", "Movie.Two.mkv"]), Some(FileType::Movie));
+    assert_eq!(detect_file_type(&["notes.txt"]), None);
+    assert_eq!(detect_file_type::<&str>(&[]), None);
+}
+
+#[test]
+fn test_auto_file_type_classifies_each_file() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S02E03.Later.mkv", "Heat.1995.1080p.BluRay.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::Auto)
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    let mut names: Vec<String> = engine.scan_directory().unwrap().into_iter().map(|rename| rename.new_name).collect();
+    names.sort();
+
+    assert_eq!(names, vec!["Heat_(1995).mkv", "Later_(S02E03).mkv", "Pilot_(S01E01).mkv"]);
+}
+
+#[test]
+fn test_parse_tmdb_season_orders_by_episode_number() {
+    let json = serde_json::json!({
+        "season_number": 1,
+        "episodes": [
+            { "episode_number": 2, "name": "Cat's in the Bag..." },
+            { "episode_number": 1, "name": "Pilot" },
+            { "episode_number": 3, "name": "...And the Bag's in the River" }
+        ]
+    });
+
+    let titles = parse_tmdb_season(&json).unwrap();
+    assert_eq!(titles, vec!["Pilot", "Cat's in the Bag...", "...And the Bag's in the River"]);
+}
+
+#[test]
+fn test_parse_tmdb_season_reports_api_errors() {
+    let json = serde_json::json!({
+        "status_code": 7,
+        "status_message": "Invalid API key: You must be granted a valid key."
+    });
+
+    let err = parse_tmdb_season(&json).unwrap_err();
+    assert!(err.to_string().contains("Invalid API key"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_parse_omdb_season_orders_by_episode_number() {
+    let json = serde_json::json!({
+        "Title": "Breaking Bad",
+        "Season": "1",
+        "Episodes": [
+            { "Title": "Cat's in the Bag...", "Episode": "2", "imdbID": "tt1054724" },
+            { "Title": "Pilot", "Episode": "1", "imdbID": "tt0959621" }
+        ],
+        "Response": "True"
+    });
+
+    let titles = parse_omdb_season(&json).unwrap();
+    assert_eq!(titles, vec!["Pilot", "Cat's in the Bag..."]);
+
+    let json = serde_json::json!({ "Response": "False", "Error": "Invalid API key!" });
+    let err = parse_omdb_season(&json).unwrap_err();
+    assert!(err.to_string().contains("Invalid API key"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_manual_season_name_with_year() {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S02".to_string())
+        .year(Some("2023".to_string()))
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    let rename = engine.process_file_with_manual_season("Show.S01E01.Pilot.mkv", 2).unwrap().unwrap();
+    assert_eq!(rename.new_name, "Pilot_(S02E01)_(2023).mkv");
+}
+
+#[test]
+fn test_manual_season_name_without_year() {
+    let engine = season_one_engine();
+
+    let rename = engine.process_file_with_manual_season("Show.S01E01.Pilot.mkv", 2).unwrap().unwrap();
+    assert_eq!(rename.new_name, "Pilot_(S02E01).mkv");
+    assert_eq!(rename.season_number, 2);
+}
+
+#[test]
+fn test_show_name_prefixes_episode_names() {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .show_name(Some("Breaking Bad".to_string()))
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    let rename = engine.process_file("Breaking.Bad.S01E01.Pilot.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Breaking_Bad_S01E01_Pilot.mkv");
+
+    // A blank name keeps the usual format
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .show_name(Some("  ".to_string()))
+        .build()
+        .unwrap();
+    assert_eq!(config.show_name, None);
+    let engine = RenameEngine::new(config).unwrap();
+    let rename = engine.process_file("Breaking.Bad.S01E01.Pilot.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Pilot_(S01E01).mkv");
+}
+
+#[test]
+fn test_separator_replaces_underscores_in_built_in_names() {
+    let engine_with = |separator: char, file_type: FileType| {
+        let config = ConfigBuilder::new()
+            .directory("/test/media")
+            .file_type(file_type)
+            .season("S01".to_string())
+            .separator(separator)
+            .build()
+            .unwrap();
+        RenameEngine::new(config).unwrap()
+    };
+
+    let spaced = engine_with(' ', FileType::TvShow);
+    let rename = spaced.process_file("Show.S01E02.The.Long.Night.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "The Long Night (S01E02).mkv");
+
+    let dotted = engine_with('.', FileType::Movie);
+    let rename = dotted.process_file("The Matrix (1999) 1080p.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "The.Matrix.(1999).mkv");
+
+    // Characters that are illegal in filenames are rejected up front
+    let err = ConfigBuilder::new()
+        .directory("/test/media")
+        .file_type(FileType::Movie)
+        .separator('/')
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("Separator"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_casing_controls_titles_parsed_from_filenames() {
+    let engine_with = |casing: Casing, file_type: FileType| {
+        let config = ConfigBuilder::new()
+            .directory("/test/media")
+            .file_type(file_type)
+            .season("S01".to_string())
+            .casing(casing)
+            .build()
+            .unwrap();
+        RenameEngine::new(config).unwrap()
+    };
+
+    let title_case = engine_with(Casing::TitleCase, FileType::Movie);
+    assert_eq!(title_case.process_file("iCarly.2007.mkv").unwrap().unwrap().new_name, "ICarly_(2007).mkv");
+
+    let preserve = engine_with(Casing::Preserve, FileType::Movie);
+    assert_eq!(preserve.process_file("iCarly.2007.mkv").unwrap().unwrap().new_name, "iCarly_(2007).mkv");
+
+    let preserve = engine_with(Casing::Preserve, FileType::TvShow);
+    let rename = preserve.process_file("iCarly.S01E01.iPilot.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "iPilot_(S01E01).mkv");
+
+    let lowercase = engine_with(Casing::Lowercase, FileType::TvShow);
+    let rename = lowercase.process_file("Show.S01E01.The.Long.Night.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "the_long_night_(S01E01).mkv");
+
+    // Fetched titles are used as published
+    let mut title_case = engine_with(Casing::TitleCase, FileType::TvShow);
+    title_case.set_imdb_titles(vec!["iCarly Saves TV".to_string()]);
+    let rename = title_case.process_file("iCarly.S01E01.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "iCarly_Saves_TV_(S01E01).mkv");
+}
+
+#[test]
+fn test_standard_and_flexible_patterns_tag_the_year_alike() {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .year(Some("2008".to_string()))
+        .build()
+        .unwrap();
+    let engine = RenameEngine::new(config).unwrap();
+
+    let standard = engine.process_file_standard("Show.S01E01.Pilot.mkv").unwrap().unwrap();
+    let flexible = engine.process_file_flexible("Show.1x01.mkv").unwrap().unwrap();
+    assert_eq!(standard.new_name, "Pilot_(S01E01)_(2008).mkv");
+    // Flexible names used to glue the year on, as in `Show__S01(2008).mkv`
+    assert_eq!(flexible.new_name, "Show__S01_(2008).mkv");
+
+    // Without a year neither pattern's names change
+    let engine = season_one_engine();
+    assert_eq!(engine.process_file_standard("Show.S01E01.Pilot.mkv").unwrap().unwrap().new_name, "Pilot_(S01E01).mkv");
+    assert_eq!(engine.process_file_flexible("Show.1x01.mkv").unwrap().unwrap().new_name, "Show__S01.mkv");
+}
+
+#[test]
+fn test_fetched_titles_are_matched_by_episode_number() {
+    let titles = titles_by_episode(vec![
+        (4, "Cancer Man".to_string()),
+        (1, "Pilot".to_string()),
+        (0, "Unaired Pilot".to_string()),
+        (2, "Cat's in the Bag...".to_string()),
+    ]);
+    assert_eq!(titles, vec!["Pilot", "Cat's in the Bag...", "", "Cancer Man"]);
+
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .build()
+        .unwrap();
+    let mut engine = RenameEngine::new(config).unwrap();
+    engine.set_imdb_titles(titles);
+
+    let name = |filename: &str| engine.process_file(filename).unwrap().unwrap().new_name;
+    assert_eq!(name("Show.S01E04.mkv"), "Cancer_Man_(S01E04).mkv");
+    // A gap, episode 0 and a number past the list fall back to the filename
+    assert_eq!(name("Show.S01E03.And.the.Bags.in.the.River.mkv"), "And_The_Bags_In_The_River_(S01E03).mkv");
+    assert_eq!(name("Show.S01E00.Special.mkv"), "Special_(S01E00).mkv");
+    assert_eq!(name("Show.S01E09.Late.mkv"), "Late_(S01E09).mkv");
+    // The flexible pattern used to index the list directly and underflowed on episode 0
+    assert!(engine.process_file_flexible("Show.1x00.mkv").unwrap().is_some());
+}
+
+#[test]
+fn test_titles_are_nfc_normalized_and_optionally_transliterated() {
+    // "Café — Finalé" with decomposed accents, as some scrapers return it
+    let decomposed = "Cafe\u{301} \u{2014} Finale\u{301}";
+    assert_eq!(normalize_unicode(decomposed, false), "Café \u{2014} Finalé");
+    assert_eq!(normalize_unicode(decomposed, true), "Cafe -- Finale");
+
+    let engine_with = |transliterate: bool| {
+        let config = ConfigBuilder::new()
+            .directory("/test/shows")
+            .file_type(FileType::TvShow)
+            .season("S01".to_string())
+            .transliterate(transliterate)
+            .build()
+            .unwrap();
+        let mut engine = RenameEngine::new(config).unwrap();
+        engine.set_imdb_titles(vec![decomposed.to_string()]);
+        engine
+    };
+
+    let name = |engine: &RenameEngine| engine.process_file("Show.S01E01.mkv").unwrap().unwrap().new_name;
+    assert_eq!(name(&engine_with(false)), "Café_\u{2014}_Finalé_(S01E01).mkv");
+    assert_eq!(name(&engine_with(true)), "Cafe_--_Finale_(S01E01).mkv");
+}
+
+const BRACKETED_PATTERN: &str = r"^\[[^\]]+\] (?P<show>.+?) S(?P<season>\d+) - (?P<episode>\d+) - (?P<title>.+?) \[[^\]]*\]\.(?P<extension>mkv)$";
+
+#[test]
+fn test_custom_pattern_is_tried_first() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["[Group] My Show S2 - 05 - The Heist [1080p].mkv", "My.Show.S02E06.Aftermath.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+    let config = ConfigBuilder::new()
+        .directory(dir.path())
+        .file_type(FileType::TvShow)
+        .season("S02".to_string())
+        .custom_pattern(BRACKETED_PATTERN.to_string())
+        .build()
+        .unwrap();
+    let renames = RenameEngine::new(config).unwrap().scan_directory().unwrap();
+
+    let mut names: Vec<&str> = renames.iter().map(|r| r.new_name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["Aftermath_(S02E06).mkv", "The_Heist_(S02E05).mkv"]);
+}
+
+#[test]
+fn test_custom_pattern_is_validated_at_build_time() {
+    let build = |pattern: &str| ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .custom_pattern(pattern.to_string())
+        .build();
+
+    let err = build(r"(?P<title>.+").unwrap_err();
+    assert!(err.to_string().contains("Invalid custom pattern"), "unexpected error: {}", err);
+
+    let err = build(r"(?P<title>.+) (?P<season>\d+)\.(?P<extension>mkv)").unwrap_err();
+    assert!(err.to_string().contains("episode"), "unexpected error: {}", err);
+
+    assert!(build("  ").unwrap().custom_pattern.is_none());
+    assert!(build(BRACKETED_PATTERN).is_ok());
+}
+
+#[test]
+fn test_process_file_dispatches_on_file_type() {
+    let engine = season_one_engine();
+    let standard = engine.process_file("Show.S01E03.mkv").unwrap().unwrap();
+    assert_eq!(standard.episode_number, 3);
+    let flexible = engine.process_file("Show 1x04.mkv").unwrap().unwrap();
+    assert_eq!(flexible.episode_number, 4);
+    assert!(engine.process_file("Film.2009.CD2.avi").unwrap().is_none());
+
+    let engine = movie_engine(false);
+    let movie = engine.process_file("Film.2009.CD2.avi").unwrap().unwrap();
+    assert_eq!(movie.new_name, "Film_(2009)-part2.avi");
+}
+
+fn absolute_engine(directory: &std::path::Path, episodes_per_season: Vec<u32>) -> RenameEngine {
+    let config = ConfigBuilder::new()
+        .directory(directory)
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .absolute_numbering(true)
+        .episodes_per_season(episodes_per_season)
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap()
+}
+
+#[test]
+fn test_map_absolute_episode() {
+    assert_eq!(map_absolute_episode(135, &[]), (1, 135));
+    assert_eq!(map_absolute_episode(24, &[24, 24, 12]), (1, 24));
+    assert_eq!(map_absolute_episode(25, &[24, 24, 12]), (2, 1));
+    assert_eq!(map_absolute_episode(60, &[24, 24, 12]), (3, 12));
+    assert_eq!(map_absolute_episode(65, &[24, 24, 12]), (3, 17));
+}
+
+#[test]
+fn test_absolute_numbered_file() {
+    let engine = absolute_engine(std::path::Path::new("/test/anime"), vec![24, 24]);
+
+    let rename = engine.process_file_absolute("[Group] Show - 27 [1080p].mkv").unwrap().unwrap();
+    assert_eq!(rename.season_number, 2);
+    assert_eq!(rename.episode_number, 3);
+    assert_eq!(rename.new_name, "Episode_(S02E03).mkv");
+
+    assert!(engine.process_file_absolute("Show.S01E01.mkv").unwrap().is_none());
+}
+
+#[test]
+fn test_absolute_numbering_is_a_scan_fallback() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Show - 135.mkv", "Show - 136v2.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+
+    let renames = absolute_engine(dir.path(), Vec::new()).scan_directory().unwrap();
+    let mut episodes: Vec<u32> = renames.iter().map(|r| r.episode_number).collect();
+    episodes.sort();
+    assert_eq!(episodes, vec![135, 136]);
+    assert!(renames.iter().all(|r| r.season_number == 1));
+
+    let without = scan_show(dir.path(), false);
+    assert!(without.is_empty(), "absolute numbers need the option enabled");
+}
+
+fn date_engine(directory: &std::path::Path, order: DateOrder) -> RenameEngine {
+    let config = ConfigBuilder::new()
+        .directory(directory)
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .date_based(true)
+        .date_order(order)
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap()
+}
+
+#[test]
+fn test_year_first_dates_ignore_the_date_order() {
+    // The order setting only settles `05.11.2023`; a leading year is never ambiguous
+    for order in [DateOrder::DayFirst, DateOrder::MonthFirst] {
+        let engine = date_engine(std::path::Path::new("/test/daily"), order);
+
+        let dotted = engine.process_file_date("Show.2023.11.05.mkv").unwrap().unwrap();
+        assert_eq!(dotted.new_name, "Show_(2023-11-05).mkv");
+        let dashed = engine.process_file_date("Show - 2023-11-05.720p.mkv").unwrap().unwrap();
+        assert_eq!(dashed.new_name, "Show_(2023-11-05).mkv");
+    }
+}
+
+#[test]
+fn test_date_day_first() {
+    let engine = date_engine(std::path::Path::new("/test/daily"), DateOrder::DayFirst);
+
+    let rename = engine.process_file_date("The.Late.Show.05.11.2023.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "The_Late_Show_(2023-11-05).mkv");
+    assert!(engine.process_file_date("Show.11.25.2023.mkv").unwrap().is_none(), "month 25 is not a date");
+}
+
+#[test]
+fn test_date_month_first() {
+    let engine = date_engine(std::path::Path::new("/test/daily"), DateOrder::MonthFirst);
+
+    let rename = engine.process_file_date("Show - 11-05-2023.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "Show_(2023-11-05).mkv");
+    assert_eq!(rename.episode_title, "2023-11-05");
+}
+
+#[test]
+fn test_date_files_are_a_scan_fallback() {
+    let dir = tempfile::tempdir().unwrap();
+    for filename in ["Show.2023.11.05.mkv", "Show.S01E01.Pilot.mkv"] {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+
+    let mut names: Vec<String> = date_engine(dir.path(), DateOrder::DayFirst)
+        .scan_directory()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.new_name)
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Pilot_(S01E01).mkv", "Show_(2023-11-05).mkv"]);
+}
+
+
+#[test]
+fn test_movie_keeps_year_found_in_filename() {
+    let engine = movie_engine(false);
+    let rename = engine.process_file_movie("The.Matrix.1999.1080p.mkv").unwrap().unwrap();
+    assert_eq!(rename.new_name, "The_Matrix_(1999).mkv");
+    assert_eq!(rename.episode_title, "The Matrix");
+
+    let name = |filename: &str| engine.process_file_movie(filename).unwrap().unwrap().new_name;
+    assert_eq!(name("The.Matrix.1999.mkv"), "The_Matrix_(1999).mkv");
+    assert_eq!(name("Film (2009) - disc 1.mkv"), "Film_(2009)-part1.mkv");
+    // Only the last year is the release year; earlier ones belong to the title
+    assert_eq!(name("Blade.Runner.2049.2017.mkv"), "Blade_Runner_2049_(2017).mkv");
+    assert_eq!(name("2012.2009.mkv"), "2012_(2009).mkv");
+    assert_eq!(name("2012.mkv"), "2012.mkv");
+}
+
+#[test]
+fn test_release_tags_are_stripped_from_episode_titles() {
+    let engine = season_one_engine();
+    let name = |filename: &str| engine.process_file(filename).unwrap().unwrap().new_name;
+
+    assert_eq!(name("Show.S01E02.The.Heist.[HorribleSubs].mkv"), "The_Heist_(S01E02).mkv");
+    assert_eq!(name("[HorribleSubs] Show - S01E06 - Gone [720p].mkv"), "Gone_(S01E06).mkv");
+    assert_eq!(name("Show.S01E03.Pilot-RARBG.mkv"), "Pilot_(S01E03).mkv");
+    assert_eq!(name("Show.S01E09.Pilot-NTb.mkv"), "Pilot_(S01E09).mkv");
+    assert_eq!(name("Show.S01E07.Gone.(WEB).(x264).mkv"), "Gone_(S01E07).mkv");
+
+    // Real parts of a title survive
+    assert_eq!(name("Show S01E04 Dinner (Part 2).mkv"), "Dinner_(Part_2)_(S01E04).mkv");
+    assert_eq!(name("Show.S01E05.Spider-Man.mkv"), "Spider_Man_(S01E05).mkv");
+    assert_eq!(name("Show.S01E08.Made.in.the.(USA).mkv"), "Made_In_The_(USA)_(S01E08).mkv");
+    assert_eq!(name("Show.S01E10.Rocky.(II).mkv"), "Rocky_(II)_(S01E10).mkv");
+    assert_eq!(name("Show.S01E11.Mega-MEN.mkv"), "Mega_MEN_(S01E11).mkv");
+
+    // An unknown group still goes when it hangs off a release tag
+    assert_eq!(name("Show.S01E12.Pilot.x264-FLUX.mkv"), "Pilot_(S01E12).mkv");
+}
@@ -0,0 +1,119 @@
+use jellyfin_rename::rename_engine::{FileType, TitleSource};
+use jellyfin_rename::settings::{FileTypeSetting, ProviderSetting, Settings};
+use jellyfin_rename::tui::app::App;
+use jellyfin_rename::tui::last_run::{LastRun, LastRunStore};
+
+fn write_settings(contents: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("jellyfin-renamer.toml");
+    std::fs::write(&path, contents).unwrap();
+    (dir, path)
+}
+
+#[test]
+fn test_settings_parse() {
+    let (_dir, path) = write_settings(r#"
+directory = "/media/incoming"
+file_type = "movie"
+template = "{title} ({year}).{ext}"
+provider = "tmdb"
+tmdb_api_key = "secret"
+incomplete_suffixes = ["part", "tmp"]
+"#);
+
+    let settings = Settings::from_path(&path).unwrap();
+    assert_eq!(settings.directory.as_deref(), Some("/media/incoming"));
+    assert_eq!(settings.file_type, Some(FileTypeSetting::Movie));
+    assert_eq!(settings.provider, Some(ProviderSetting::Tmdb));
+    assert_eq!(settings.imdb_id, None);
+    assert_eq!(settings.incomplete_suffixes, Some(vec!["part".to_string(), "tmp".to_string()]));
+}
+
+#[test]
+fn test_invalid_settings_are_ignored() {
+    let (_dir, path) = write_settings("file_type = \"cartoon\"");
+    assert!(Settings::from_path(&path).is_none());
+
+    let (_dir, path) = write_settings("this is not toml");
+    assert!(Settings::from_path(&path).is_none());
+}
+
+#[test]
+fn test_settings_fill_app_defaults() {
+    let settings = Settings {
+        directory: Some("/media/incoming".to_string()),
+        file_type: Some(FileTypeSetting::Movie),
+        template: Some("{title}.{ext}".to_string()),
+        provider: Some(ProviderSetting::Tmdb),
+        imdb_id: None,
+        tmdb_api_key: Some("secret".to_string()),
+        omdb_api_key: None,
+        processing_delay_ms: Some(250),
+        write_report: None,
+        report_path: None,
+        incomplete_suffixes: Some(vec!["tmp".to_string()]),
+    };
+
+    let mut app = App::new();
+    assert!(app.processing_delay.is_zero());
+    app.apply_settings(&settings);
+    assert_eq!(app.processing_delay, std::time::Duration::from_millis(250));
+    assert_eq!(app.directory_input, "/media/incoming");
+    assert_eq!(app.file_type, FileType::Movie);
+    assert_eq!(app.template.as_deref(), Some("{title}.{ext}"));
+    assert!(app.use_imdb);
+    assert_eq!(app.title_source, TitleSource::Tmdb);
+    assert_eq!(app.tmdb_api_key_input, "secret");
+    assert_eq!(app.incomplete_suffixes, Some(vec!["tmp".to_string()]));
+
+    // A directory from the command line wins over the file
+    let mut app = App::with_directory("/from/cli".to_string());
+    app.apply_settings(&settings);
+    assert_eq!(app.directory_input, "/from/cli");
+}
+
+#[tokio::test]
+async fn test_last_run_is_remembered_and_restored() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = LastRunStore::new(dir.path().join("state").join("last_run.json"));
+    assert_eq!(store.load(), LastRun::default());
+
+    // Nothing is remembered until a directory was actually used
+    let mut app = App::with_directory(dir.path().to_string_lossy().to_string());
+    app.file_type = FileType::Movie;
+    app.use_imdb = false;
+    assert_eq!(app.last_run(), None);
+    app.create_rename_engine().await.unwrap();
+    store.save(&app.last_run().unwrap()).unwrap();
+
+    let mut app = App::new();
+    app.apply_last_run(&store.load());
+    assert_eq!(app.directory_input, dir.path().to_string_lossy());
+    assert_eq!(app.file_type, FileType::Movie);
+    assert!(!app.use_imdb);
+
+    // A directory given on the command line wins
+    let mut app = App::with_directory("/from/cli".to_string());
+    app.apply_last_run(&store.load());
+    assert_eq!(app.directory_input, "/from/cli");
+}
+
+#[test]
+fn test_last_run_ignores_a_directory_that_is_gone() {
+    let last_run = LastRun {
+        directory: Some("/no/such/jellyfin/directory".to_string()),
+        file_type: Some(FileTypeSetting::Tv),
+        provider: Some(ProviderSetting::Tmdb),
+    };
+
+    let mut app = App::new();
+    app.apply_last_run(&last_run);
+    assert!(app.directory_input.is_empty());
+    assert_eq!(app.file_type, FileType::TvShow);
+    assert_eq!(app.title_source, TitleSource::Tmdb);
+
+    let dir = tempfile::tempdir().unwrap();
+    let store = LastRunStore::new(dir.path().join("last_run.json"));
+    std::fs::write(store.path(), "not json").unwrap();
+    assert_eq!(store.load(), LastRun::default());
+}
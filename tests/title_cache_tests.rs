@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use jellyfin_rename::rename_engine::{ConfigBuilder, EpisodeTitleProvider, FileType, RenameEngine};
+use jellyfin_rename::title_cache::TitleCache;
+
+fn titles() -> Vec<String> {
+    vec!["Pilot".to_string(), "Cat's in the Bag...".to_string()]
+}
+
+/// Answers every season with the same titles, without touching the network.
+struct StubProvider {
+    titles: Vec<String>,
+}
+
+impl EpisodeTitleProvider for StubProvider {
+    async fn titles(&self, _season: u32) -> Result<Vec<String>> {
+        Ok(self.titles.clone())
+    }
+}
+
+#[test]
+fn test_cache_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = TitleCache::new(dir.path().join("cache.json"), Duration::from_secs(60));
+
+    assert!(cache.load("imdb", "tt0903747", 1).is_none());
+    cache.store("imdb", "tt0903747", 1, &titles()).unwrap();
+
+    assert_eq!(cache.load("imdb", "tt0903747", 1), Some(titles()));
+    assert!(cache.load("imdb", "tt0903747", 2).is_none());
+    assert!(cache.load("tmdb", "tt0903747", 1).is_none());
+}
+
+#[test]
+fn test_cache_entries_expire() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cache.json");
+    std::fs::write(&path, r#"{"imdb:tt0903747:1": {"fetched_at": 0, "titles": ["Pilot"]}}"#).unwrap();
+
+    let cache = TitleCache::new(&path, Duration::from_secs(60));
+    assert!(cache.load("imdb", "tt0903747", 1).is_none());
+}
+
+#[test]
+fn test_corrupt_cache_is_a_miss() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cache.json");
+    std::fs::write(&path, "{ not json").unwrap();
+
+    let cache = TitleCache::new(&path, Duration::from_secs(60));
+    assert!(cache.load("imdb", "tt0903747", 1).is_none());
+
+    cache.store("imdb", "tt0903747", 1, &titles()).unwrap();
+    assert_eq!(cache.load("imdb", "tt0903747", 1), Some(titles()));
+}
+
+fn imdb_engine(cache_path: &std::path::Path, no_cache: bool) -> RenameEngine {
+    let config = ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season("S01".to_string())
+        .imdb(Some("tt0000000".to_string()))
+        .cache_path(cache_path)
+        .no_cache(no_cache)
+        .build()
+        .unwrap();
+    RenameEngine::new(config).unwrap()
+}
+
+#[tokio::test]
+async fn test_fetch_reads_cache_before_network() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cache.json");
+    TitleCache::new(&path, Duration::from_secs(60)).store("imdb", "tt0000000", 1, &titles()).unwrap();
+
+    let mut engine = imdb_engine(&path, false);
+    let error = engine.fetch_imdb_titles().await.unwrap();
+
+    assert!(error.is_none(), "unexpected error: {:?}", error);
+    assert_eq!(engine.get_imdb_titles(), &titles());
+}
+
+#[tokio::test]
+async fn test_no_cache_skips_cached_titles() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cache.json");
+    TitleCache::new(&path, Duration::from_secs(60)).store("imdb", "tt0000000", 1, &titles()).unwrap();
+
+    let fresh = vec!["Pilot (fresh)".to_string()];
+    let provider = StubProvider { titles: fresh.clone() };
+
+    let mut engine = imdb_engine(&path, true);
+    assert!(engine.fetch_titles_from(&provider).await.unwrap().is_none());
+    assert_eq!(engine.get_imdb_titles(), &fresh);
+    // Nor is what was fetched written back
+    assert_eq!(TitleCache::new(&path, Duration::from_secs(60)).load("imdb", "tt0000000", 1), Some(titles()));
+
+    let mut engine = imdb_engine(&path, false);
+    assert!(engine.fetch_titles_from(&provider).await.unwrap().is_none());
+    assert_eq!(engine.get_imdb_titles(), &titles());
+}
+
+#[tokio::test]
+async fn test_reconfiguring_keeps_titles_until_the_season_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cache.json");
+    TitleCache::new(&path, Duration::from_secs(60)).store("imdb", "tt0000000", 1, &titles()).unwrap();
+    let config = |season: &str| ConfigBuilder::new()
+        .directory("/test/shows")
+        .file_type(FileType::TvShow)
+        .season(season.to_string())
+        .imdb(Some("tt0000000".to_string()))
+        .cache_path(&path)
+        .build()
+        .unwrap();
+
+    let mut engine = imdb_engine(&path, false);
+    assert!(engine.needs_title_fetch());
+    assert!(engine.fetch_titles_if_needed().await.unwrap().is_none());
+    assert!(!engine.needs_title_fetch());
+
+    // The same season written differently, and an unrelated change, keep what was fetched
+    engine.reconfigure(config("1")).unwrap();
+    let mut spaced = config("S01");
+    spaced.separator = ' ';
+    engine.reconfigure(spaced).unwrap();
+    assert!(!engine.needs_title_fetch());
+    assert_eq!(engine.get_imdb_titles(), &titles());
+
+    engine.reconfigure(config("S02")).unwrap();
+    assert!(engine.needs_title_fetch());
+    assert!(engine.get_imdb_titles().is_empty());
+}
@@ -0,0 +1,1366 @@
+// Integration tests for the TUI application state
+
+use anyhow::Result;
+use jellyfin_rename::error::RenameError;
+use jellyfin_rename::rename_engine::{EpisodeTitleProvider, FileType, MatchKind, TitleSource};
+use crossterm::event::KeyCode;
+use ratatui::widgets::ScrollbarState;
+use jellyfin_rename::tui::app::App;
+use jellyfin_rename::tui::keymap::{confirm_action, key_label, main_action, Action, ACTION_KEYS, CONFIRM_KEYS, HELP_SECTIONS, NAVIGATION_KEYS};
+use jellyfin_rename::report::ProcessingStatus;
+use jellyfin_rename::tui::models::{ConfigInputMode, SortMode};
+use jellyfin_rename::tui::rendering::{help_lines, ui};
+use jellyfin_rename::tui::undo_log::{next_batch_key, UndoLog};
+use jellyfin_rename::paths::expand_path_with;
+
+struct StubProvider {
+    titles: std::result::Result<Vec<String>, String>,
+}
+
+impl EpisodeTitleProvider for StubProvider {
+    async fn titles(&self, _season: u32) -> Result<Vec<String>> {
+        self.titles.clone().map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+#[tokio::test]
+async fn test_provider_connection_reports_success() {
+    let mut app = App::new();
+    app.season_input = "S02".to_string();
+
+    let provider = StubProvider {
+        titles: Ok(vec!["Pilot".to_string(), "Second".to_string(), "Third".to_string()]),
+    };
+    app.test_provider_connection(&provider).await;
+
+    let status = app.status_message.expect("status message should be set");
+    assert!(status.contains("Connection OK"), "unexpected status: {}", status);
+    assert!(status.contains("3 episodes"), "unexpected status: {}", status);
+    assert!(status.contains("season 2"), "unexpected status: {}", status);
+    assert!(app.show_config);
+}
+
+#[tokio::test]
+async fn test_provider_connection_reports_failure() {
+    let mut app = App::new();
+
+    let provider = StubProvider {
+        titles: Err("Incorrect IMDb ID.".to_string()),
+    };
+    app.test_provider_connection(&provider).await;
+
+    let status = app.status_message.expect("status message should be set");
+    assert!(status.contains("Connection failed"), "unexpected status: {}", status);
+    assert!(status.contains("Incorrect IMDb ID."), "unexpected status: {}", status);
+}
+
+#[tokio::test]
+async fn test_provider_connection_reports_empty_season() {
+    let mut app = App::new();
+
+    let provider = StubProvider { titles: Ok(Vec::new()) };
+    app.test_provider_connection(&provider).await;
+
+    let status = app.status_message.expect("status message should be set");
+    assert!(status.contains("no episodes found"), "unexpected status: {}", status);
+}
+
+async fn scanned_tv_app(dir: &std::path::Path, filenames: &[&str]) -> App {
+    for filename in filenames {
+        std::fs::write(dir.join(filename), b"video").unwrap();
+    }
+
+    let mut app = App::with_directory(dir.to_string_lossy().to_string());
+    app.undo_log = UndoLog::new(dir.join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.create_rename_engine().await.unwrap();
+    app.scan_directory().await.unwrap();
+    app
+}
+
+#[tokio::test]
+async fn test_process_only_checked_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &[
+        "Show.S01E01.Pilot.mkv",
+        "Show.S01E02.Second.mkv",
+        "Show.S01E03.Third.mkv",
+    ]).await;
+
+    let checked: Vec<String> = [0, 2].iter().map(|&i| {
+        app.list_state.select(Some(i));
+        app.toggle_checked();
+        app.files[i].new_name.clone()
+    }).collect();
+    let unchecked = app.files[1].clone();
+
+    assert_eq!(app.checked_count(), 2);
+    app.process_files().await.unwrap();
+
+    for new_name in &checked {
+        assert!(dir.path().join(new_name).exists(), "{} should have been renamed", new_name);
+    }
+    assert!(dir.path().join(&unchecked.original_name).exists());
+    assert!(!dir.path().join(&unchecked.new_name).exists());
+    assert_eq!(app.files[1].status, ProcessingStatus::Pending);
+    assert_eq!(app.stats.successful, 2);
+}
+
+#[tokio::test]
+async fn test_undo_limited_to_directory() {
+    let root = tempfile::tempdir().unwrap();
+    let dir_a = root.path().join("a");
+    let dir_b = root.path().join("b");
+    std::fs::create_dir_all(&dir_a).unwrap();
+    std::fs::create_dir_all(&dir_b).unwrap();
+
+    let mut selected = Vec::new();
+    for (dir, filename) in [(&dir_a, "Show.S01E01.Pilot.mkv"), (&dir_b, "Show.S01E02.Second.mkv")] {
+        let path = dir.join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        selected.push(path.to_string_lossy().to_string());
+    }
+
+    let mut app = App::with_selected_files(selected);
+    app.undo_log = UndoLog::new(root.path().join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.create_rename_engine().await.unwrap();
+    app.process_selected_files().await.unwrap();
+    app.process_files().await.unwrap();
+    assert_eq!(app.undo_operations.len(), 2);
+
+    let file_a = app.files.iter().find(|f| f.original_path.starts_with(dir_a.to_str().unwrap())).unwrap().clone();
+    let file_b = app.files.iter().find(|f| f.original_path.starts_with(dir_b.to_str().unwrap())).unwrap().clone();
+    assert!(dir_a.join(&file_a.new_name).exists());
+    assert!(dir_b.join(&file_b.new_name).exists());
+
+    app.undo_renames_in_directory(&dir_a).await.unwrap();
+
+    assert!(dir_a.join(&file_a.original_name).exists(), "directory a should be reverted");
+    assert!(!dir_a.join(&file_a.new_name).exists());
+    assert!(dir_b.join(&file_b.new_name).exists(), "directory b should keep its rename");
+    assert!(!dir_b.join(&file_b.original_name).exists());
+    assert_eq!(app.undo_operations.len(), 1);
+    assert!(app.finished);
+}
+
+#[tokio::test]
+async fn test_undo_directory_reverts_the_last_session() {
+    let root = tempfile::tempdir().unwrap();
+    let log_path = root.path().join("undo_log.json");
+    let dir_a = root.path().join("a");
+    let dir_b = root.path().join("b");
+    std::fs::create_dir_all(&dir_a).unwrap();
+    std::fs::create_dir_all(&dir_b).unwrap();
+
+    let mut selected = Vec::new();
+    for (dir, filename) in [(&dir_a, "Show.S01E01.Pilot.mkv"), (&dir_b, "Show.S01E02.Second.mkv")] {
+        let path = dir.join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        selected.push(path.to_string_lossy().to_string());
+    }
+
+    let mut app = App::with_selected_files(selected);
+    app.undo_log = UndoLog::new(&log_path);
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.create_rename_engine().await.unwrap();
+    app.process_selected_files().await.unwrap();
+    app.process_files().await.unwrap();
+    drop(app);
+
+    // A relaunch in directory a picks the batch up from the log and reverts only a's share
+    let mut relaunched = scanned_tv_app(&dir_a, &[]).await;
+    relaunched.undo_log = UndoLog::new(&log_path);
+    relaunched.load_last_session();
+    assert_eq!(relaunched.undo_operations.len(), 2);
+    relaunched.undo_selected_directory().await.unwrap();
+
+    assert!(dir_a.join("Show.S01E01.Pilot.mkv").exists(), "directory a should be reverted");
+    assert!(!dir_b.join("Show.S01E02.Second.mkv").exists(), "directory b should keep its rename");
+    let remaining = UndoLog::new(&log_path).last_batch().expect("directory b is still undoable");
+    assert_eq!(remaining.operations.len(), 1);
+    assert!(remaining.operations[0].original_path.ends_with("Show.S01E02.Second.mkv"));
+}
+
+#[tokio::test]
+async fn test_mixed_seasons_warn_and_allow_per_file_seasons() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut selected = Vec::new();
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S02E01.Return.mkv"] {
+        let path = dir.path().join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        selected.push(path.to_string_lossy().to_string());
+    }
+
+    let mut app = App::with_selected_files(selected);
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.create_rename_engine().await.unwrap();
+
+    assert_eq!(app.detected_seasons(), vec![1, 2]);
+    assert!(app.has_mixed_seasons());
+
+    app.refresh_selected_files().await.unwrap();
+    let status = app.status_message.clone().expect("mixed seasons should warn");
+    assert!(status.contains("seasons 1, 2"), "unexpected status: {}", status);
+    assert!(app.files.iter().all(|f| f.new_name.contains("(S01E01)")));
+
+    app.toggle_per_file_seasons();
+    app.refresh_selected_files().await.unwrap();
+    let mut names: Vec<&str> = app.files.iter().map(|f| f.new_name.as_str()).collect();
+    names.sort();
+    assert!(names[0].contains("(S01E01)"), "unexpected names: {:?}", names);
+    assert!(names[1].contains("(S02E01)"), "unexpected names: {:?}", names);
+}
+
+#[tokio::test]
+async fn test_dry_run_leaves_files_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let filenames = ["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"];
+    for filename in filenames {
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+    }
+
+    let mut app = App::with_directory(dir.path().to_string_lossy().to_string());
+    app.undo_log = UndoLog::new(dir.path().join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.dry_run = true;
+    app.create_rename_engine().await.unwrap();
+    app.scan_directory().await.unwrap();
+    app.process_files().await.unwrap();
+
+    for filename in filenames {
+        assert!(dir.path().join(filename).exists(), "{} should not have been renamed", filename);
+    }
+    assert_eq!(app.stats.successful, 2);
+    assert!(app.undo_operations.is_empty());
+}
+
+#[tokio::test]
+async fn test_undo_moves_subtitle_companions_back() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.srt"), b"subs").unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.en.srt"), b"subs").unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+
+    app.process_files().await.unwrap();
+    assert!(dir.path().join("Pilot_(S01E01).en.srt").exists());
+
+    app.undo_renames().await.unwrap();
+
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S01E01.Pilot.srt", "Show.S01E01.Pilot.en.srt"] {
+        assert!(dir.path().join(filename).exists(), "{} should be restored", filename);
+    }
+    assert!(!dir.path().join("Pilot_(S01E01).srt").exists());
+}
+
+#[test]
+fn test_choosing_tmdb_asks_for_an_api_key() {
+    let mut app = App::new();
+    app.file_type = FileType::TvShow;
+    app.config_input_mode = ConfigInputMode::ImdbChoice;
+
+    app.handle_config_input('t');
+    assert!(app.use_imdb);
+    assert_eq!(app.title_source, TitleSource::Tmdb);
+    assert_eq!(app.config_input_mode, ConfigInputMode::ImdbId);
+
+    app.advance_config_step();
+    assert_eq!(app.config_input_mode, ConfigInputMode::TmdbApiKey);
+    for c in "secret".chars() {
+        app.handle_config_input(c);
+    }
+    assert_eq!(app.tmdb_api_key_input, "secret");
+
+    app.advance_config_step();
+    assert_eq!(app.config_input_mode, ConfigInputMode::Confirm);
+}
+
+#[test]
+fn test_choosing_omdb_asks_for_its_own_api_key() {
+    let mut app = App::new();
+    app.file_type = FileType::TvShow;
+    app.config_input_mode = ConfigInputMode::ImdbChoice;
+
+    app.handle_config_input('o');
+    assert_eq!(app.title_source, TitleSource::Omdb);
+
+    app.advance_config_step();
+    assert_eq!(app.config_input_mode, ConfigInputMode::OmdbApiKey);
+    for c in "key123".chars() {
+        app.handle_config_input(c);
+    }
+    assert_eq!(app.omdb_api_key_input, "key123");
+    assert!(app.tmdb_api_key_input.is_empty());
+
+    app.go_back_config_step();
+    assert_eq!(app.config_input_mode, ConfigInputMode::ImdbId);
+}
+
+#[tokio::test]
+async fn test_processing_keeps_season_number() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut selected = Vec::new();
+    for filename in ["Show.S03E01.Opener.mkv", "Show.S03E02.Follow.mkv"] {
+        let path = dir.path().join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        selected.push(path.to_string_lossy().to_string());
+    }
+
+    let mut app = App::with_selected_files(selected);
+    app.undo_log = UndoLog::new(dir.path().join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S03".to_string();
+    app.create_rename_engine().await.unwrap();
+    app.process_selected_files().await.unwrap();
+    app.process_files().await.unwrap();
+
+    assert_eq!(app.stats.successful, 2);
+    assert!(app.files.iter().all(|f| f.season_number == 3), "seasons: {:?}",
+        app.files.iter().map(|f| f.season_number).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_redo_reapplies_undone_renames() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"]).await;
+    let new_names: Vec<String> = app.files.iter().map(|f| f.new_name.clone()).collect();
+
+    app.process_files().await.unwrap();
+    app.undo_renames().await.unwrap();
+    assert_eq!(app.redo_operations.len(), 2);
+    assert!(!app.finished);
+
+    app.redo_renames();
+
+    for new_name in &new_names {
+        assert!(dir.path().join(new_name).exists(), "{} should be renamed again", new_name);
+    }
+    assert!(app.redo_operations.is_empty());
+    assert_eq!(app.undo_operations.len(), 2);
+    assert_eq!(app.stats.successful, 2);
+    assert!(app.finished);
+    assert!(app.files.iter().all(|f| f.status == ProcessingStatus::Success));
+}
+
+#[tokio::test]
+async fn test_processing_discards_redo_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+
+    app.process_files().await.unwrap();
+    app.undo_renames().await.unwrap();
+    assert!(!app.redo_operations.is_empty());
+
+    app.process_files().await.unwrap();
+    assert!(app.redo_operations.is_empty());
+}
+
+#[tokio::test]
+async fn test_undo_log_survives_relaunch() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("undo_log.json");
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"]).await;
+    app.process_files().await.unwrap();
+
+    let batch = UndoLog::new(&log_path).last_batch().expect("batch should be logged");
+    assert_eq!(batch.operations.len(), 2);
+    assert_eq!(batch.created_at, app.undo_batch_created_at);
+
+    // A fresh app sees the renamed files and can still revert them
+    let mut relaunched = scanned_tv_app(dir.path(), &[]).await;
+    relaunched.load_last_session();
+    assert_eq!(relaunched.undo_operations.len(), 2);
+    relaunched.undo_renames().await.unwrap();
+
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"] {
+        assert!(dir.path().join(filename).exists(), "{} should be restored", filename);
+        assert!(relaunched.files.iter().any(|f| f.original_name == filename));
+    }
+    assert!(!log_path.exists(), "log should be removed once everything is undone");
+}
+
+#[test]
+fn test_undo_log_keeps_batches_in_order() {
+    use jellyfin_rename::tui::models::UndoOperation;
+    use jellyfin_rename::tui::undo_log::UndoBatch;
+
+    let dir = tempfile::tempdir().unwrap();
+    let log = UndoLog::new(dir.path().join("undo_log.json"));
+    let batch = |created_at: u64, name: &str| UndoBatch {
+        created_at,
+        operations: vec![UndoOperation {
+            original_path: format!("/shows/{}", name),
+            renamed_path: format!("/shows/renamed_{}", name),
+            original_name: name.to_string(),
+            new_name: format!("renamed_{}", name),
+            created_dirs: Vec::new(),
+            created_files: Vec::new(),
+            trashed: None,
+        }],
+    };
+
+    log.save_batch(&batch(20, "b.mkv")).unwrap();
+    log.save_batch(&batch(10, "a.mkv")).unwrap();
+    assert_eq!(log.batches().len(), 2);
+    assert_eq!(log.last_batch().unwrap().created_at, 20);
+
+    log.save_batch(&UndoBatch { created_at: 20, operations: Vec::new() }).unwrap();
+    assert_eq!(log.last_batch().unwrap().created_at, 10);
+}
+
+#[tokio::test]
+async fn test_undo_log_keeps_back_to_back_runs_apart() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("undo_log.json");
+    for filename in ["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"] {
+        let mut app = scanned_tv_app(dir.path(), &[]).await;
+        std::fs::write(dir.path().join(filename), b"video").unwrap();
+        app.scan_directory().await.unwrap();
+        app.undo_log = UndoLog::new(&log_path);
+        app.process_files().await.unwrap();
+    }
+
+    // Both runs started within the same second
+    let batches = UndoLog::new(&log_path).batches();
+    assert_eq!(batches.len(), 2, "the second run must not replace the first");
+    assert!(batches[0].created_at < batches[1].created_at);
+
+    let key = next_batch_key(0);
+    assert!(next_batch_key(key) > key, "a key is never reused within one millisecond");
+}
+
+#[tokio::test]
+async fn test_last_session_is_only_loaded_for_its_directory() {
+    let root = tempfile::tempdir().unwrap();
+    let log_path = root.path().join("undo_log.json");
+    let shows = root.path().join("shows");
+    let other = root.path().join("other");
+    std::fs::create_dir_all(&shows).unwrap();
+    std::fs::create_dir_all(&other).unwrap();
+
+    let mut app = scanned_tv_app(&shows, &["Show.S01E01.Pilot.mkv"]).await;
+    app.undo_log = UndoLog::new(&log_path);
+    app.process_files().await.unwrap();
+
+    let mut elsewhere = scanned_tv_app(&other, &["Other.S01E01.Pilot.mkv"]).await;
+    elsewhere.undo_log = UndoLog::new(&log_path);
+    elsewhere.load_last_session();
+    assert!(elsewhere.undo_operations.is_empty(), "another folder's renames are not offered");
+
+    let mut back = scanned_tv_app(&shows, &[]).await;
+    back.undo_log = UndoLog::new(&log_path);
+    back.load_last_session();
+    assert_eq!(back.undo_operations.len(), 1);
+}
+
+#[tokio::test]
+async fn test_filter_narrows_list_and_navigation() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &[
+        "Show.S01E01.Pilot.mkv",
+        "Show.S01E02.Second.mkv",
+        "Show.S01E03.Third.mkv",
+        "Show.S01E04.Pilot.Redux.mkv",
+    ]).await;
+
+    app.start_filter();
+    for c in "PILOT".chars() {
+        app.push_filter_char(c);
+    }
+    app.finish_filter();
+
+    let visible = app.visible_indices();
+    assert_eq!(visible.len(), 2);
+    assert!(visible.iter().all(|&i| app.files[i].original_name.contains("Pilot")));
+
+    app.list_state.select(Some(visible[0]));
+    app.next();
+    assert_eq!(app.list_state.selected(), Some(visible[1]));
+    app.next();
+    assert_eq!(app.list_state.selected(), Some(visible[0]), "navigation should wrap within the filtered view");
+
+    app.clear_filter();
+    assert_eq!(app.visible_indices().len(), 4);
+    assert!(!app.filter_editing);
+}
+
+#[tokio::test]
+async fn test_filter_moves_selection_onto_a_match() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"]).await;
+    let second = app.files.iter().position(|f| f.original_name.contains("Second")).unwrap();
+    let pilot = 1 - second;
+    app.list_state.select(Some(pilot));
+
+    for c in "second".chars() {
+        app.push_filter_char(c);
+    }
+
+    assert_eq!(app.list_state.selected(), Some(second));
+}
+
+#[tokio::test]
+async fn test_user_skipped_file_is_left_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"]).await;
+    let skipped = app.files[1].clone();
+
+    app.list_state.select(Some(1));
+    app.toggle_skipped();
+    assert!(app.files[1].is_user_skipped());
+    assert_eq!(app.skipped_count(), 1);
+
+    app.process_files().await.unwrap();
+
+    assert!(dir.path().join(&skipped.original_name).exists());
+    assert!(!dir.path().join(&skipped.new_name).exists());
+    assert_eq!(app.stats.successful, 1);
+    assert_eq!(app.stats.skipped, 1);
+    assert_eq!(app.undo_operations.len(), 1);
+}
+
+#[tokio::test]
+async fn test_skip_toggle_restores_pending() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+
+    app.toggle_skipped();
+    app.toggle_skipped();
+
+    assert_eq!(app.files[0].status, ProcessingStatus::Pending);
+    assert!(!app.files[0].is_user_skipped());
+}
+
+#[tokio::test]
+async fn test_bulk_skip_respects_the_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &[
+        "Show.S01E01.Pilot.mkv",
+        "Show.S01E02.Second.mkv",
+        "Show.S01E03.Pilot.Redux.mkv",
+    ]).await;
+
+    for c in "pilot".chars() {
+        app.push_filter_char(c);
+    }
+    app.set_visible_skipped(true);
+    assert_eq!(app.skipped_count(), 2);
+    assert!(app.files.iter().filter(|f| f.is_user_skipped()).all(|f| f.original_name.contains("Pilot")));
+
+    app.clear_filter();
+    app.set_visible_skipped(true);
+    assert_eq!(app.skipped_count(), 3);
+    app.set_visible_skipped(false);
+    assert_eq!(app.skipped_count(), 0);
+    assert!(app.files.iter().all(|f| f.status == ProcessingStatus::Pending));
+}
+
+#[test]
+fn test_name_editor_cursor_editing() {
+    use jellyfin_rename::tui::models::NameEditor;
+
+    let mut editor = NameEditor::new(0, "Pilt.mkv");
+    editor.move_home();
+    for _ in 0..3 {
+        editor.move_right();
+    }
+    editor.insert('o');
+    assert_eq!(editor.buffer, "Pilot.mkv");
+
+    editor.move_end();
+    editor.backspace();
+    editor.insert('4');
+    editor.move_home();
+    editor.delete();
+    assert_eq!(editor.buffer, "ilot.mk4");
+    assert_eq!(editor.cursor, 0);
+}
+
+#[tokio::test]
+async fn test_edited_name_survives_refresh() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("Show.S01E01.Pilot.mkv");
+    std::fs::write(&path, b"video").unwrap();
+
+    let mut app = App::with_selected_files(vec![path.to_string_lossy().to_string()]);
+    app.undo_log = UndoLog::new(dir.path().join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.create_rename_engine().await.unwrap();
+    app.process_selected_files().await.unwrap();
+
+    app.start_name_edit();
+    let editor = app.name_edit.as_mut().unwrap();
+    editor.move_home();
+    editor.insert('X');
+    app.confirm_name_edit();
+    assert_eq!(app.files[0].new_name, "XPilot_(S01E01).mkv");
+    assert!(app.files[0].manually_edited);
+
+    app.season_input = "S02".to_string();
+    app.refresh_selected_files().await.unwrap();
+    assert_eq!(app.files[0].new_name, "XPilot_(S01E01).mkv");
+
+    app.process_files().await.unwrap();
+    assert!(dir.path().join("XPilot_(S01E01).mkv").exists());
+}
+
+#[test]
+fn test_rename_dialog_renames_a_lone_file_and_logs_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("Show.S01E01.mkv");
+    std::fs::write(&path, b"video").unwrap();
+    let log_path = dir.path().join("undo_log.json");
+
+    let mut app = App::with_selected_files(vec![path.to_string_lossy().to_string()]);
+    app.undo_log = UndoLog::new(&log_path);
+    app.show_rename_dialog(&path.to_string_lossy());
+    assert_eq!(app.rename_dialog.as_ref().unwrap().editor.buffer, "Show.S01E01");
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| ui(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Rename Show.S01E01.mkv"), "{screen}");
+
+    let editor = &mut app.rename_dialog.as_mut().unwrap().editor;
+    editor.buffer = "Show S01E01 Pilot".to_string();
+    assert!(app.confirm_rename_dialog());
+
+    assert!(app.rename_dialog.is_none());
+    assert!(dir.path().join("Show S01E01 Pilot.mkv").exists());
+    assert!(!path.exists());
+    let batch = UndoLog::new(&log_path).last_batch().expect("the rename is undoable");
+    assert_eq!(batch.operations[0].new_name, "Show S01E01 Pilot.mkv");
+}
+
+#[test]
+fn test_rename_dialog_stays_open_on_a_blank_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("Show.S01E01.mkv");
+    std::fs::write(&path, b"video").unwrap();
+
+    let mut app = App::with_selected_files(vec![path.to_string_lossy().to_string()]);
+    app.undo_log = UndoLog::new(dir.path().join("undo_log.json"));
+    app.show_rename_dialog(&path.to_string_lossy());
+    app.rename_dialog.as_mut().unwrap().editor.buffer = "  ".to_string();
+
+    assert!(!app.confirm_rename_dialog());
+    assert!(app.rename_dialog.is_some());
+    assert!(app.error_popup.is_some());
+    assert!(path.exists());
+
+    // Esc leaves the file for the guided setup
+    app.cancel_rename_dialog();
+    assert!(app.rename_dialog.is_none());
+    assert_eq!(app.files.len(), 1);
+}
+
+#[tokio::test]
+async fn test_edit_rejects_path_separators() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+    let proposed = app.files[0].new_name.clone();
+
+    app.start_name_edit();
+    app.name_edit.as_mut().unwrap().insert('/');
+    app.confirm_name_edit();
+
+    assert!(app.name_edit.is_some(), "editor should stay open on an invalid name");
+    assert_eq!(app.files[0].new_name, proposed);
+}
+
+#[tokio::test]
+async fn test_sort_modes_keep_selection() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &[
+        "C.Show.S01E01.mkv",
+        "A.Show.S01E03.mkv",
+        "B.Show.S01E02.mkv",
+    ]).await;
+    assert_eq!(app.sort_mode, SortMode::Episode);
+    let episodes: Vec<u32> = app.files.iter().map(|f| f.episode_number).collect();
+    assert_eq!(episodes, vec![1, 2, 3]);
+
+    app.list_state.select(Some(1));
+    let selected = app.files[1].original_path.clone();
+
+    app.cycle_sort_mode();
+    assert_eq!(app.sort_mode, SortMode::Status);
+    app.cycle_sort_mode();
+    assert_eq!(app.sort_mode, SortMode::OriginalName);
+    let names: Vec<&str> = app.files.iter().map(|f| f.original_name.as_str()).collect();
+    assert_eq!(names, vec!["A.Show.S01E03.mkv", "B.Show.S01E02.mkv", "C.Show.S01E01.mkv"]);
+    assert_eq!(app.files[app.list_state.selected().unwrap()].original_path, selected);
+}
+
+#[tokio::test]
+async fn test_status_sort_puts_errors_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.mkv", "Show.S01E02.mkv"]).await;
+    app.files[1].status = ProcessingStatus::Error;
+
+    app.cycle_sort_mode();
+
+    assert_eq!(app.sort_mode, SortMode::Status);
+    assert_eq!(app.files[0].status, ProcessingStatus::Error);
+}
+
+async fn selected_tv_app(dir: &std::path::Path, filenames: &[&str]) -> App {
+    let selected = filenames.iter().map(|filename| {
+        let path = dir.join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        path.to_string_lossy().to_string()
+    }).collect();
+
+    let mut app = App::with_selected_files(selected);
+    app.undo_log = UndoLog::new(dir.join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.create_rename_engine().await.unwrap();
+    app.process_selected_files().await.unwrap();
+    app
+}
+
+#[tokio::test]
+async fn test_colliding_targets_are_not_renamed() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = selected_tv_app(dir.path(), &[
+        "Show.S01E01.Pilot.720p.mkv",
+        "Show.S01E01.Pilot.1080p.mkv",
+        "Show.S01E02.Second.mkv",
+    ]).await;
+
+    assert_eq!(app.collision_count(), 2);
+    assert!(app.files.iter().filter(|f| f.has_collision()).all(|f| f.error_message.as_ref().unwrap().contains("also the target of")));
+
+    app.process_files().await.unwrap();
+
+    assert!(dir.path().join("Show.S01E01.Pilot.720p.mkv").exists());
+    assert!(dir.path().join("Show.S01E01.Pilot.1080p.mkv").exists());
+    assert!(!dir.path().join("Pilot_(S01E01).mkv").exists());
+    assert_eq!(app.stats.successful, 1);
+    assert_eq!(app.stats.failed, 2);
+}
+
+#[tokio::test]
+async fn test_collision_with_existing_file_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Pilot_(S01E01).mkv"), b"already here").unwrap();
+    let mut app = selected_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+
+    assert_eq!(app.collision_count(), 1);
+    assert!(app.files[0].error_message.as_ref().unwrap().contains("already exists on disk"));
+
+    app.process_files().await.unwrap();
+    assert_eq!(std::fs::read(dir.path().join("Pilot_(S01E01).mkv")).unwrap(), b"already here");
+    assert!(app.undo_operations.is_empty());
+
+    // Resolving the clash clears the mark on the next check
+    std::fs::remove_file(dir.path().join("Pilot_(S01E01).mkv")).unwrap();
+    assert_eq!(app.detect_collisions(), 0);
+    assert_eq!(app.files[0].status, ProcessingStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_missing_directory_is_a_typed_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = App::with_directory(dir.path().join("missing").to_string_lossy().to_string());
+    app.file_type = FileType::Movie;
+    app.create_rename_engine().await.unwrap();
+
+    let error = app.scan_directory().await.unwrap_err();
+    assert!(matches!(error, RenameError::InvalidConfig(_)), "unexpected error: {:?}", error);
+    assert!(error.to_string().contains("Directory does not exist"));
+
+    app.show_error(&error);
+    assert!(app.error_popup.as_ref().unwrap().contains("Directory does not exist"));
+    assert!(app.status_message.as_ref().unwrap().starts_with("Error:"));
+    app.dismiss_error();
+    assert!(app.error_popup.is_none());
+}
+
+#[tokio::test]
+async fn test_title_fetch_failures_are_reported_as_scrape_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let selected = ["Show.S01E01.mkv", "Show.S01E02.mkv"].iter().map(|filename| {
+        let path = dir.path().join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        path.to_string_lossy().to_string()
+    }).collect();
+    let mut app = App::with_selected_files(selected);
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.use_imdb = true;
+    app.imdb_id_input = "tt0903747".to_string();
+    app.title_source = TitleSource::Tmdb;
+
+    app.create_rename_engine().await.unwrap();
+
+    let expected = RenameError::Scrape("A TMDB API key is required for TMDB titles".to_string());
+    assert_eq!(app.status_message, Some(expected.to_string()));
+}
+
+#[tokio::test]
+async fn test_colliding_files_are_reported_in_the_status_bar() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = selected_tv_app(dir.path(), &["Show.S01E01.Pilot.720p.mkv", "Show.S01E01.Pilot.1080p.mkv"]).await;
+
+    app.process_files().await.unwrap();
+
+    assert_eq!(app.status_message, Some(RenameError::Collision(2).to_string()));
+}
+
+#[tokio::test]
+async fn test_quit_asks_for_confirmation_with_pending_renames() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = selected_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+
+    assert_eq!(app.pending_rename_count(), 1);
+    assert!(!app.request_quit());
+    assert!(app.confirm_quit);
+
+    app.confirm_quit = false;
+    app.process_files().await.unwrap();
+    assert!(app.request_quit(), "nothing left to lose once processing has finished");
+    assert!(!app.confirm_quit);
+}
+
+#[tokio::test]
+async fn test_copying_without_a_clipboard_reports_instead_of_panicking() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+    app.list_state.select(Some(0));
+
+    app.copy_selected_name(false);
+
+    // Whether or not this machine has a clipboard, the outcome is reported
+    let message = app.status_message.clone().unwrap();
+    assert!(
+        message == "Copied Pilot_(S01E01).mkv to the clipboard" || message.starts_with("Could not copy to the clipboard"),
+        "unexpected message: {}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_target_path_stays_in_the_source_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+
+    assert_eq!(app.files[0].target_path(), dir.path().join("Pilot_(S01E01).mkv").to_string_lossy());
+}
+
+#[test]
+fn test_eta_and_elapsed_formatting() {
+    use std::time::Duration;
+    use jellyfin_rename::tui::utils::{estimate_remaining, format_mm_ss};
+
+    assert_eq!(format_mm_ss(Duration::from_secs(0)), "00:00");
+    assert_eq!(format_mm_ss(Duration::from_secs(125)), "02:05");
+    assert_eq!(format_mm_ss(Duration::from_secs(3725)), "62:05");
+
+    assert_eq!(estimate_remaining(Duration::from_secs(10), 0, 5), None, "no estimate before the first file");
+    assert_eq!(estimate_remaining(Duration::from_secs(10), 2, 6), Some(Duration::from_secs(20)));
+    assert_eq!(estimate_remaining(Duration::from_secs(10), 6, 6), None);
+}
+
+#[test]
+fn test_file_type_is_detected_from_the_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Heat.1995.1080p.mkv"), b"video").unwrap();
+
+    let app = App::with_directory(dir.path().to_string_lossy().to_string());
+    assert_eq!(app.file_type, FileType::Movie);
+    assert_eq!(app.detected_file_type, Some(FileType::Movie));
+
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.mkv"), b"video").unwrap();
+    std::fs::write(dir.path().join("Show.S01E02.Second.mkv"), b"video").unwrap();
+    let mut app = App::with_directory(dir.path().to_string_lossy().to_string());
+    assert_eq!(app.file_type, FileType::TvShow);
+
+    // Enter keeps the guess and moves on
+    app.advance_config_step();
+    assert_eq!(app.file_type, FileType::TvShow);
+    assert_eq!(app.config_input_mode, ConfigInputMode::Directory);
+}
+
+#[test]
+fn test_show_name_step_accepts_shortcut_letters_and_suggests_the_series_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    let season_dir = dir.path().join("Breaking Bad").join("Season 01");
+    std::fs::create_dir_all(&season_dir).unwrap();
+
+    let mut app = App::new();
+    app.directory_input = season_dir.to_string_lossy().to_string();
+    app.config_input_mode = ConfigInputMode::ShowName;
+    assert_eq!(app.suggested_show_name().as_deref(), Some("Breaking Bad"));
+
+    assert!(app.is_text_input_mode());
+    for c in "The Rookie".chars() {
+        app.handle_config_input(c);
+    }
+    assert_eq!(app.show_name_input, "The Rookie");
+}
+
+#[tokio::test]
+async fn test_undo_removes_created_season_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.mkv"), b"video").unwrap();
+    let mut app = App::with_directory(dir.path().to_string_lossy().to_string());
+    app.undo_log = UndoLog::new(dir.path().join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.organize_into_folders = true;
+    app.create_rename_engine().await.unwrap();
+    app.scan_directory().await.unwrap();
+
+    let season_dir = dir.path().join("Season 01");
+    assert_eq!(app.target_path(&app.files[0]), season_dir.join("Pilot_(S01E01).mkv").to_string_lossy());
+
+    app.process_files().await.unwrap();
+    assert!(season_dir.join("Pilot_(S01E01).mkv").exists());
+
+    app.undo_renames().await.unwrap();
+    assert!(dir.path().join("Show.S01E01.Pilot.mkv").exists());
+    assert!(!season_dir.exists(), "the empty season folder should be removed");
+
+    app.redo_renames();
+    assert!(season_dir.join("Pilot_(S01E01).mkv").exists());
+}
+
+#[tokio::test]
+async fn test_undo_deletes_written_nfo_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Show.S01E01.Pilot.mkv"), b"video").unwrap();
+    let mut app = App::with_directory(dir.path().to_string_lossy().to_string());
+    app.undo_log = UndoLog::new(dir.path().join("undo_log.json"));
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.write_nfo = true;
+    app.create_rename_engine().await.unwrap();
+    app.scan_directory().await.unwrap();
+
+    app.process_files().await.unwrap();
+    let episode_nfo = dir.path().join("Pilot_(S01E01).nfo");
+    let contents = std::fs::read_to_string(&episode_nfo).unwrap();
+    assert!(contents.contains("<episode>1</episode>"), "{}", contents);
+    assert!(std::fs::read_to_string(dir.path().join("tvshow.nfo")).unwrap().contains("<title>Show</title>"));
+
+    app.undo_renames().await.unwrap();
+    assert!(!episode_nfo.exists());
+    assert!(!dir.path().join("tvshow.nfo").exists());
+    assert!(dir.path().join("Show.S01E01.Pilot.mkv").exists());
+}
+
+#[test]
+fn test_movie_years_are_prefilled_from_filenames() {
+    let dir = tempfile::tempdir().unwrap();
+    let selected: Vec<String> = ["The.Matrix.1999.1080p.mkv", "Heat.mkv", "Se7en.1995.mkv"]
+        .iter()
+        .map(|filename| {
+            let path = dir.path().join(filename);
+            std::fs::write(&path, b"video").unwrap();
+            path.to_string_lossy().to_string()
+        })
+        .collect();
+
+    let mut app = App::with_selected_files(selected);
+    app.file_type = FileType::Movie;
+    app.config_input_mode = ConfigInputMode::FileType;
+    app.movie_years[2] = "1996".to_string();
+    app.advance_config_step();
+
+    assert_eq!(app.config_input_mode, ConfigInputMode::MovieYears);
+    let years_by_name: Vec<(&str, &str)> = app.files.iter()
+        .zip(&app.movie_years)
+        .map(|(file, year)| (file.original_name.as_str(), year.as_str()))
+        .collect();
+    assert!(years_by_name.contains(&("The.Matrix.1999.1080p.mkv", "1999")), "{:?}", years_by_name);
+    assert!(years_by_name.contains(&("Heat.mkv", "")), "{:?}", years_by_name);
+    assert!(years_by_name.iter().any(|&(_, year)| year == "1996"), "a typed year is kept: {:?}", years_by_name);
+}
+
+#[tokio::test]
+async fn test_collisions_get_the_conflict_status() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = selected_tv_app(dir.path(), &[
+        "Show.S01E01.Pilot.720p.mkv",
+        "Show.S01E01.Pilot.1080p.mkv",
+        "Show.S01E02.Second.mkv",
+    ]).await;
+
+    let statuses: Vec<ProcessingStatus> = app.files.iter().map(|f| f.status.clone()).collect();
+    assert_eq!(statuses.iter().filter(|&s| *s == ProcessingStatus::Conflict).count(), 2, "{:?}", statuses);
+    assert!(!statuses.contains(&ProcessingStatus::Error));
+
+    // Renaming one of them away clears the conflict on both
+    let index = app.files.iter().position(|f| f.original_name == "Show.S01E01.Pilot.720p.mkv").unwrap();
+    app.files[index].new_name = "Pilot_720p_(S01E01).mkv".to_string();
+    app.detect_collisions();
+    assert!(app.files.iter().all(|f| f.status == ProcessingStatus::Pending));
+}
+
+#[tokio::test]
+async fn test_process_files_writes_a_markdown_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"]).await;
+    let report_path = dir.path().join("report.md");
+    app.write_report = true;
+    app.report_path = Some(report_path.clone());
+
+    let second = app.files.iter().position(|file| file.original_name == "Show.S01E02.Second.mkv").unwrap();
+    app.list_state.select(Some(second));
+    app.toggle_skipped();
+    app.process_files().await.unwrap();
+
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    assert!(report.contains("| Show.S01E01.Pilot.mkv | Pilot_(S01E01).mkv | Success |  |"), "{}", report);
+    assert!(report.contains("| Show.S01E02.Second.mkv | Second_(S01E02).mkv | Skipped |  |"), "{}", report);
+    assert!(report.contains("**Totals:** 2 files, 2 processed, 1 successful, 0 failed, 1 skipped"), "{}", report);
+}
+
+#[test]
+fn test_help_is_rendered_from_the_dispatched_keymap() {
+    // No binding is shadowed by an earlier one using the same key
+    for binding in NAVIGATION_KEYS.iter().chain(ACTION_KEYS) {
+        for key in binding.keys {
+            assert_eq!(main_action(*key), Some(binding.action), "{:?}", key);
+        }
+    }
+    for binding in CONFIRM_KEYS {
+        for key in binding.keys {
+            let KeyCode::Char(c) = key else { panic!("confirm keys are characters") };
+            assert_eq!(confirm_action(*c), Some(binding.action), "{:?}", key);
+        }
+    }
+
+    let help = help_lines();
+    for (_, bindings) in HELP_SECTIONS {
+        for binding in *bindings {
+            let label = key_label(binding);
+            assert!(help.iter().any(|line| line.contains(&label) && line.contains(binding.description)), "{} missing", label);
+        }
+    }
+    assert!(help.iter().any(|line| line.contains("Enter/Space") && line.contains("Start processing (asks to confirm)")));
+    assert!(help.iter().any(|line| line.trim_start().starts_with("x ") && line.contains("Check file")));
+
+    let mut app = App::new();
+    app.config_input_mode = ConfigInputMode::Confirm;
+    app.handle_config_input('R');
+    assert!(app.write_report);
+}
+
+#[tokio::test]
+async fn test_status_messages_do_not_replace_the_progress_label() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+    app.show_config = false;
+    app.set_status_message("Undoing renames...".to_string());
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| ui(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+
+    assert!(screen.contains("Undoing renames..."));
+    assert!(screen.contains("Ready to process 1 files"));
+}
+
+#[test]
+fn test_api_keys_are_masked_on_screen() {
+    let dir = tempfile::tempdir().unwrap();
+    let selected = ["Show.S01E01.mkv", "Show.S01E02.mkv"].iter().map(|filename| {
+        let path = dir.path().join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        path.to_string_lossy().to_string()
+    }).collect();
+    let mut app = App::with_selected_files(selected);
+    app.file_type = FileType::TvShow;
+    app.use_imdb = true;
+    app.title_source = TitleSource::Tmdb;
+    app.tmdb_api_key_input = "abc123secret".to_string();
+    app.config_input_mode = ConfigInputMode::TmdbApiKey;
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 80)).unwrap();
+    terminal.draw(|f| ui(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+
+    assert!(screen.contains("TMDB API key"));
+    assert!(screen.contains("************"));
+    assert!(!screen.contains("abc123secret"));
+}
+
+#[tokio::test]
+async fn test_starting_from_the_file_list_asks_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"]).await;
+    app.show_config = false;
+
+    app.request_process();
+    assert!(app.confirm_process);
+    assert!(!app.is_processing(), "nothing runs until the prompt is answered");
+    assert_eq!(app.queued_rename_count(), 2);
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| ui(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Rename 2 file(s) now?"));
+
+    app.process_files().await.unwrap();
+    app.confirm_process = false;
+    app.request_process();
+    assert!(!app.confirm_process, "a finished run is not offered again");
+}
+
+#[tokio::test]
+async fn test_processing_one_file_per_tick_shows_progress() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &[
+        "Show.S01E01.Pilot.mkv",
+        "Show.S01E02.Second.mkv",
+        "Show.S01E03.Third.mkv",
+    ]).await;
+    app.show_config = false;
+
+    app.start_processing();
+    app.process_next_file().await;
+
+    // The run is mid-way, so the screen can be redrawn and quitting asks first
+    assert!(app.is_processing());
+    assert_eq!(app.stats.processed, 1);
+    assert_eq!(app.current_processing, Some(1));
+    assert!(!app.finished);
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| ui(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Processing... 2/3"), "progress label missing");
+    assert!(screen.contains("elapsed"));
+    assert!(!app.request_quit());
+    app.confirm_quit = false;
+
+    while app.is_processing() {
+        app.process_next_file().await;
+    }
+    assert!(app.finished);
+    assert_eq!(app.stats.successful, 3);
+    assert_eq!(app.current_processing, None);
+}
+
+#[test]
+fn test_directory_step_refuses_paths_that_are_not_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("Show.S01E01.mkv");
+    std::fs::write(&file, b"video").unwrap();
+
+    let mut app = App::new();
+    app.config_input_mode = ConfigInputMode::Directory;
+    for (input, error) in [
+        (dir.path().join("missing").to_string_lossy().to_string(), "does not exist"),
+        (file.to_string_lossy().to_string(), "is not a directory"),
+    ] {
+        app.directory_input = input;
+        app.handle_config_input('\n');
+        assert_eq!(app.config_input_mode, ConfigInputMode::Directory);
+        assert!(app.directory_error.as_ref().unwrap().contains(error));
+    }
+
+    // Editing clears the complaint, and a real directory moves on
+    app.directory_input = format!("  {}  ", dir.path().display());
+    app.handle_config_input('\x08');
+    app.handle_config_input(' ');
+    assert!(app.directory_error.is_none());
+    app.handle_config_input('\n');
+    assert_eq!(app.config_input_mode, ConfigInputMode::Season);
+    assert_eq!(app.directory_input, dir.path().to_string_lossy());
+}
+
+#[test]
+fn test_typed_paths_expand_home_and_environment_variables() {
+    let home = Some("/home/viewer");
+    let lookup = |name: &str| match name {
+        "MEDIA" => Some("/srv/media".to_string()),
+        "USERPROFILE" => Some(r"C:\Users\viewer".to_string()),
+        _ => None,
+    };
+
+    assert_eq!(expand_path_with("~/Videos", home, lookup), "/home/viewer/Videos");
+    assert_eq!(expand_path_with("~", home, lookup), "/home/viewer");
+    assert_eq!(expand_path_with("~/Videos", None, lookup), "~/Videos");
+    assert_eq!(expand_path_with("$MEDIA/shows", home, lookup), "/srv/media/shows");
+    assert_eq!(expand_path_with("${MEDIA}_old/shows", home, lookup), "/srv/media_old/shows");
+    assert_eq!(expand_path_with(r"%USERPROFILE%\Videos", home, lookup), r"C:\Users\viewer\Videos");
+    // Unset variables, stray sigils and a ~ inside a name are left alone
+    assert_eq!(expand_path_with("$UNSET/shows", home, lookup), "$UNSET/shows");
+    assert_eq!(expand_path_with("/shows/100%/%UNSET%", home, lookup), "/shows/100%/%UNSET%");
+    assert_eq!(expand_path_with("/shows/~temp/$", home, lookup), "/shows/~temp/$");
+}
+
+#[tokio::test]
+async fn test_file_list_scrollbar_follows_the_selection_and_survives_tiny_terminals() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv", "Show.S01E03.Third.mkv"]).await;
+    app.show_config = false;
+    app.list_state.select(Some(0));
+
+    app.next();
+    assert_eq!(app.scroll_state, ScrollbarState::new(3).position(1));
+    app.previous();
+    app.previous();
+    assert_eq!(app.scroll_state, ScrollbarState::new(3).position(2));
+
+    for height in 0..12 {
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, height)).unwrap();
+        terminal.draw(|f| ui(f, &app)).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_scrollbar_counts_only_the_files_shown() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv", "Show.S01E03.Third.mkv"]).await;
+    assert_eq!(app.scroll_state, ScrollbarState::new(3).position(0));
+
+    app.next();
+    app.next();
+    for c in "third".chars() {
+        app.push_filter_char(c);
+    }
+    assert_eq!(app.scroll_state, ScrollbarState::new(1).position(0));
+
+    app.clear_filter();
+    assert_eq!(app.scroll_state, ScrollbarState::new(3).position(2));
+}
+
+#[test]
+fn test_setup_screens_draw_at_any_terminal_size() {
+    let mut app = App::new();
+    for (mode, show_help) in [
+        (ConfigInputMode::FileType, false),
+        (ConfigInputMode::Directory, true),
+        (ConfigInputMode::Season, false),
+        (ConfigInputMode::Confirm, false),
+    ] {
+        app.config_input_mode = mode;
+        app.show_help = show_help;
+        for (width, height) in [(0, 0), (1, 1), (10, 3), (20, 8), (40, 15), (200, 60)] {
+            let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+            terminal.draw(|f| ui(f, &app)).unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_season_override_renames_every_file_after_scanning() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv", "Show.S01E02.Second.mkv"]).await;
+    assert_eq!(main_action(KeyCode::Char('S')), Some(Action::OverrideSeason));
+
+    app.start_season_edit();
+    assert_eq!(app.season_edit.as_deref(), Some("01"));
+    app.season_edit = Some("x".to_string());
+    app.apply_season_edit().await.unwrap();
+    assert!(app.season_edit.is_some(), "an invalid season keeps the prompt open");
+
+    app.season_edit = Some("3".to_string());
+    app.apply_season_edit().await.unwrap();
+    assert!(app.season_edit.is_none());
+    assert_eq!(app.season_input, "S03");
+    assert!(app.files.iter().all(|file| file.season_number == 3 && file.new_name.contains("S03E0")), "{:?}",
+        app.files.iter().map(|file| &file.new_name).collect::<Vec<_>>());
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| ui(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Jellyfin Rename Tool - S03"));
+}
+
+#[tokio::test]
+async fn test_provider_title_survives_a_season_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.mkv", "Show.S01E02.Parsed.Title.mkv"]).await;
+    app.rename_engine.as_mut().unwrap().set_imdb_titles(vec!["Pilot".to_string()]);
+    app.scan_directory().await.unwrap();
+    assert_eq!(app.files[0].new_name, "Pilot_(S01E01).mkv");
+    assert_eq!(app.files[0].provider_title.as_deref(), Some("Pilot"));
+
+    // The rebuilt engine has no titles, so only the season part of the name changes
+    app.season_input = "S02".to_string();
+    app.refresh_selected_files().await.unwrap();
+    let names: Vec<&str> = app.files.iter().map(|file| file.new_name.as_str()).collect();
+    assert_eq!(names, vec!["Pilot_(S02E01).mkv", "Parsed_Title_(S02E02).mkv"]);
+    assert_eq!(app.files[0].episode_title, "Pilot");
+}
+
+#[tokio::test]
+async fn test_previews_refresh_only_once_typing_pauses() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.S01E01.Pilot.mkv"]).await;
+    app.show_config = true;
+    app.config_input_mode = ConfigInputMode::Season;
+    app.season_input.clear();
+
+    for c in "S02".chars() {
+        app.handle_config_input(c);
+    }
+    assert!(app.needs_refresh);
+    assert!(!app.refresh_due(), "a refresh waits for typing to pause");
+
+    app.refresh_debounce = std::time::Duration::ZERO;
+    assert!(app.refresh_due());
+}
+
+#[tokio::test]
+async fn test_preview_names_the_pattern_behind_each_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = scanned_tv_app(dir.path(), &["Show.1x02.Second.mkv"]).await;
+    app.show_config = false;
+    assert_eq!(app.files[0].match_kind, MatchKind::Flexible);
+
+    let screen = |app: &App| {
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 40)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect::<String>()
+    };
+    assert!(screen(&app).contains("Matched: flexible pattern (1x01)"));
+
+    let unmatched = dir.path().join("holiday-video.mkv");
+    std::fs::write(&unmatched, b"video").unwrap();
+    let mut app = App::with_selected_files(vec![unmatched.to_string_lossy().to_string()]);
+    app.show_config = false;
+    app.list_state.select(Some(0));
+    assert!(screen(&app).contains("No pattern matched - name unchanged"));
+}
+
+#[tokio::test]
+async fn test_unmatched_files_are_flagged_instead_of_queued() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut selected = Vec::new();
+    for filename in ["Show.S01E01.Pilot.mkv", "holiday video.mkv"] {
+        let path = dir.path().join(filename);
+        std::fs::write(&path, b"video").unwrap();
+        selected.push(path.to_string_lossy().to_string());
+    }
+
+    let mut app = App::with_selected_files(selected);
+    app.file_type = FileType::TvShow;
+    app.season_input = "S01".to_string();
+    app.create_rename_engine().await.unwrap();
+    app.process_selected_files().await.unwrap();
+    app.show_config = false;
+
+    let unmatched = app.files.iter().position(|file| file.original_name == "holiday video.mkv").unwrap();
+    assert!(app.files[unmatched].is_unmatched(), "{:?}", app.files[unmatched]);
+    assert_eq!(app.files[unmatched].status, ProcessingStatus::Skipped);
+    assert_eq!(app.pending_rename_count(), 1);
+
+    app.list_state.select(Some(unmatched));
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(160, 40)).unwrap();
+    terminal.draw(|f| ui(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("[NO MATCH]"));
+    assert!(screen.contains("Nothing will change"));
+}